@@ -105,7 +105,8 @@ async fn build(self) -> AnyResult<Self::Context> {
         assert!(folders.contains(&Folder {
             kind: Some(FolderKind::Inbox),
             name: "INBOX".into(),
-            desc: "".into()
+            desc: "".into(),
+            ..Default::default()
         }));
     })
     .await
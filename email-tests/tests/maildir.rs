@@ -62,11 +62,13 @@ async fn test_maildir_features() {
             name: "Inbox".into(),
             kind: Some(FolderKind::Inbox),
             desc: tmp_dir.join("Inbox").to_string_lossy().to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Nested".into(),
             kind: None,
             desc: tmp_dir.join("Nested").to_string_lossy().to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Nested/Folder".into(),
@@ -76,16 +78,19 @@ async fn test_maildir_features() {
                 .join("Folder")
                 .to_string_lossy()
                 .to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Trash".into(),
             kind: Some(FolderKind::Trash),
             desc: tmp_dir.join("Trash").to_string_lossy().to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Subdir".into(),
             kind: Some(FolderKind::UserDefined("subdir".into())),
             desc: tmp_dir.join("Subdir").to_string_lossy().to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Subdir/Subdir".into(),
@@ -95,6 +100,7 @@ async fn test_maildir_features() {
                 .join("Subdir")
                 .to_string_lossy()
                 .to_string(),
+            ..Default::default()
         },
     ]);
 
@@ -110,6 +116,7 @@ async fn test_maildir_features() {
             name: "Inbox".into(),
             kind: Some(FolderKind::Inbox),
             desc: tmp_dir.join("Inbox").to_string_lossy().to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Nested/Folder".into(),
@@ -119,16 +126,19 @@ async fn test_maildir_features() {
                 .join("Folder")
                 .to_string_lossy()
                 .to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Trash".into(),
             kind: Some(FolderKind::Trash),
             desc: tmp_dir.join("Trash").to_string_lossy().to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Subdir".into(),
             kind: Some(FolderKind::UserDefined("subdir".into())),
             desc: tmp_dir.join("Subdir").to_string_lossy().to_string(),
+            ..Default::default()
         },
         Folder {
             name: "Subdir/Subdir".into(),
@@ -138,6 +148,7 @@ async fn test_maildir_features() {
                 .join("Subdir")
                 .to_string_lossy()
                 .to_string(),
+            ..Default::default()
         },
     ]);
 
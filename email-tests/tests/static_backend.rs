@@ -91,7 +91,8 @@ async fn send_message(&self, msg: &[u8]) -> AnyResult<()> {
         assert!(folders.contains(&Folder {
             kind: Some(FolderKind::Inbox),
             name: "INBOX".into(),
-            desc: "".into()
+            desc: "".into(),
+            ..Default::default()
         }));
     })
     .await
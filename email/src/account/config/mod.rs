@@ -9,6 +9,8 @@
 #[cfg(feature = "pgp")]
 pub mod pgp;
 
+#[cfg(feature = "watch")]
+use std::time::Duration;
 use std::{
     collections::HashMap,
     env,
@@ -23,8 +25,6 @@
 use mail_builder::headers::address::{Address, EmailAddress};
 use mail_parser::Address::*;
 use mml::MimeInterpreterBuilder;
-#[cfg(feature = "notify")]
-use notify_rust::Notification;
 use process::Command;
 use shellexpand_utils::{shellexpand_path, shellexpand_str, try_shellexpand_path};
 use tracing::debug;
@@ -35,6 +35,8 @@
 use super::sync::config::SyncConfig;
 #[doc(inline)]
 pub use super::{Error, Result};
+#[cfg(all(feature = "watch", feature = "notify"))]
+use crate::watch::notify::WatchNotifier;
 use crate::{
     date::from_mail_parser_to_chrono_datetime,
     email::config::EmailTextPlainFormat,
@@ -42,14 +44,20 @@
     flag::config::FlagConfig,
     folder::{config::FolderConfig, FolderKind, DRAFTS, INBOX, SENT, TRASH},
     message::config::MessageConfig,
+    quota::config::QuotaConfig,
     template::{
         config::TemplateConfig,
         forward::config::{ForwardTemplatePostingStyle, ForwardTemplateSignatureStyle},
         new::config::NewTemplateSignatureStyle,
         reply::config::{ReplyTemplatePostingStyle, ReplyTemplateSignatureStyle},
     },
-    watch::config::WatchHook,
+    watch::{
+        config::WatchHook,
+        hook::{WatchHookEnvelope, WatchHookVars},
+    },
 };
+#[cfg(feature = "watch")]
+use crate::{envelope::watch::EnvelopeEvent, quota::Quota, watch::hook::WatchHookQuota};
 
 pub const DEFAULT_PAGE_SIZE: usize = 10;
 pub const DEFAULT_SIGNATURE_DELIM: &str = "-- \n";
@@ -118,6 +126,9 @@ pub struct AccountConfig {
     /// The message configuration.
     pub template: Option<TemplateConfig>,
 
+    /// The quota configuration.
+    pub quota: Option<QuotaConfig>,
+
     /// The account synchronization configuration.
     #[cfg(feature = "sync")]
     pub sync: Option<SyncConfig>,
@@ -240,85 +251,130 @@ pub async fn exec_any_envelope_hook(&self, envelope: &Envelope) {
         }
     }
 
+    /// Execute the envelope hook matching the given event.
+    ///
+    /// Notifications are batched by the given watcher notifier, if
+    /// any. Otherwise they are sent straight.
+    #[cfg(feature = "watch")]
+    pub async fn exec_envelope_event_hook(
+        &self,
+        event: &EnvelopeEvent,
+        #[cfg(feature = "notify")] notifier: Option<&WatchNotifier>,
+    ) {
+        let hook = self
+            .envelope
+            .as_ref()
+            .and_then(|c| c.watch.as_ref())
+            .and_then(|c| c.find_hook(event.kind()));
+
+        let Some(hook) = hook else {
+            return;
+        };
+
+        let envelope = WatchHookEnvelope::new(event.envelope()).with_folder(event.folder());
+
+        Self::exec_watch_hook_cmd(hook, &envelope).await;
+
+        #[cfg(feature = "notify")]
+        if let Some(notify) = hook.notify.as_ref() {
+            match notifier {
+                Some(notifier) => notifier.notify(self, notify, &envelope).await,
+                None => crate::watch::notify::notify(self, notify, &envelope).await,
+            }
+        }
+
+        Self::exec_watch_hook_callback(hook, &envelope).await;
+    }
+
     /// Execute the given envelope hook.
     pub async fn exec_envelope_hook(&self, hook: &WatchHook, envelope: &Envelope) {
-        let sender = envelope.from.name.as_deref().unwrap_or(&envelope.from.addr);
-        let sender_name = envelope.from.name.as_deref().unwrap_or("unknown");
-        let recipient = envelope.to.name.as_deref().unwrap_or(&envelope.to.addr);
-        let recipient_name = envelope.to.name.as_deref().unwrap_or("unknown");
+        self.exec_watch_hook(hook, &WatchHookEnvelope::new(envelope))
+            .await
+    }
 
+    /// Execute the given watch hook for the given envelope.
+    ///
+    /// The shell command receives the envelope as placeholders, as
+    /// `EMAIL_*` environment variables and as JSON on its standard
+    /// input. Notifications are sent straight, without batching.
+    pub async fn exec_watch_hook(&self, hook: &WatchHook, envelope: &WatchHookEnvelope<'_>) {
+        Self::exec_watch_hook_cmd(hook, envelope).await;
+
+        #[cfg(feature = "notify")]
+        if let Some(notify) = hook.notify.as_ref() {
+            crate::watch::notify::notify(self, notify, envelope).await;
+        }
+
+        Self::exec_watch_hook_callback(hook, envelope).await;
+    }
+
+    /// Execute the callback of the given watch hook, if any, for the
+    /// given envelope.
+    async fn exec_watch_hook_callback(hook: &WatchHook, envelope: &WatchHookEnvelope<'_>) {
+        if let Some(callback) = hook.callback.as_ref() {
+            let res = callback(envelope.envelope()).await;
+            if let Err(_err) = res {
+                debug!("error while executing callback");
+                debug!("{_err:?}");
+            }
+        }
+    }
+
+    /// Execute the shell command of the given watch hook, if any,
+    /// for the given hook variables.
+    async fn exec_watch_hook_cmd(hook: &WatchHook, vars: &impl WatchHookVars) {
         if let Some(cmd) = hook.cmd.as_ref() {
-            let res = cmd
-                .clone()
-                .replace("{id}", &envelope.id)
-                .replace("{subject}", &envelope.subject)
-                .replace("{sender}", sender)
-                .replace("{sender.name}", sender_name)
-                .replace("{sender.address}", &envelope.from.addr)
-                .replace("{recipient}", recipient)
-                .replace("{recipient.name}", recipient_name)
-                .replace("{recipient.address}", &envelope.to.addr)
-                .run()
-                .await;
+            let mut cmd = cmd.clone().with_envs(vars.env_vars());
+            *cmd = vars.replace_quoted(&cmd);
+
+            #[cfg(feature = "watch")]
+            let res = cmd.run_with(vars.to_json()).await;
+            #[cfg(not(feature = "watch"))]
+            let res = cmd.run().await;
 
             if let Err(_err) = res {
                 debug!("error while executing watch command hook");
                 debug!("{_err:?}");
             }
         }
+    }
 
-        #[allow(unused_variables)]
-        let replace = move |fmt: &str, envelope: &Envelope| -> String {
-            fmt.replace("{id}", &envelope.id)
-                .replace("{subject}", &envelope.subject)
-                .replace("{sender}", sender)
-                .replace("{sender.name}", sender_name)
-                .replace("{sender.address}", &envelope.from.addr)
-                .replace("{recipient}", recipient)
-                .replace("{recipient.name}", recipient_name)
-                .replace("{recipient.address}", &envelope.to.addr)
+    /// Get the minimum interval between two quota checks, if a quota
+    /// watch hook is configured.
+    #[cfg(feature = "watch")]
+    pub fn find_quota_watch_interval(&self) -> Option<Duration> {
+        let config = self.quota.as_ref().and_then(|c| c.watch.as_ref())?;
+        config.exceeded.as_ref()?;
+        Some(config.get_interval())
+    }
+
+    /// Execute the quota exceeded hook for each resource whose usage
+    /// crossed the configured threshold since the previous quota.
+    ///
+    /// The hook is executed like envelope hooks, except that the
+    /// callback is not supported.
+    #[cfg(feature = "watch")]
+    pub async fn exec_quota_hook(&self, prev: Option<&Quota>, next: &Quota) {
+        let Some(config) = self.quota.as_ref().and_then(|c| c.watch.as_ref()) else {
+            return;
         };
 
-        #[cfg(all(feature = "notify", target_os = "linux"))]
-        if let Some(notify) = hook.notify.as_ref() {
-            let res = Notification::new()
-                .summary(&replace(&notify.summary, envelope))
-                .body(&replace(&notify.body, envelope))
-                .show_async()
-                .await;
-            if let Err(err) = res {
-                debug!("error while sending system notification");
-                debug!("{err:?}");
-            }
-        }
+        let Some(hook) = config.exceeded.as_ref() else {
+            return;
+        };
 
-        #[cfg(all(feature = "notify", not(target_os = "linux")))]
-        if let Some(notify) = hook.notify.as_ref() {
-            let summary = replace(&notify.summary, &envelope);
-            let body = replace(&notify.body, &envelope);
+        for kind in next.crossed_threshold(prev, config.get_threshold()) {
+            let Some(usage) = next.get(kind) else {
+                continue;
+            };
 
-            let res = tokio::task::spawn_blocking(move || {
-                Notification::new().summary(&summary).body(&body).show()
-            })
-            .await;
-
-            if let Err(err) = res {
-                debug!("cannot send system notification");
-                debug!("{err:?}");
-            } else {
-                let res = res.unwrap();
-                if let Err(err) = res {
-                    debug!("error while sending system notification");
-                    debug!("{err:?}");
-                }
-            }
-        }
+            let quota = WatchHookQuota::new(kind, usage);
 
-        if let Some(callback) = hook.callback.as_ref() {
-            let res = callback(envelope).await;
-            if let Err(_err) = res {
-                debug!("error while executing callback");
-                debug!("{_err:?}");
+            Self::exec_watch_hook_cmd(hook, &quota).await;
+
+            #[cfg(feature = "notify")]
+            if let Some(notify) = hook.notify.as_ref() {
+                crate::watch::notify::notify_vars(notify, &quota).await;
             }
         }
     }
@@ -389,6 +445,15 @@ pub fn get_folder_aliases(&self) -> Option<&HashMap<String, String>> {
         self.folder.as_ref().and_then(|c| c.aliases.as_ref())
     }
 
+    /// Return `true` if only subscribed folders should be listed.
+    pub fn is_folder_list_subscribed_only(&self) -> bool {
+        self.folder
+            .as_ref()
+            .and_then(|c| c.list.as_ref())
+            .and_then(|c| c.subscribed_only)
+            .unwrap_or_default()
+    }
+
     /// Find the folder kind associated to the given folder alias.
     ///
     /// This function is the reverse of [`get_folder_alias`], as it
@@ -0,0 +1,169 @@
+//! # Backend capability
+//!
+//! Module dedicated to backend capabilities introspection. The main
+//! structure of this module is [`BackendCapabilities`], which gathers
+//! the backend features enabled at build time as well as the
+//! capabilities advertised by the server, discovered at check up.
+//!
+//! This is useful for interfaces that want to hide actions that
+//! cannot be performed by the current backend.
+
+use std::collections::BTreeSet;
+
+/// The backend feature kind.
+///
+/// Each variant matches a backend feature that can be enabled or
+/// disabled using the [`super::BackendBuilder`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum BackendFeatureKind {
+    CheckUp,
+    AddFolder,
+    ListFolders,
+    ListFoldersWithStatus,
+    GetFolderStatus,
+    ExpungeFolder,
+    PurgeFolder,
+    RenameFolder,
+    SubscribeFolder,
+    UnsubscribeFolder,
+    GetFolderAnnotations,
+    SetFolderAnnotation,
+    DeleteFolder,
+    GetEnvelope,
+    ListEnvelopes,
+    ThreadEnvelopes,
+    WatchEnvelopes,
+    AddFlags,
+    SetFlags,
+    RemoveFlags,
+    AddMessage,
+    SendMessage,
+    PeekMessages,
+    GetMessages,
+    CopyMessages,
+    MoveMessages,
+    DeleteMessages,
+    RemoveMessages,
+    GetQuota,
+}
+
+/// The server capability.
+///
+/// Server capabilities are advertised by remote servers, and depend
+/// on the server implementation and configuration.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ServerCapability {
+    /// The IMAP server can sort envelopes (RFC 5256).
+    ImapSort,
+
+    /// The IMAP server can thread envelopes (RFC 5256).
+    ImapThread,
+
+    /// The IMAP server can move messages (RFC 6851).
+    ImapMove,
+
+    /// The IMAP server can notify changes in real time (RFC 2177).
+    ImapIdle,
+
+    /// The IMAP server supports conditional store (RFC 7162).
+    ImapCondstore,
+
+    /// Any other capability advertised by the IMAP server.
+    Imap(String),
+
+    /// Extension advertised by the SMTP server, as an `EHLO`
+    /// keyword.
+    Smtp(String),
+}
+
+impl ServerCapability {
+    /// Build a server capability from the given IMAP capability, as
+    /// advertised by the server.
+    pub fn from_imap(capability: impl AsRef<str>) -> Self {
+        let capability = capability.as_ref().to_ascii_uppercase();
+
+        match capability.as_str() {
+            "SORT" => Self::ImapSort,
+            "MOVE" => Self::ImapMove,
+            "IDLE" => Self::ImapIdle,
+            "CONDSTORE" => Self::ImapCondstore,
+            capability if capability.starts_with("THREAD=") => Self::ImapThread,
+            _ => Self::Imap(capability),
+        }
+    }
+}
+
+/// The set of capabilities advertised by a server.
+pub type ServerCapabilities = BTreeSet<ServerCapability>;
+
+/// The backend capabilities.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct BackendCapabilities {
+    /// The backend features enabled for this backend.
+    pub features: BTreeSet<BackendFeatureKind>,
+
+    /// The capabilities advertised by the server(s) behind this
+    /// backend.
+    pub server: ServerCapabilities,
+}
+
+impl BackendCapabilities {
+    /// Return `true` if the given backend feature is enabled.
+    pub fn has_feature(&self, feature: BackendFeatureKind) -> bool {
+        self.features.contains(&feature)
+    }
+
+    /// Return `true` if the given capability is advertised by the
+    /// server.
+    pub fn has_server_capability(&self, capability: &ServerCapability) -> bool {
+        self.server.contains(capability)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServerCapability;
+
+    #[test]
+    fn from_imap() {
+        assert_eq!(
+            ServerCapability::from_imap("SORT"),
+            ServerCapability::ImapSort
+        );
+        assert_eq!(
+            ServerCapability::from_imap("thread=references"),
+            ServerCapability::ImapThread,
+        );
+        assert_eq!(
+            ServerCapability::from_imap("Move"),
+            ServerCapability::ImapMove
+        );
+        assert_eq!(
+            ServerCapability::from_imap("IDLE"),
+            ServerCapability::ImapIdle
+        );
+        assert_eq!(
+            ServerCapability::from_imap("CONDSTORE"),
+            ServerCapability::ImapCondstore,
+        );
+        assert_eq!(
+            ServerCapability::from_imap("uidplus"),
+            ServerCapability::Imap("UIDPLUS".into()),
+        );
+    }
+}
@@ -17,13 +17,21 @@
     envelope::{get::GetEnvelope, list::ListEnvelopes},
     flag::{add::AddFlags, remove::RemoveFlags, set::SetFlags},
     folder::{
-        add::AddFolder, delete::DeleteFolder, expunge::ExpungeFolder, list::ListFolders,
+        add::AddFolder,
+        annotation::{GetFolderAnnotations, SetFolderAnnotation},
+        delete::DeleteFolder,
+        expunge::ExpungeFolder,
+        list::ListFolders,
         purge::PurgeFolder,
+        rename::RenameFolder,
+        status::{GetFolderStatus, ListFoldersWithStatus},
+        subscribe::{SubscribeFolder, UnsubscribeFolder},
     },
     message::{
         add::AddMessage, copy::CopyMessages, delete::DeleteMessages, get::GetMessages,
         peek::PeekMessages, r#move::MoveMessages, remove::RemoveMessages, send::SendMessage,
     },
+    quota::GetQuota,
     AnyResult,
 };
 
@@ -79,8 +87,15 @@ async fn configure(&mut self) -> AnyResult<()> {
 
     feature!(AddFolder);
     feature!(ListFolders);
+    feature!(ListFoldersWithStatus);
+    feature!(GetFolderStatus);
     feature!(ExpungeFolder);
     feature!(PurgeFolder);
+    feature!(RenameFolder);
+    feature!(SubscribeFolder);
+    feature!(UnsubscribeFolder);
+    feature!(GetFolderAnnotations);
+    feature!(SetFolderAnnotation);
     feature!(DeleteFolder);
     feature!(GetEnvelope);
     feature!(ListEnvelopes);
@@ -99,6 +114,7 @@ async fn configure(&mut self) -> AnyResult<()> {
     feature!(MoveMessages);
     feature!(DeleteMessages);
     feature!(RemoveMessages);
+    feature!(GetQuota);
 
     /// Build the final context used by the backend.
     async fn build(self) -> AnyResult<Self::Context>;
@@ -161,6 +177,7 @@ fn try_to_sync_cache_builder(
             flag: account_config.flag.clone(),
             message: account_config.message.clone(),
             template: account_config.template.clone(),
+            quota: account_config.quota.clone(),
             sync: None,
             #[cfg(feature = "pgp")]
             pgp: account_config.pgp.clone(),
@@ -14,12 +14,26 @@ pub enum Error {
     AddFolderNotAvailableError,
     #[error("cannot list folders: feature not available, or backend configuration for this functionality is not set")]
     ListFoldersNotAvailableError,
+    #[error("cannot list folders with status: feature not available, or backend configuration for this functionality is not set")]
+    ListFoldersWithStatusNotAvailableError,
+    #[error("cannot get folder status: feature not available, or backend configuration for this functionality is not set")]
+    GetFolderStatusNotAvailableError,
     #[error("cannot expunge folder: feature not available, or backend configuration for this functionality is not set")]
     ExpungeFolderNotAvailableError,
     #[error("cannot purge folder: feature not available, or backend configuration for this functionality is not set")]
     PurgeFolderNotAvailableError,
     #[error("cannot delete folder: feature not available, or backend configuration for this functionality is not set")]
     DeleteFolderNotAvailableError,
+    #[error("cannot rename folder: feature not available, or backend configuration for this functionality is not set")]
+    RenameFolderNotAvailableError,
+    #[error("cannot subscribe to folder: feature not available, or backend configuration for this functionality is not set")]
+    SubscribeFolderNotAvailableError,
+    #[error("cannot unsubscribe from folder: feature not available, or backend configuration for this functionality is not set")]
+    UnsubscribeFolderNotAvailableError,
+    #[error("cannot get folder annotations: feature not available, or backend configuration for this functionality is not set")]
+    GetFolderAnnotationsNotAvailableError,
+    #[error("cannot set folder annotation: feature not available, or backend configuration for this functionality is not set")]
+    SetFolderAnnotationNotAvailableError,
     #[error("cannot list envelopes: feature not available, or backend configuration for this functionality is not set")]
     ListEnvelopesNotAvailableError,
     #[error("cannot thread envelopes: feature not available, or backend configuration for this functionality is not set")]
@@ -52,6 +66,19 @@ pub enum Error {
     DeleteMessagesNotAvailableError,
     #[error("cannot remove messages: feature not available, or backend configuration for this functionality is not set")]
     RemoveMessagesNotAvailableError,
+    #[error("cannot get quota: feature not available, or backend configuration for this functionality is not set")]
+    GetQuotaNotAvailableError,
+
+    #[error("cannot parse unified folder or identifier {0}: missing account namespace")]
+    ParseUnifiedNamespaceError(String),
+    #[error("cannot find unified account {0}, or feature not available for this account")]
+    GetUnifiedAccountError(String),
+    #[error("cannot use folder from account {1} with identifiers from account {0}")]
+    UnifiedAccountMismatchError(String, String),
+    #[error("cannot {0}: operation not supported by the unified inbox")]
+    UnifiedInboxNotSupportedError(&'static str),
+    #[error("cannot get messages from unified account {0}: expected {1} messages, got {2}")]
+    UnifiedMessagesCountMismatchError(String, usize, usize),
 }
 
 impl AnyError for Error {
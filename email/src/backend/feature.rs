@@ -8,7 +8,7 @@
 
 use async_trait::async_trait;
 
-use super::{context::BackendContext, AnyResult};
+use super::{capability::ServerCapabilities, context::BackendContext, AnyResult};
 
 /// Backend builder feature for checking up configuration and context
 /// integrity.
@@ -20,6 +20,14 @@ pub trait CheckUp: Send + Sync {
     async fn check_up(&self) -> AnyResult<()> {
         Ok(())
     }
+
+    /// Discover the capabilities advertised by the server.
+    ///
+    /// Backends not relying on a server do not advertise any
+    /// capability.
+    async fn server_capabilities(&self) -> AnyResult<ServerCapabilities> {
+        Ok(ServerCapabilities::default())
+    }
 }
 
 /// The backend feature.
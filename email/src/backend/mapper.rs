@@ -19,13 +19,21 @@
     envelope::{get::GetEnvelope, list::ListEnvelopes},
     flag::{add::AddFlags, remove::RemoveFlags, set::SetFlags},
     folder::{
-        add::AddFolder, delete::DeleteFolder, expunge::ExpungeFolder, list::ListFolders,
+        add::AddFolder,
+        annotation::{GetFolderAnnotations, SetFolderAnnotation},
+        delete::DeleteFolder,
+        expunge::ExpungeFolder,
+        list::ListFolders,
         purge::PurgeFolder,
+        rename::RenameFolder,
+        status::{GetFolderStatus, ListFoldersWithStatus},
+        subscribe::{SubscribeFolder, UnsubscribeFolder},
     },
     message::{
         add::AddMessage, copy::CopyMessages, delete::DeleteMessages, get::GetMessages,
         peek::PeekMessages, r#move::MoveMessages, remove::RemoveMessages, send::SendMessage,
     },
+    quota::GetQuota,
 };
 
 /// Macro for defining some [`BackendContextBuilder`] feature mapper.
@@ -68,8 +76,15 @@ fn map_feature<T: ?Sized + 'static>(
 
     some_feature_mapper!(AddFolder);
     some_feature_mapper!(ListFolders);
+    some_feature_mapper!(ListFoldersWithStatus);
+    some_feature_mapper!(GetFolderStatus);
     some_feature_mapper!(ExpungeFolder);
     some_feature_mapper!(PurgeFolder);
+    some_feature_mapper!(RenameFolder);
+    some_feature_mapper!(SubscribeFolder);
+    some_feature_mapper!(UnsubscribeFolder);
+    some_feature_mapper!(GetFolderAnnotations);
+    some_feature_mapper!(SetFolderAnnotation);
     some_feature_mapper!(DeleteFolder);
     some_feature_mapper!(GetEnvelope);
     some_feature_mapper!(ListEnvelopes);
@@ -88,6 +103,7 @@ fn map_feature<T: ?Sized + 'static>(
     some_feature_mapper!(MoveMessages);
     some_feature_mapper!(DeleteMessages);
     some_feature_mapper!(RemoveMessages);
+    some_feature_mapper!(GetQuota);
 }
 
 /// Automatically implement [`SomeBackendContextBuilderMapper`].
@@ -132,8 +148,15 @@ fn map_feature<T: ?Sized + 'static>(
 
     feature_mapper!(AddFolder);
     feature_mapper!(ListFolders);
+    feature_mapper!(ListFoldersWithStatus);
+    feature_mapper!(GetFolderStatus);
     feature_mapper!(ExpungeFolder);
     feature_mapper!(PurgeFolder);
+    feature_mapper!(RenameFolder);
+    feature_mapper!(SubscribeFolder);
+    feature_mapper!(UnsubscribeFolder);
+    feature_mapper!(GetFolderAnnotations);
+    feature_mapper!(SetFolderAnnotation);
     feature_mapper!(DeleteFolder);
     feature_mapper!(GetEnvelope);
     feature_mapper!(ListEnvelopes);
@@ -152,6 +175,7 @@ fn map_feature<T: ?Sized + 'static>(
     feature_mapper!(MoveMessages);
     feature_mapper!(DeleteMessages);
     feature_mapper!(RemoveMessages);
+    feature_mapper!(GetQuota);
 }
 
 /// Automatically implement [`BackendContextBuilderMapper`].
@@ -39,19 +39,23 @@
 //!
 //! See a full example at `../../tests/static_backend.rs`.
 
+pub mod capability;
 pub mod context;
 mod error;
 pub mod feature;
 pub mod mapper;
+pub mod unified;
 pub mod macros {
     pub use email_macros::BackendContext;
 }
 
 #[cfg(feature = "sync")]
 use std::hash::DefaultHasher;
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use once_cell::sync::OnceCell;
 use paste::paste;
 #[cfg(feature = "watch")]
 use tokio::sync::oneshot::{Receiver, Sender};
@@ -59,11 +63,12 @@ pub mod macros {
 #[doc(inline)]
 pub use self::error::{Error, Result};
 use self::{
+    capability::{BackendCapabilities, BackendFeatureKind, ServerCapabilities},
     context::{BackendContext, BackendContextBuilder},
     feature::{BackendFeature, BackendFeatureSource, CheckUp},
 };
 #[cfg(feature = "watch")]
-use crate::envelope::watch::WatchEnvelopes;
+use crate::envelope::watch::{EnvelopeEventSender, WatchEnvelopes};
 #[cfg(feature = "thread")]
 use crate::envelope::{thread::ThreadEnvelopes, ThreadedEnvelopes};
 #[cfg(feature = "sync")]
@@ -77,14 +82,23 @@ pub mod macros {
     },
     flag::{add::AddFlags, remove::RemoveFlags, set::SetFlags, Flags},
     folder::{
-        add::AddFolder, delete::DeleteFolder, expunge::ExpungeFolder, list::ListFolders,
-        purge::PurgeFolder, Folders,
+        add::AddFolder,
+        annotation::{FolderAnnotations, GetFolderAnnotations, SetFolderAnnotation},
+        delete::DeleteFolder,
+        expunge::ExpungeFolder,
+        list::ListFolders,
+        purge::PurgeFolder,
+        rename::RenameFolder,
+        status::{FolderStatus, FolderWithStatus, GetFolderStatus, ListFoldersWithStatus},
+        subscribe::{SubscribeFolder, UnsubscribeFolder},
+        Folders,
     },
     message::{
         add::AddMessage, copy::CopyMessages, delete::DeleteMessages, get::GetMessages,
         peek::PeekMessages, r#move::MoveMessages, remove::RemoveMessages, send::SendMessage,
         Messages,
     },
+    quota::{GetQuota, Quota},
     AnyResult,
 };
 
@@ -105,14 +119,31 @@ pub struct Backend<C>
     /// The backend context.
     pub context: Arc<C>,
 
+    /// The check up backend feature.
+    pub check_up: Option<BackendFeature<C, dyn CheckUp>>,
+
     /// The add folder backend feature.
     pub add_folder: Option<BackendFeature<C, dyn AddFolder>>,
     /// The list folders backend feature.
     pub list_folders: Option<BackendFeature<C, dyn ListFolders>>,
+    /// The list folders with status backend feature.
+    pub list_folders_with_status: Option<BackendFeature<C, dyn ListFoldersWithStatus>>,
+    /// The get folder status backend feature.
+    pub get_folder_status: Option<BackendFeature<C, dyn GetFolderStatus>>,
     /// The expunge folder backend feature.
     pub expunge_folder: Option<BackendFeature<C, dyn ExpungeFolder>>,
     /// The purge folder backend feature.
     pub purge_folder: Option<BackendFeature<C, dyn PurgeFolder>>,
+    /// The rename folder backend feature.
+    pub rename_folder: Option<BackendFeature<C, dyn RenameFolder>>,
+    /// The subscribe folder backend feature.
+    pub subscribe_folder: Option<BackendFeature<C, dyn SubscribeFolder>>,
+    /// The unsubscribe folder backend feature.
+    pub unsubscribe_folder: Option<BackendFeature<C, dyn UnsubscribeFolder>>,
+    /// The get folder annotations backend feature.
+    pub get_folder_annotations: Option<BackendFeature<C, dyn GetFolderAnnotations>>,
+    /// The set folder annotation backend feature.
+    pub set_folder_annotation: Option<BackendFeature<C, dyn SetFolderAnnotation>>,
     /// The delete folder backend feature.
     pub delete_folder: Option<BackendFeature<C, dyn DeleteFolder>>,
 
@@ -150,6 +181,12 @@ pub struct Backend<C>
     pub delete_messages: Option<BackendFeature<C, dyn DeleteMessages>>,
     /// The delete messages backend feature.
     pub remove_messages: Option<BackendFeature<C, dyn RemoveMessages>>,
+    /// The get quota backend feature.
+    pub get_quota: Option<BackendFeature<C, dyn GetQuota>>,
+
+    /// The server capabilities, discovered once by
+    /// [`Backend::capabilities`].
+    server_capabilities: OnceCell<ServerCapabilities>,
 }
 
 impl<C: BackendContext> HasAccountConfig for Backend<C> {
@@ -158,6 +195,87 @@ fn account_config(&self) -> &AccountConfig {
     }
 }
 
+impl<C: BackendContext> Backend<C> {
+    /// Return the capabilities of the backend.
+    ///
+    /// Capabilities are composed of the enabled backend features and
+    /// the server capabilities, discovered using the check up
+    /// feature. Server capabilities are discovered once, then cached
+    /// for the lifetime of the backend.
+    pub async fn capabilities(&self) -> AnyResult<BackendCapabilities> {
+        let mut features = BTreeSet::new();
+
+        macro_rules! add_feature {
+            ($field:ident, $kind:ident) => {
+                if self.$field.is_some() {
+                    features.insert(BackendFeatureKind::$kind);
+                }
+            };
+        }
+
+        add_feature!(check_up, CheckUp);
+        add_feature!(add_folder, AddFolder);
+        add_feature!(list_folders, ListFolders);
+        add_feature!(list_folders_with_status, ListFoldersWithStatus);
+        add_feature!(get_folder_status, GetFolderStatus);
+        add_feature!(expunge_folder, ExpungeFolder);
+        add_feature!(purge_folder, PurgeFolder);
+        add_feature!(rename_folder, RenameFolder);
+        add_feature!(subscribe_folder, SubscribeFolder);
+        add_feature!(unsubscribe_folder, UnsubscribeFolder);
+        add_feature!(get_folder_annotations, GetFolderAnnotations);
+        add_feature!(set_folder_annotation, SetFolderAnnotation);
+        add_feature!(delete_folder, DeleteFolder);
+        add_feature!(get_envelope, GetEnvelope);
+        add_feature!(list_envelopes, ListEnvelopes);
+        #[cfg(feature = "thread")]
+        add_feature!(thread_envelopes, ThreadEnvelopes);
+        #[cfg(feature = "watch")]
+        add_feature!(watch_envelopes, WatchEnvelopes);
+        add_feature!(add_flags, AddFlags);
+        add_feature!(set_flags, SetFlags);
+        add_feature!(remove_flags, RemoveFlags);
+        add_feature!(add_message, AddMessage);
+        add_feature!(send_message, SendMessage);
+        add_feature!(peek_messages, PeekMessages);
+        add_feature!(get_messages, GetMessages);
+        add_feature!(copy_messages, CopyMessages);
+        add_feature!(move_messages, MoveMessages);
+        add_feature!(delete_messages, DeleteMessages);
+        add_feature!(remove_messages, RemoveMessages);
+        add_feature!(get_quota, GetQuota);
+
+        let server = match self.server_capabilities.get() {
+            Some(server) => server.clone(),
+            None => {
+                let server = self.server_capabilities().await?;
+                // a concurrent call may have discovered them already
+                let _ = self.server_capabilities.set(server.clone());
+                server
+            }
+        };
+
+        Ok(BackendCapabilities { features, server })
+    }
+}
+
+#[async_trait]
+impl<C: BackendContext> CheckUp for Backend<C> {
+    async fn check_up(&self) -> AnyResult<()> {
+        match self.check_up.as_ref().and_then(|f| f(&self.context)) {
+            Some(f) => f.check_up().await,
+            None => Ok(()),
+        }
+    }
+
+    async fn server_capabilities(&self) -> AnyResult<ServerCapabilities> {
+        match self.check_up.as_ref().and_then(|f| f(&self.context)) {
+            Some(f) => f.server_capabilities().await,
+            None => Ok(ServerCapabilities::default()),
+        }
+    }
+}
+
 #[async_trait]
 impl<C: BackendContext> AddFolder for Backend<C> {
     async fn add_folder(&self, folder: &str) -> AnyResult<()> {
@@ -180,6 +298,39 @@ async fn list_folders(&self) -> AnyResult<Folders> {
             .list_folders()
             .await
     }
+
+    async fn list_folders_with_subscriptions(&self) -> AnyResult<Folders> {
+        self.list_folders
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::ListFoldersNotAvailableError)?
+            .list_folders_with_subscriptions()
+            .await
+    }
+}
+
+#[async_trait]
+impl<C: BackendContext> ListFoldersWithStatus for Backend<C> {
+    async fn list_folders_with_status(&self) -> AnyResult<Vec<FolderWithStatus>> {
+        self.list_folders_with_status
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::ListFoldersWithStatusNotAvailableError)?
+            .list_folders_with_status()
+            .await
+    }
+}
+
+#[async_trait]
+impl<C: BackendContext> GetFolderStatus for Backend<C> {
+    async fn get_folder_status(&self, folder: &str) -> AnyResult<FolderStatus> {
+        self.get_folder_status
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::GetFolderStatusNotAvailableError)?
+            .get_folder_status(folder)
+            .await
+    }
 }
 
 #[async_trait]
@@ -206,6 +357,71 @@ async fn purge_folder(&self, folder: &str) -> AnyResult<()> {
     }
 }
 
+#[async_trait]
+impl<C: BackendContext> RenameFolder for Backend<C> {
+    async fn rename_folder(&self, folder: &str, new_folder: &str) -> AnyResult<()> {
+        self.rename_folder
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::RenameFolderNotAvailableError)?
+            .rename_folder(folder, new_folder)
+            .await
+    }
+}
+
+#[async_trait]
+impl<C: BackendContext> SubscribeFolder for Backend<C> {
+    async fn subscribe_folder(&self, folder: &str) -> AnyResult<()> {
+        self.subscribe_folder
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::SubscribeFolderNotAvailableError)?
+            .subscribe_folder(folder)
+            .await
+    }
+}
+
+#[async_trait]
+impl<C: BackendContext> UnsubscribeFolder for Backend<C> {
+    async fn unsubscribe_folder(&self, folder: &str) -> AnyResult<()> {
+        self.unsubscribe_folder
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::UnsubscribeFolderNotAvailableError)?
+            .unsubscribe_folder(folder)
+            .await
+    }
+}
+
+#[async_trait]
+impl<C: BackendContext> GetFolderAnnotations for Backend<C> {
+    async fn get_folder_annotations(&self, folder: &str) -> AnyResult<FolderAnnotations> {
+        self.get_folder_annotations
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::GetFolderAnnotationsNotAvailableError)?
+            .get_folder_annotations(folder)
+            .await
+    }
+}
+
+#[async_trait]
+impl<C: BackendContext> SetFolderAnnotation for Backend<C> {
+    async fn set_folder_annotation(
+        &self,
+        folder: &str,
+        key: &str,
+        val: Option<&str>,
+    ) -> AnyResult<()> {
+        self.set_folder_annotation
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::SetFolderAnnotationNotAvailableError)?
+            .set_folder_annotation(folder, key, val)
+            .await
+    }
+}
+
 #[async_trait]
 impl<C: BackendContext> DeleteFolder for Backend<C> {
     async fn delete_folder(&self, folder: &str) -> AnyResult<()> {
@@ -293,6 +509,35 @@ async fn watch_envelopes(
             .watch_envelopes(folder, wait_for_shutdown_request, shutdown)
             .await
     }
+
+    async fn watch_folders_envelopes(
+        &self,
+        folders: &[String],
+        wait_for_shutdown_request: Receiver<()>,
+        shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        self.watch_envelopes
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::WatchEnvelopesNotAvailableError)?
+            .watch_folders_envelopes(folders, wait_for_shutdown_request, shutdown)
+            .await
+    }
+
+    async fn watch_envelopes_events(
+        &self,
+        folders: &[String],
+        events: EnvelopeEventSender,
+        wait_for_shutdown_request: Receiver<()>,
+        shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        self.watch_envelopes
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::WatchEnvelopesNotAvailableError)?
+            .watch_envelopes_events(folders, events, wait_for_shutdown_request, shutdown)
+            .await
+    }
 }
 
 #[async_trait]
@@ -346,6 +591,21 @@ async fn add_message_with_flags(
             .add_message_with_flags(folder, msg, flags)
             .await
     }
+
+    async fn add_message_with_flags_and_date(
+        &self,
+        folder: &str,
+        msg: &[u8],
+        flags: &Flags,
+        date: &DateTime<FixedOffset>,
+    ) -> AnyResult<()> {
+        self.add_message
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::AddMessageNotAvailableError)?
+            .add_message_with_flags_and_date(folder, msg, flags, date)
+            .await
+    }
 }
 
 #[async_trait]
@@ -370,6 +630,15 @@ async fn peek_messages(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
             .peek_messages(folder, id)
             .await
     }
+
+    async fn peek_messages_headers(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
+        self.peek_messages
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::PeekMessagesNotAvailableError)?
+            .peek_messages_headers(folder, id)
+            .await
+    }
 }
 
 #[async_trait]
@@ -432,6 +701,18 @@ async fn remove_messages(&self, folder: &str, id: &Id) -> AnyResult<()> {
     }
 }
 
+#[async_trait]
+impl<C: BackendContext> GetQuota for Backend<C> {
+    async fn get_quota(&self, folder: &str) -> AnyResult<Quota> {
+        self.get_quota
+            .as_ref()
+            .and_then(|feature| feature(&self.context))
+            .ok_or(Error::GetQuotaNotAvailableError)?
+            .get_quota(folder)
+            .await
+    }
+}
+
 /// Macro for defining [`BackendBuilder`] feature getter and setters.
 macro_rules! feature_accessors {
     ($feat:ty) => {
@@ -512,10 +793,24 @@ pub struct BackendBuilder<CB>
     pub add_folder: BackendFeatureSource<CB::Context, dyn AddFolder>,
     /// The list folders backend builder feature.
     pub list_folders: BackendFeatureSource<CB::Context, dyn ListFolders>,
+    /// The list folders with status backend builder feature.
+    pub list_folders_with_status: BackendFeatureSource<CB::Context, dyn ListFoldersWithStatus>,
+    /// The get folder status backend builder feature.
+    pub get_folder_status: BackendFeatureSource<CB::Context, dyn GetFolderStatus>,
     /// The expunge folder backend builder feature.
     pub expunge_folder: BackendFeatureSource<CB::Context, dyn ExpungeFolder>,
     /// The purge folder backend builder feature.
     pub purge_folder: BackendFeatureSource<CB::Context, dyn PurgeFolder>,
+    /// The rename folder backend builder feature.
+    pub rename_folder: BackendFeatureSource<CB::Context, dyn RenameFolder>,
+    /// The subscribe folder backend builder feature.
+    pub subscribe_folder: BackendFeatureSource<CB::Context, dyn SubscribeFolder>,
+    /// The unsubscribe folder backend builder feature.
+    pub unsubscribe_folder: BackendFeatureSource<CB::Context, dyn UnsubscribeFolder>,
+    /// The get folder annotations backend builder feature.
+    pub get_folder_annotations: BackendFeatureSource<CB::Context, dyn GetFolderAnnotations>,
+    /// The set folder annotation backend builder feature.
+    pub set_folder_annotation: BackendFeatureSource<CB::Context, dyn SetFolderAnnotation>,
     /// The delete folder backend builder feature.
     pub delete_folder: BackendFeatureSource<CB::Context, dyn DeleteFolder>,
 
@@ -553,6 +848,8 @@ pub struct BackendBuilder<CB>
     pub delete_messages: BackendFeatureSource<CB::Context, dyn DeleteMessages>,
     /// The remove messages backend builder feature.
     pub remove_messages: BackendFeatureSource<CB::Context, dyn RemoveMessages>,
+    /// The get quota backend builder feature.
+    pub get_quota: BackendFeatureSource<CB::Context, dyn GetQuota>,
 }
 
 impl<CB> BackendBuilder<CB>
@@ -562,8 +859,15 @@ impl<CB> BackendBuilder<CB>
     feature_accessors!(CheckUp);
     feature_accessors!(AddFolder);
     feature_accessors!(ListFolders);
+    feature_accessors!(ListFoldersWithStatus);
+    feature_accessors!(GetFolderStatus);
     feature_accessors!(ExpungeFolder);
     feature_accessors!(PurgeFolder);
+    feature_accessors!(RenameFolder);
+    feature_accessors!(SubscribeFolder);
+    feature_accessors!(UnsubscribeFolder);
+    feature_accessors!(GetFolderAnnotations);
+    feature_accessors!(SetFolderAnnotation);
     feature_accessors!(DeleteFolder);
     feature_accessors!(GetEnvelope);
     feature_accessors!(ListEnvelopes);
@@ -582,6 +886,7 @@ impl<CB> BackendBuilder<CB>
     feature_accessors!(MoveMessages);
     feature_accessors!(DeleteMessages);
     feature_accessors!(RemoveMessages);
+    feature_accessors!(GetQuota);
 
     /// Create a new backend builder using the given backend context
     /// builder.
@@ -596,8 +901,15 @@ pub fn new(account_config: Arc<AccountConfig>, ctx_builder: CB) -> Self {
 
             add_folder: BackendFeatureSource::Context,
             list_folders: BackendFeatureSource::Context,
+            list_folders_with_status: BackendFeatureSource::Context,
+            get_folder_status: BackendFeatureSource::Context,
             expunge_folder: BackendFeatureSource::Context,
             purge_folder: BackendFeatureSource::Context,
+            rename_folder: BackendFeatureSource::Context,
+            subscribe_folder: BackendFeatureSource::Context,
+            unsubscribe_folder: BackendFeatureSource::Context,
+            get_folder_annotations: BackendFeatureSource::Context,
+            set_folder_annotation: BackendFeatureSource::Context,
             delete_folder: BackendFeatureSource::Context,
 
             get_envelope: BackendFeatureSource::Context,
@@ -619,6 +931,7 @@ pub fn new(account_config: Arc<AccountConfig>, ctx_builder: CB) -> Self {
             move_messages: BackendFeatureSource::Context,
             delete_messages: BackendFeatureSource::Context,
             remove_messages: BackendFeatureSource::Context,
+            get_quota: BackendFeatureSource::Context,
         }
     }
 
@@ -637,10 +950,19 @@ pub async fn check_up(self) -> AnyResult<()> {
     }
 
     pub async fn build(self) -> AnyResult<Backend<CB::Context>> {
+        let check_up = self.get_check_up();
+
         let add_folder = self.get_add_folder();
         let list_folders = self.get_list_folders();
+        let list_folders_with_status = self.get_list_folders_with_status();
+        let get_folder_status = self.get_get_folder_status();
         let expunge_folder = self.get_expunge_folder();
         let purge_folder = self.get_purge_folder();
+        let rename_folder = self.get_rename_folder();
+        let subscribe_folder = self.get_subscribe_folder();
+        let unsubscribe_folder = self.get_unsubscribe_folder();
+        let get_folder_annotations = self.get_get_folder_annotations();
+        let set_folder_annotation = self.get_set_folder_annotation();
         let delete_folder = self.get_delete_folder();
 
         let get_envelope = self.get_get_envelope();
@@ -662,15 +984,25 @@ pub async fn build(self) -> AnyResult<Backend<CB::Context>> {
         let move_messages = self.get_move_messages();
         let delete_messages = self.get_delete_messages();
         let remove_messages = self.get_remove_messages();
+        let get_quota = self.get_get_quota();
 
         Ok(Backend {
             account_config: self.account_config,
             context: Arc::new(self.ctx_builder.build().await?),
 
+            check_up,
+
             add_folder,
             list_folders,
+            list_folders_with_status,
+            get_folder_status,
             expunge_folder,
             purge_folder,
+            rename_folder,
+            subscribe_folder,
+            unsubscribe_folder,
+            get_folder_annotations,
+            set_folder_annotation,
             delete_folder,
 
             get_envelope,
@@ -692,6 +1024,9 @@ pub async fn build(self) -> AnyResult<Backend<CB::Context>> {
             move_messages,
             delete_messages,
             remove_messages,
+            get_quota,
+
+            server_capabilities: OnceCell::new(),
         })
     }
 }
@@ -710,8 +1045,15 @@ fn clone(&self) -> Self {
 
             add_folder: self.add_folder.clone(),
             list_folders: self.list_folders.clone(),
+            list_folders_with_status: self.list_folders_with_status.clone(),
+            get_folder_status: self.get_folder_status.clone(),
             expunge_folder: self.expunge_folder.clone(),
             purge_folder: self.purge_folder.clone(),
+            rename_folder: self.rename_folder.clone(),
+            subscribe_folder: self.subscribe_folder.clone(),
+            unsubscribe_folder: self.unsubscribe_folder.clone(),
+            get_folder_annotations: self.get_folder_annotations.clone(),
+            set_folder_annotation: self.set_folder_annotation.clone(),
             delete_folder: self.delete_folder.clone(),
 
             get_envelope: self.get_envelope.clone(),
@@ -733,6 +1075,7 @@ fn clone(&self) -> Self {
             move_messages: self.move_messages.clone(),
             delete_messages: self.delete_messages.clone(),
             remove_messages: self.remove_messages.clone(),
+            get_quota: self.get_quota.clone(),
         }
     }
 }
@@ -0,0 +1,1144 @@
+//! # Unified backend
+//!
+//! Module dedicated to the unified backend. The main structure of
+//! this module is [`UnifiedContextBuilder`], a composite
+//! [`BackendContextBuilder`] gathering multiple accounts behind a
+//! single backend.
+//!
+//! Folders and envelope identifiers are namespaced by account name,
+//! using [`UNIFIED_SEPARATOR`]: the folder `INBOX` of the account
+//! `work` becomes `work:INBOX`, and the envelope `42` of the same
+//! account becomes `work:42`. The virtual folder [`UNIFIED_INBOX`]
+//! merges the inboxes of all accounts, sorted according to the
+//! search query sorters. Mutating features are routed to the account
+//! owning the given identifiers.
+//!
+//! Features reading from all accounts at once skip the accounts
+//! failing, unless all of them fail.
+//!
+//! ```rust,ignore
+//! let ctx_builder = UnifiedContextBuilder::new()
+//!     .with_account("work", work_ctx_builder)
+//!     .with_account("perso", perso_ctx_builder);
+//!
+//! let backend = BackendBuilder::new(account_config, ctx_builder).build().await?;
+//! let envelopes = backend.list_envelopes(UNIFIED_INBOX, Default::default()).await?;
+//! ```
+//!
+//! All accounts share the same context builder type. Accounts of
+//! different kinds can be unified using a dynamic context builder
+//! (see `../../tests/dynamic_backend.rs`).
+
+#[cfg(feature = "thread")]
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use futures::future::{join_all, try_join_all};
+use paste::paste;
+#[cfg(feature = "thread")]
+use petgraph::graphmap::DiGraphMap;
+use tracing::debug;
+
+use super::{
+    capability::ServerCapabilities,
+    context::{BackendContext, BackendContextBuilder},
+    feature::{BackendFeature, CheckUp},
+    Error, Result,
+};
+#[cfg(feature = "thread")]
+use crate::envelope::{thread::ThreadEnvelopes, ThreadedEnvelope, ThreadedEnvelopes};
+use crate::{
+    envelope::{
+        get::GetEnvelope,
+        list::{ListEnvelopes, ListEnvelopesOptions},
+        Envelope, Envelopes, Id, SingleId,
+    },
+    flag::{add::AddFlags, remove::RemoveFlags, set::SetFlags, Flags},
+    folder::{
+        add::AddFolder,
+        annotation::{FolderAnnotations, GetFolderAnnotations, SetFolderAnnotation},
+        delete::DeleteFolder,
+        expunge::ExpungeFolder,
+        list::ListFolders,
+        purge::PurgeFolder,
+        rename::RenameFolder,
+        status::{FolderStatus, FolderWithStatus, GetFolderStatus, ListFoldersWithStatus},
+        subscribe::{SubscribeFolder, UnsubscribeFolder},
+        Folder, Folders, INBOX,
+    },
+    message::{
+        add::AddMessage, copy::CopyMessages, delete::DeleteMessages, get::GetMessages,
+        peek::PeekMessages, r#move::MoveMessages, remove::RemoveMessages, Messages,
+    },
+    quota::{GetQuota, Quota},
+    AnyBoxedError, AnyResult,
+};
+
+/// The name of the virtual folder merging the inboxes of all
+/// accounts.
+pub const UNIFIED_INBOX: &str = "All Inboxes";
+
+/// The separator between the account name and the folder name or
+/// the envelope identifier.
+pub const UNIFIED_SEPARATOR: char = ':';
+
+/// Namespace the given folder name or envelope identifier with the
+/// given account name.
+pub fn namespace(account: &str, name: &str) -> String {
+    format!("{account}{UNIFIED_SEPARATOR}{name}")
+}
+
+/// Split the given namespaced folder name or envelope identifier
+/// into an account name and a folder name or envelope identifier.
+pub fn split_namespace(name: &str) -> Result<(&str, &str)> {
+    name.split_once(UNIFIED_SEPARATOR)
+        .ok_or_else(|| Error::ParseUnifiedNamespaceError(name.to_owned()))
+}
+
+/// Resolve the folder of the given account from the given unified
+/// folder.
+///
+/// The unified inbox resolves to the inbox of the account.
+fn resolve_folder<'a>(folder: &'a str, account: &str) -> Result<&'a str> {
+    if folder == UNIFIED_INBOX {
+        return Ok(INBOX);
+    }
+
+    match split_namespace(folder)? {
+        (folder_account, folder) if folder_account == account => Ok(folder),
+        (folder_account, _) => Err(Error::UnifiedAccountMismatchError(
+            account.to_owned(),
+            folder_account.to_owned(),
+        )),
+    }
+}
+
+/// Group of envelope identifiers belonging to the same account.
+#[derive(Debug, Eq, PartialEq)]
+struct IdsGroup<'a> {
+    account: &'a str,
+    positions: Vec<usize>,
+    ids: Vec<&'a str>,
+}
+
+impl IdsGroup<'_> {
+    fn to_id(&self) -> Id {
+        match self.ids.as_slice() {
+            [id] => Id::single(id),
+            ids => Id::multiple(ids),
+        }
+    }
+}
+
+/// Group the given namespaced envelope identifiers by account,
+/// preserving the order in which accounts appear.
+fn group_ids(id: &Id) -> Result<Vec<IdsGroup>> {
+    let mut groups: Vec<IdsGroup> = Vec::new();
+
+    for (position, id) in id.iter().enumerate() {
+        let (account, id) = split_namespace(id)?;
+
+        match groups.iter_mut().find(|group| group.account == account) {
+            Some(group) => {
+                group.positions.push(position);
+                group.ids.push(id);
+            }
+            None => groups.push(IdsGroup {
+                account,
+                positions: vec![position],
+                ids: vec![id],
+            }),
+        }
+    }
+
+    Ok(groups)
+}
+
+fn namespace_envelopes(account: &str, envelopes: Envelopes) -> impl Iterator<Item = Envelope> + '_ {
+    envelopes.into_iter().map(move |mut envelope| {
+        envelope.id = namespace(account, &envelope.id);
+        envelope
+    })
+}
+
+/// Put the raw messages of an identifiers group back at their
+/// original positions.
+///
+/// Messages are paired with identifiers by position, so the group
+/// must return exactly one message per identifier.
+fn insert_messages(
+    msgs: &mut [Option<Vec<u8>>],
+    group: &IdsGroup<'_>,
+    group_msgs: Messages,
+) -> AnyResult<()> {
+    let group_msgs = group_msgs.to_vec();
+
+    if group_msgs.len() != group.ids.len() {
+        let err = Error::UnifiedMessagesCountMismatchError(
+            group.account.to_owned(),
+            group.ids.len(),
+            group_msgs.len(),
+        );
+        return Err(err.into());
+    }
+
+    for (position, msg) in group.positions.iter().zip(group_msgs) {
+        msgs[*position] = Some(msg.raw()?.to_vec());
+    }
+
+    Ok(())
+}
+
+/// Collect the results of all accounts.
+///
+/// Accounts failing are skipped, unless all of them fail, in which
+/// case the last error is returned.
+fn collect_results<'a, T>(
+    results: impl IntoIterator<Item = (&'a str, AnyResult<T>)>,
+) -> AnyResult<Vec<(&'a str, T)>> {
+    let mut oks = Vec::new();
+    let mut last_err = None;
+
+    for (account, res) in results {
+        match res {
+            Ok(res) => oks.push((account, res)),
+            Err(err) => {
+                debug!(account, ?err, "unified feature failed, skipping account");
+                last_err = Some(err);
+            }
+        }
+    }
+
+    match last_err {
+        Some(err) if oks.is_empty() => Err(err),
+        _ => Ok(oks),
+    }
+}
+
+/// Merge the given threads, namespacing their envelope identifiers.
+#[cfg(feature = "thread")]
+fn namespace_threads<'a>(
+    threads: impl IntoIterator<Item = (&'a str, ThreadedEnvelopes)>,
+) -> ThreadedEnvelopes {
+    let mut envelopes = HashMap::new();
+    let mut edges = Vec::new();
+
+    for (account, thread) in threads {
+        for (a, b, w) in thread.graph().all_edges() {
+            let a = match a.id {
+                "0" => String::from("0"),
+                id => namespace(account, id),
+            };
+            edges.push((a, namespace(account, b.id), *w));
+        }
+
+        for envelope in thread.map().values() {
+            let mut envelope = envelope.clone();
+            envelope.id = namespace(account, &envelope.id);
+            envelopes.insert(envelope.id.clone(), envelope);
+        }
+    }
+
+    ThreadedEnvelopes::build(envelopes, move |envelopes| {
+        let mut graph = DiGraphMap::<ThreadedEnvelope, u8>::new();
+
+        for (a, b, w) in &edges {
+            let Some(eb) = envelopes.get(b) else {
+                continue;
+            };
+
+            let ea = match envelopes.get(a) {
+                Some(ea) => ea.as_threaded(),
+                None => ThreadedEnvelope {
+                    id: "0",
+                    message_id: "0",
+                    subject: "",
+                    from: "",
+                    date: Default::default(),
+                },
+            };
+
+            graph.add_edge(ea, eb.as_threaded(), *w);
+        }
+
+        graph
+    })
+}
+
+/// The unified backend context.
+///
+/// The context holds the context of every account, in the order
+/// they were added to the [`UnifiedContextBuilder`].
+pub struct UnifiedContext<C: BackendContext> {
+    pub accounts: Vec<(String, C)>,
+}
+
+impl<C: BackendContext> BackendContext for UnifiedContext<C> {}
+
+/// Macro for defining [`UnifiedContextBuilder`] features.
+macro_rules! unified_feature {
+    ($feat:ty) => {
+        paste! {
+            fn [<$feat:snake>](&self) -> Option<BackendFeature<Self::Context, dyn $feat>> {
+                let f = self.map_feature(|cb| cb.[<$feat:snake>]())?;
+                Some(Arc::new(move |ctx: &Self::Context| {
+                    let feature: Box<dyn $feat> = f(ctx)?;
+                    Some(feature)
+                }))
+            }
+        }
+    };
+}
+
+/// The unified backend context builder.
+///
+/// Each account is identified by its name, which is used to
+/// namespace folders and envelope identifiers. Account names should
+/// not contain [`UNIFIED_SEPARATOR`].
+#[derive(Clone)]
+pub struct UnifiedContextBuilder<CB: BackendContextBuilder> {
+    pub accounts: Vec<(String, CB)>,
+}
+
+impl<CB: BackendContextBuilder> Default for UnifiedContextBuilder<CB> {
+    fn default() -> Self {
+        Self {
+            accounts: Vec::new(),
+        }
+    }
+}
+
+impl<CB> UnifiedContextBuilder<CB>
+where
+    CB: BackendContextBuilder,
+    CB::Context: 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_account(&mut self, name: impl ToString, ctx_builder: CB) {
+        self.accounts.push((name.to_string(), ctx_builder));
+    }
+
+    pub fn with_account(mut self, name: impl ToString, ctx_builder: CB) -> Self {
+        self.add_account(name, ctx_builder);
+        self
+    }
+
+    /// Map the given feature of every account to the unified
+    /// context.
+    ///
+    /// Returns `None` if no account defines the feature. Accounts
+    /// not defining the feature are ignored.
+    fn map_feature<F: ?Sized + 'static>(
+        &self,
+        f: impl Fn(&CB) -> Option<BackendFeature<CB::Context, F>>,
+    ) -> Option<BackendFeature<UnifiedContext<CB::Context>, UnifiedFeature<F>>> {
+        let features: Vec<_> = self
+            .accounts
+            .iter()
+            .map(|(account, cb)| (account.clone(), f(cb)))
+            .collect();
+
+        if features.iter().all(|(_, f)| f.is_none()) {
+            return None;
+        }
+
+        Some(Arc::new(move |ctx: &UnifiedContext<CB::Context>| {
+            let features = ctx
+                .accounts
+                .iter()
+                .zip(&features)
+                .filter_map(|((account, ctx), (_, f))| Some((account.clone(), f.as_ref()?(ctx)?)))
+                .collect();
+
+            Some(Box::new(UnifiedFeature { features }))
+        }))
+    }
+}
+
+#[async_trait]
+impl<CB> BackendContextBuilder for UnifiedContextBuilder<CB>
+where
+    CB: BackendContextBuilder,
+    CB::Context: 'static,
+{
+    type Context = UnifiedContext<CB::Context>;
+
+    async fn check(&self) -> AnyResult<()> {
+        try_join_all(self.accounts.iter().map(|(_, cb)| cb.check())).await?;
+        Ok(())
+    }
+
+    fn check_configuration(&self) -> AnyResult<()> {
+        for (_, cb) in &self.accounts {
+            cb.check_configuration()?;
+        }
+        Ok(())
+    }
+
+    async fn configure(&mut self) -> AnyResult<()> {
+        for (_, cb) in &mut self.accounts {
+            cb.configure().await?;
+        }
+        Ok(())
+    }
+
+    unified_feature!(CheckUp);
+
+    unified_feature!(AddFolder);
+    unified_feature!(ListFolders);
+    unified_feature!(ListFoldersWithStatus);
+    unified_feature!(GetFolderStatus);
+    unified_feature!(ExpungeFolder);
+    unified_feature!(PurgeFolder);
+    unified_feature!(RenameFolder);
+    unified_feature!(SubscribeFolder);
+    unified_feature!(UnsubscribeFolder);
+    unified_feature!(GetFolderAnnotations);
+    unified_feature!(SetFolderAnnotation);
+    unified_feature!(DeleteFolder);
+    unified_feature!(GetEnvelope);
+    unified_feature!(ListEnvelopes);
+    #[cfg(feature = "thread")]
+    unified_feature!(ThreadEnvelopes);
+    unified_feature!(AddFlags);
+    unified_feature!(SetFlags);
+    unified_feature!(RemoveFlags);
+    unified_feature!(AddMessage);
+    unified_feature!(PeekMessages);
+    unified_feature!(GetMessages);
+    unified_feature!(CopyMessages);
+    unified_feature!(MoveMessages);
+    unified_feature!(DeleteMessages);
+    unified_feature!(RemoveMessages);
+    unified_feature!(GetQuota);
+
+    async fn build(self) -> AnyResult<Self::Context> {
+        let accounts = try_join_all(self.accounts.into_iter().map(|(account, cb)| async move {
+            Ok::<_, AnyBoxedError>((account, cb.build().await?))
+        }))
+        .await?;
+
+        Ok(UnifiedContext { accounts })
+    }
+}
+
+/// The unified backend feature.
+///
+/// Holds the given feature for every account defining it.
+pub struct UnifiedFeature<F: ?Sized> {
+    features: Vec<(String, Box<F>)>,
+}
+
+impl<F: ?Sized> UnifiedFeature<F> {
+    fn get(&self, account: &str) -> Result<&F> {
+        self.features
+            .iter()
+            .find(|(name, _)| name == account)
+            .map(|(_, f)| f.as_ref())
+            .ok_or_else(|| Error::GetUnifiedAccountError(account.to_owned()))
+    }
+}
+
+#[async_trait]
+impl CheckUp for UnifiedFeature<dyn CheckUp> {
+    async fn check_up(&self) -> AnyResult<()> {
+        try_join_all(self.features.iter().map(|(_, f)| f.check_up())).await?;
+        Ok(())
+    }
+
+    async fn server_capabilities(&self) -> AnyResult<ServerCapabilities> {
+        let capabilities =
+            try_join_all(self.features.iter().map(|(_, f)| f.server_capabilities())).await?;
+        Ok(capabilities.into_iter().flatten().collect())
+    }
+}
+
+#[async_trait]
+impl AddFolder for UnifiedFeature<dyn AddFolder> {
+    async fn add_folder(&self, folder: &str) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("add folder").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.add_folder(folder).await
+    }
+}
+
+impl UnifiedFeature<dyn ListFolders> {
+    /// List folders of all accounts, preceded by the unified inbox.
+    async fn list_unified_folders(&self, with_subscriptions: bool) -> AnyResult<Folders> {
+        let mut folders = vec![Folder {
+            kind: None,
+            name: UNIFIED_INBOX.to_owned(),
+            desc: String::from("Inboxes of all accounts"),
+            ..Default::default()
+        }];
+
+        let results = join_all(self.features.iter().map(|(account, f)| async move {
+            let res = if with_subscriptions {
+                f.list_folders_with_subscriptions().await
+            } else {
+                f.list_folders().await
+            };
+            (account.as_str(), res)
+        }))
+        .await;
+
+        for (account, account_folders) in collect_results(results)? {
+            folders.extend(account_folders.into_iter().map(|mut folder| {
+                folder.name = namespace(account, &folder.name);
+                folder
+            }));
+        }
+
+        Ok(Folders::from_iter(folders))
+    }
+}
+
+#[async_trait]
+impl ListFolders for UnifiedFeature<dyn ListFolders> {
+    async fn list_folders(&self) -> AnyResult<Folders> {
+        self.list_unified_folders(false).await
+    }
+
+    async fn list_folders_with_subscriptions(&self) -> AnyResult<Folders> {
+        self.list_unified_folders(true).await
+    }
+}
+
+/// Sum the given folder statuses.
+///
+/// Unique identifiers cannot be merged, so they are discarded. The
+/// size is kept only if known for all statuses.
+fn sum_folder_statuses(statuses: impl IntoIterator<Item = FolderStatus>) -> FolderStatus {
+    let mut sum = FolderStatus {
+        size: Some(0),
+        ..Default::default()
+    };
+
+    for status in statuses {
+        sum.total += status.total;
+        sum.unseen += status.unseen;
+        sum.recent += status.recent;
+        sum.size = sum.size.zip(status.size).map(|(a, b)| a + b);
+    }
+
+    sum
+}
+
+#[async_trait]
+impl GetFolderStatus for UnifiedFeature<dyn GetFolderStatus> {
+    /// Get the status of the given folder.
+    ///
+    /// The status of the unified inbox is the sum of the inbox
+    /// statuses of all accounts.
+    async fn get_folder_status(&self, folder: &str) -> AnyResult<FolderStatus> {
+        if folder == UNIFIED_INBOX {
+            let results = join_all(self.features.iter().map(|(account, f)| async move {
+                (account.as_str(), f.get_folder_status(INBOX).await)
+            }))
+            .await;
+
+            let statuses = collect_results(results)?;
+            return Ok(sum_folder_statuses(statuses.into_iter().map(|(_, s)| s)));
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.get_folder_status(folder).await
+    }
+}
+
+#[async_trait]
+impl ListFoldersWithStatus for UnifiedFeature<dyn ListFoldersWithStatus> {
+    /// List folders of all accounts alongside their status, preceded
+    /// by the unified inbox.
+    async fn list_folders_with_status(&self) -> AnyResult<Vec<FolderWithStatus>> {
+        let results = join_all(self.features.iter().map(|(account, f)| async move {
+            (account.as_str(), f.list_folders_with_status().await)
+        }))
+        .await;
+
+        let mut inboxes = Vec::new();
+        let mut folders = Vec::new();
+
+        for (account, account_folders) in collect_results(results)? {
+            for (mut folder, status) in account_folders {
+                if folder.is_inbox() {
+                    inboxes.push(status.clone());
+                }
+
+                folder.name = namespace(account, &folder.name);
+                folders.push((folder, status));
+            }
+        }
+
+        let inbox = Folder {
+            kind: None,
+            name: UNIFIED_INBOX.to_owned(),
+            desc: String::from("Inboxes of all accounts"),
+            ..Default::default()
+        };
+
+        folders.insert(0, (inbox, sum_folder_statuses(inboxes)));
+
+        Ok(folders)
+    }
+}
+
+#[async_trait]
+impl ExpungeFolder for UnifiedFeature<dyn ExpungeFolder> {
+    async fn expunge_folder(&self, folder: &str) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX {
+            for (_, f) in &self.features {
+                f.expunge_folder(INBOX).await?;
+            }
+            return Ok(());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.expunge_folder(folder).await
+    }
+}
+
+#[async_trait]
+impl PurgeFolder for UnifiedFeature<dyn PurgeFolder> {
+    async fn purge_folder(&self, folder: &str) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX {
+            for (_, f) in &self.features {
+                f.purge_folder(INBOX).await?;
+            }
+            return Ok(());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.purge_folder(folder).await
+    }
+}
+
+#[async_trait]
+impl RenameFolder for UnifiedFeature<dyn RenameFolder> {
+    /// Rename the given folder.
+    ///
+    /// Folders cannot be renamed across accounts.
+    async fn rename_folder(&self, folder: &str, new_folder: &str) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX || new_folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("rename folder").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        let new_folder = resolve_folder(new_folder, account)?;
+        self.get(account)?.rename_folder(folder, new_folder).await
+    }
+}
+
+#[async_trait]
+impl SubscribeFolder for UnifiedFeature<dyn SubscribeFolder> {
+    async fn subscribe_folder(&self, folder: &str) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("subscribe folder").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.subscribe_folder(folder).await
+    }
+}
+
+#[async_trait]
+impl UnsubscribeFolder for UnifiedFeature<dyn UnsubscribeFolder> {
+    async fn unsubscribe_folder(&self, folder: &str) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("unsubscribe folder").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.unsubscribe_folder(folder).await
+    }
+}
+
+#[async_trait]
+impl GetFolderAnnotations for UnifiedFeature<dyn GetFolderAnnotations> {
+    async fn get_folder_annotations(&self, folder: &str) -> AnyResult<FolderAnnotations> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("get folder annotations").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.get_folder_annotations(folder).await
+    }
+}
+
+#[async_trait]
+impl SetFolderAnnotation for UnifiedFeature<dyn SetFolderAnnotation> {
+    async fn set_folder_annotation(
+        &self,
+        folder: &str,
+        key: &str,
+        val: Option<&str>,
+    ) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("set folder annotation").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?
+            .set_folder_annotation(folder, key, val)
+            .await
+    }
+}
+
+#[async_trait]
+impl DeleteFolder for UnifiedFeature<dyn DeleteFolder> {
+    async fn delete_folder(&self, folder: &str) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("delete folder").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.delete_folder(folder).await
+    }
+}
+
+#[async_trait]
+impl GetEnvelope for UnifiedFeature<dyn GetEnvelope> {
+    async fn get_envelope(&self, folder: &str, id: &SingleId) -> AnyResult<Envelope> {
+        let (account, id) = split_namespace(id.as_str())?;
+        let folder = resolve_folder(folder, account)?;
+        let mut envelope = self.get(account)?.get_envelope(folder, &id.into()).await?;
+        envelope.id = namespace(account, &envelope.id);
+        Ok(envelope)
+    }
+}
+
+#[async_trait]
+impl ListEnvelopes for UnifiedFeature<dyn ListEnvelopes> {
+    /// List envelopes of the given namespaced folder, or of the
+    /// inboxes of all accounts in case of the unified inbox.
+    ///
+    /// For the unified inbox, the first pages of every account are
+    /// merged then sorted, so that the requested page is consistent
+    /// across accounts.
+    async fn list_envelopes(
+        &self,
+        folder: &str,
+        opts: ListEnvelopesOptions,
+    ) -> AnyResult<Envelopes> {
+        if folder != UNIFIED_INBOX {
+            let (account, folder) = split_namespace(folder)?;
+            let envelopes = self.get(account)?.list_envelopes(folder, opts).await?;
+            let total = envelopes.total();
+            let envelopes: Envelopes = namespace_envelopes(account, envelopes).collect();
+            return Ok(envelopes.with_total(total));
+        }
+
+        let account_opts = ListEnvelopesOptions {
+            page_size: (opts.page + 1) * opts.page_size,
+            page: 0,
+            query: opts.query.clone(),
+        };
+
+        let results = join_all(self.features.iter().map(|(account, f)| {
+            let opts = account_opts.clone();
+            async move { (account.as_str(), f.list_envelopes(INBOX, opts).await) }
+        }))
+        .await;
+
+        let mut envelopes = Vec::new();
+        let mut total = 0;
+
+        for (account, account_envelopes) in collect_results(results)? {
+            total += account_envelopes.total();
+            envelopes.extend(namespace_envelopes(account, account_envelopes));
+        }
+
+        let mut envelopes = Envelopes::from_iter(envelopes);
+        opts.sort_envelopes(&mut envelopes);
+
+        if opts.page_size > 0 {
+            let page_begin = opts.page * opts.page_size;
+            envelopes = envelopes
+                .into_iter()
+                .skip(page_begin)
+                .take(opts.page_size)
+                .collect();
+        }
+
+        Ok(envelopes.with_total(total))
+    }
+}
+
+#[cfg(feature = "thread")]
+#[async_trait]
+impl ThreadEnvelopes for UnifiedFeature<dyn ThreadEnvelopes> {
+    async fn thread_envelopes(
+        &self,
+        folder: &str,
+        opts: ListEnvelopesOptions,
+    ) -> AnyResult<ThreadedEnvelopes> {
+        if folder != UNIFIED_INBOX {
+            let (account, folder) = split_namespace(folder)?;
+            let thread = self.get(account)?.thread_envelopes(folder, opts).await?;
+            return Ok(namespace_threads([(account, thread)]));
+        }
+
+        let results = join_all(self.features.iter().map(|(account, f)| {
+            let opts = opts.clone();
+            async move { (account.as_str(), f.thread_envelopes(INBOX, opts).await) }
+        }))
+        .await;
+
+        Ok(namespace_threads(collect_results(results)?))
+    }
+
+    async fn thread_envelope(
+        &self,
+        folder: &str,
+        id: SingleId,
+        opts: ListEnvelopesOptions,
+    ) -> AnyResult<ThreadedEnvelopes> {
+        let (account, id) = split_namespace(id.as_str())?;
+        let folder = resolve_folder(folder, account)?;
+        let f = self.get(account)?;
+        let thread = f.thread_envelope(folder, id.into(), opts).await?;
+        Ok(namespace_threads([(account, thread)]))
+    }
+}
+
+#[async_trait]
+impl AddFlags for UnifiedFeature<dyn AddFlags> {
+    async fn add_flags(&self, folder: &str, id: &Id, flags: &Flags) -> AnyResult<()> {
+        for group in group_ids(id)? {
+            let folder = resolve_folder(folder, group.account)?;
+            let f = self.get(group.account)?;
+            f.add_flags(folder, &group.to_id(), flags).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SetFlags for UnifiedFeature<dyn SetFlags> {
+    async fn set_flags(&self, folder: &str, id: &Id, flags: &Flags) -> AnyResult<()> {
+        for group in group_ids(id)? {
+            let folder = resolve_folder(folder, group.account)?;
+            let f = self.get(group.account)?;
+            f.set_flags(folder, &group.to_id(), flags).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RemoveFlags for UnifiedFeature<dyn RemoveFlags> {
+    async fn remove_flags(&self, folder: &str, id: &Id, flags: &Flags) -> AnyResult<()> {
+        for group in group_ids(id)? {
+            let folder = resolve_folder(folder, group.account)?;
+            let f = self.get(group.account)?;
+            f.remove_flags(folder, &group.to_id(), flags).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AddMessage for UnifiedFeature<dyn AddMessage> {
+    async fn add_message_with_flags(
+        &self,
+        folder: &str,
+        msg: &[u8],
+        flags: &Flags,
+    ) -> AnyResult<SingleId> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("add message").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        let f = self.get(account)?;
+        let id = f.add_message_with_flags(folder, msg, flags).await?;
+        Ok(namespace(account, &id).into())
+    }
+
+    async fn add_message_with_flags_and_date(
+        &self,
+        folder: &str,
+        msg: &[u8],
+        flags: &Flags,
+        date: &DateTime<FixedOffset>,
+    ) -> AnyResult<()> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("add message").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        let f = self.get(account)?;
+        f.add_message_with_flags_and_date(folder, msg, flags, date)
+            .await
+    }
+}
+
+#[async_trait]
+impl PeekMessages for UnifiedFeature<dyn PeekMessages> {
+    async fn peek_messages(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
+        let mut msgs = vec![None; id.iter().count()];
+
+        for group in group_ids(id)? {
+            let folder = resolve_folder(folder, group.account)?;
+            let f = self.get(group.account)?;
+            let group_msgs = f.peek_messages(folder, &group.to_id()).await?;
+            insert_messages(&mut msgs, &group, group_msgs)?;
+        }
+
+        Ok(Messages::from(
+            msgs.into_iter().flatten().collect::<Vec<_>>(),
+        ))
+    }
+
+    async fn peek_messages_headers(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
+        let mut msgs = vec![None; id.iter().count()];
+
+        for group in group_ids(id)? {
+            let folder = resolve_folder(folder, group.account)?;
+            let f = self.get(group.account)?;
+            let group_msgs = f.peek_messages_headers(folder, &group.to_id()).await?;
+            insert_messages(&mut msgs, &group, group_msgs)?;
+        }
+
+        Ok(Messages::from(
+            msgs.into_iter().flatten().collect::<Vec<_>>(),
+        ))
+    }
+}
+
+#[async_trait]
+impl GetMessages for UnifiedFeature<dyn GetMessages> {
+    async fn get_messages(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
+        let mut msgs = vec![None; id.iter().count()];
+
+        for group in group_ids(id)? {
+            let folder = resolve_folder(folder, group.account)?;
+            let f = self.get(group.account)?;
+            let group_msgs = f.get_messages(folder, &group.to_id()).await?;
+            insert_messages(&mut msgs, &group, group_msgs)?;
+        }
+
+        Ok(Messages::from(
+            msgs.into_iter().flatten().collect::<Vec<_>>(),
+        ))
+    }
+}
+
+#[async_trait]
+impl CopyMessages for UnifiedFeature<dyn CopyMessages> {
+    /// Copy messages to the given folder.
+    ///
+    /// Messages cannot be copied across accounts.
+    async fn copy_messages(&self, from_folder: &str, to_folder: &str, id: &Id) -> AnyResult<()> {
+        for group in group_ids(id)? {
+            let from_folder = resolve_folder(from_folder, group.account)?;
+            let to_folder = resolve_folder(to_folder, group.account)?;
+            let f = self.get(group.account)?;
+            f.copy_messages(from_folder, to_folder, &group.to_id())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MoveMessages for UnifiedFeature<dyn MoveMessages> {
+    /// Move messages to the given folder.
+    ///
+    /// Messages cannot be moved across accounts.
+    async fn move_messages(&self, from_folder: &str, to_folder: &str, id: &Id) -> AnyResult<()> {
+        for group in group_ids(id)? {
+            let from_folder = resolve_folder(from_folder, group.account)?;
+            let to_folder = resolve_folder(to_folder, group.account)?;
+            let f = self.get(group.account)?;
+            f.move_messages(from_folder, to_folder, &group.to_id())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeleteMessages for UnifiedFeature<dyn DeleteMessages> {
+    async fn delete_messages(&self, folder: &str, id: &Id) -> AnyResult<()> {
+        for group in group_ids(id)? {
+            let folder = resolve_folder(folder, group.account)?;
+            let f = self.get(group.account)?;
+            f.delete_messages(folder, &group.to_id()).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RemoveMessages for UnifiedFeature<dyn RemoveMessages> {
+    async fn remove_messages(&self, folder: &str, id: &Id) -> AnyResult<()> {
+        for group in group_ids(id)? {
+            let folder = resolve_folder(folder, group.account)?;
+            let f = self.get(group.account)?;
+            f.remove_messages(folder, &group.to_id()).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GetQuota for UnifiedFeature<dyn GetQuota> {
+    async fn get_quota(&self, folder: &str) -> AnyResult<Quota> {
+        if folder == UNIFIED_INBOX {
+            return Err(Error::UnifiedInboxNotSupportedError("get quota").into());
+        }
+
+        let (account, folder) = split_namespace(folder)?;
+        self.get(account)?.get_quota(folder).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use chrono::DateTime;
+
+    use super::{
+        group_ids, insert_messages, resolve_folder, IdsGroup, UnifiedFeature, UNIFIED_INBOX,
+    };
+    use crate::{
+        backend::Error,
+        envelope::{
+            list::{ListEnvelopes, ListEnvelopesOptions},
+            Envelope, Envelopes, Id,
+        },
+        message::Messages,
+        AnyResult,
+    };
+
+    /// List envelopes stub, failing when no envelope is given.
+    struct ListEnvelopesStub(Option<Vec<u32>>);
+
+    #[async_trait]
+    impl ListEnvelopes for ListEnvelopesStub {
+        async fn list_envelopes(
+            &self,
+            _folder: &str,
+            opts: ListEnvelopesOptions,
+        ) -> AnyResult<Envelopes> {
+            let Some(timestamps) = self.0.as_ref() else {
+                return Err(Error::GetUnifiedAccountError("stub".into()).into());
+            };
+
+            let mut envelopes: Envelopes = timestamps
+                .iter()
+                .map(|timestamp| Envelope {
+                    id: timestamp.to_string(),
+                    date: DateTime::from_timestamp(*timestamp as i64, 0)
+                        .unwrap()
+                        .fixed_offset(),
+                    ..Default::default()
+                })
+                .collect();
+
+            opts.sort_envelopes(&mut envelopes);
+
+            let total = envelopes.len();
+            let envelopes: Envelopes = envelopes.into_iter().take(opts.page_size).collect();
+            Ok(envelopes.with_total(total))
+        }
+    }
+
+    fn unified_list_envelopes(
+        accounts: impl IntoIterator<Item = (&'static str, Option<Vec<u32>>)>,
+    ) -> UnifiedFeature<dyn ListEnvelopes> {
+        let features = accounts
+            .into_iter()
+            .map(|(account, timestamps)| {
+                let f: Box<dyn ListEnvelopes> = Box::new(ListEnvelopesStub(timestamps));
+                (account.to_owned(), f)
+            })
+            .collect();
+
+        UnifiedFeature { features }
+    }
+
+    #[test]
+    fn resolve_folder_from_namespace() {
+        assert_eq!(resolve_folder(UNIFIED_INBOX, "work").unwrap(), "INBOX");
+        assert_eq!(resolve_folder("work:Sent", "work").unwrap(), "Sent");
+        assert_eq!(resolve_folder("work:a:b", "work").unwrap(), "a:b");
+        assert!(resolve_folder("perso:Sent", "work").is_err());
+        assert!(resolve_folder("Sent", "work").is_err());
+    }
+
+    #[test]
+    fn group_ids_by_account() {
+        let id = Id::multiple(["work:1", "perso:a", "work:2"]);
+
+        assert_eq!(
+            group_ids(&id).unwrap(),
+            vec![
+                IdsGroup {
+                    account: "work",
+                    positions: vec![0, 2],
+                    ids: vec!["1", "2"],
+                },
+                IdsGroup {
+                    account: "perso",
+                    positions: vec![1],
+                    ids: vec!["a"],
+                },
+            ]
+        );
+
+        assert_eq!(group_ids(&id).unwrap()[1].to_id(), Id::single("a"));
+        assert!(group_ids(&Id::single("1")).is_err());
+    }
+
+    #[tokio::test]
+    async fn list_unified_inbox_envelopes() {
+        let f = unified_list_envelopes([
+            ("work", Some(vec![1, 3, 5])),
+            ("broken", None),
+            ("perso", Some(vec![2, 4])),
+        ]);
+
+        let opts = ListEnvelopesOptions {
+            page_size: 2,
+            page: 1,
+            query: None,
+        };
+
+        let envelopes = f.list_envelopes(UNIFIED_INBOX, opts).await.unwrap();
+        let ids: Vec<_> = envelopes.iter().map(|e| e.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["work:3", "perso:2"]);
+        assert_eq!(envelopes.total(), 5);
+
+        let f = unified_list_envelopes([("work", None), ("perso", None)]);
+        assert!(f
+            .list_envelopes(UNIFIED_INBOX, Default::default())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn insert_messages_by_position() {
+        let id = Id::multiple(["work:1", "perso:a", "work:2"]);
+        let groups = group_ids(&id).unwrap();
+        let mut msgs = vec![None; 3];
+
+        let work_msgs = Messages::from(vec![b"1".to_vec(), b"2".to_vec()]);
+        insert_messages(&mut msgs, &groups[0], work_msgs).unwrap();
+
+        let perso_msgs = Messages::from(Vec::<Vec<u8>>::new());
+        assert!(insert_messages(&mut msgs, &groups[1], perso_msgs).is_err());
+
+        assert_eq!(msgs, vec![Some(b"1".to_vec()), None, Some(b"2".to_vec())]);
+    }
+}
@@ -80,6 +80,7 @@ pub fn account(&self, name: impl AsRef<str>) -> Result<AccountConfig> {
             flag: account_config.flag.clone(),
             message: account_config.message.clone(),
             template: account_config.template.clone(),
+            quota: account_config.quota.clone(),
             #[cfg(feature = "sync")]
             sync: account_config.sync.clone(),
             #[cfg(feature = "pgp")]
@@ -19,14 +19,25 @@
 };
 
 /// The IMAP fetch items needed to retrieve everything we need to
-/// build an envelope: UID, flags and envelope (Message-ID, From, To,
-/// Subject, Date).
+/// build an envelope: UID, flags, envelope (Message-ID, From, To,
+/// Subject, Date), size and internal date.
 pub static FETCH_ENVELOPES: Lazy<MacroOrMessageDataItemNames<'static>> = Lazy::new(|| {
     MacroOrMessageDataItemNames::MessageDataItemNames(vec![
         MessageDataItemName::Uid,
         MessageDataItemName::Flags,
         MessageDataItemName::Envelope,
         MessageDataItemName::BodyStructure,
+        MessageDataItemName::Rfc822Size,
+        MessageDataItemName::InternalDate,
+    ])
+});
+
+/// The IMAP fetch items needed to retrieve the flags of messages
+/// only: UID and flags.
+pub static FETCH_FLAGS: Lazy<MacroOrMessageDataItemNames<'static>> = Lazy::new(|| {
+    MacroOrMessageDataItemNames::MessageDataItemNames(vec![
+        MessageDataItemName::Uid,
+        MessageDataItemName::Flags,
     ])
 });
 
@@ -54,6 +65,8 @@ pub fn from_imap_data_items(items: &[MessageDataItem]) -> Self {
         let mut flags = Flags::default();
         let mut msg = Vec::default();
         let mut has_attachment = false;
+        let mut size = 0;
+        let mut internal_date = None;
 
         for item in items {
             match item {
@@ -149,6 +162,12 @@ pub fn from_imap_data_items(items: &[MessageDataItem]) -> Self {
                 MessageDataItem::BodyStructure(body) => {
                     has_attachment = has_at_least_one_attachment([body]);
                 }
+                MessageDataItem::Rfc822Size(rfc822_size) => {
+                    size = *rfc822_size as usize;
+                }
+                MessageDataItem::InternalDate(date) => {
+                    internal_date = Some(*date.as_ref());
+                }
                 _ => (),
             }
         }
@@ -156,6 +175,8 @@ pub fn from_imap_data_items(items: &[MessageDataItem]) -> Self {
         let msg = Message::from(msg);
         let mut env = Envelope::from_msg(id, flags, msg);
         env.has_attachment = has_attachment;
+        env.size = size;
+        env.internal_date = internal_date;
         env
     }
 }
@@ -72,11 +72,16 @@ async fn list_envelopes(
             return Ok(Envelopes::default());
         }
 
-        let envelopes = if let Some(query) = opts.query.as_ref() {
+        let (mut envelopes, total) = if let Some(query) = opts.query.as_ref() {
             let sort_supported = client.ext_sort_supported();
             let sort_criteria = query.to_imap_sort_criteria();
             let search_criteria = query.to_imap_search_criteria();
 
+            // TODO: use SEARCH RETURN (COUNT PARTIAL …) when ESEARCH
+            // (RFC 4731) and PARTIAL (RFC 9394) are advertised, once
+            // supported by imap-types, which can neither encode the
+            // RETURN options nor parse ESEARCH responses yet. In the
+            // meantime, all matching UIDs are received.
             let uids = if sort_supported {
                 client
                     .sort_uids(sort_criteria.clone(), search_criteria.clone())
@@ -85,6 +90,8 @@ async fn list_envelopes(
                 client.search_uids(search_criteria.clone()).await
             }?;
 
+            let total = uids.len();
+
             // this client is not used anymore, so we can drop it now
             // in order to free one client slot from the clients
             // connection pool
@@ -155,15 +162,17 @@ async fn list_envelopes(
                 apply_pagination(&mut envelopes, opts.page, opts.page_size)?;
             }
 
-            envelopes
+            (envelopes, total)
         } else {
             let seq = build_sequence(opts.page, opts.page_size, folder_size)?;
             let mut envelopes = client.fetch_envelopes_by_sequence(seq.into()).await?;
             envelopes.sort_by(|a, b| b.date.cmp(&a.date));
-            envelopes
+            (envelopes, folder_size)
         };
 
-        debug!("found {} imap envelopes", envelopes.len());
+        envelopes.set_total(total);
+
+        debug!("found {}/{total} imap envelopes", envelopes.len());
         trace!("{envelopes:#?}");
 
         Ok(envelopes)
@@ -288,18 +297,30 @@ pub fn to_imap_sort_criterion(&self) -> SortCriterion {
     }
 }
 
+/// Check that the given page is in the bounds of the given total.
+///
+/// The first page is always valid, even when there is nothing to
+/// list.
+fn check_page_bounds(page: usize, page_size: usize, total: usize) -> Result<()> {
+    let page_cursor = page * page_size;
+
+    if page_cursor > 0 && page_cursor >= total {
+        Err(Error::BuildPageRangeOutOfBoundsImapError(page + 1))?
+    }
+
+    Ok(())
+}
+
 fn paginate<T>(items: &[T], page: usize, page_size: usize) -> Result<&[T]> {
     if page_size == 0 {
         return Ok(items);
     }
 
     let total = items.len();
-    let page_cursor = page * page_size;
-    if page_cursor >= total {
-        Err(Error::BuildPageRangeOutOfBoundsImapError(page + 1))?
-    }
+    check_page_bounds(page, page_size, total)?;
 
-    Ok(&items[0..page_size.min(total)])
+    let page_cursor = page * page_size;
+    Ok(&items[page_cursor..total.min(page_cursor + page_size)])
 }
 
 fn apply_pagination(
@@ -307,18 +328,16 @@ fn apply_pagination(
     page: usize,
     page_size: usize,
 ) -> result::Result<(), Error> {
-    let total = envelopes.len();
-    let page_cursor = page * page_size;
-    if page_cursor >= total {
-        Err(Error::BuildPageRangeOutOfBoundsImapError(page + 1))?
-    }
-
     if page_size == 0 {
         return Ok(());
     }
 
-    let page_size = page_size.min(total);
-    *envelopes = Envelopes(envelopes[0..page_size].to_vec());
+    let total = envelopes.len();
+    check_page_bounds(page, page_size, total)?;
+
+    let page_cursor = page * page_size;
+    let page_end = total.min(page_cursor + page_size);
+    **envelopes = envelopes[page_cursor..page_end].to_vec();
     Ok(())
 }
 
@@ -348,3 +367,19 @@ fn build_sequence(page: usize, page_size: usize, total: usize) -> Result<Sequenc
 
     Ok(seq)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::paginate;
+
+    #[test]
+    fn paginate_items() {
+        let items = [1, 2, 3, 4, 5];
+
+        assert_eq!(paginate(&items, 0, 0).unwrap(), &items);
+        assert_eq!(paginate(&items, 0, 2).unwrap(), &[1, 2]);
+        assert_eq!(paginate(&items, 2, 2).unwrap(), &[5]);
+        assert!(paginate(&items, 3, 2).is_err());
+        assert!(paginate::<u8>(&[], 0, 2).unwrap().is_empty());
+    }
+}
@@ -72,6 +72,7 @@ async fn list_envelopes(
         debug!("page end: {}", page_end);
 
         opts.sort_envelopes(&mut envelopes);
+        envelopes.set_total(envelopes.len());
         *envelopes = envelopes[page_begin..page_end].into();
 
         Ok(envelopes)
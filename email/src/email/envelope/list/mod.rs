@@ -21,6 +21,9 @@
 pub trait ListEnvelopes: Send + Sync {
     /// List all available envelopes from the given folder matching
     /// the given pagination.
+    ///
+    /// The total number of envelopes matching the listing, before
+    /// pagination, is available via [`Envelopes::total`].
     async fn list_envelopes(
         &self,
         folder: &str,
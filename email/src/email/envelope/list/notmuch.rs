@@ -90,6 +90,7 @@ async fn list_envelopes(
         });
 
         opts.sort_envelopes(&mut envelopes);
+        envelopes.set_total(envelopes.len());
         *envelopes = envelopes[page_begin..page_end].into();
 
         db.close().map_err(Error::NotMuchFailure)?;
@@ -3,6 +3,9 @@
 //! This module contains envelope-related mapping functions from the
 //! [maildirpp] crate types.
 
+use std::fs;
+
+use chrono::{DateTime, Local};
 use maildirs::MaildirEntry;
 use rayon::prelude::*;
 
@@ -43,7 +46,13 @@ impl TryFrom<MaildirEntry> for Envelope {
 
     fn try_from(entry: MaildirEntry) -> Result<Self> {
         let id = entry.id()?.to_owned();
-        let msg = Message::from(entry.read()?);
+        let bytes = entry.read()?;
+        let size = bytes.len();
+        let internal_date = fs::metadata(entry.path())
+            .and_then(|meta| meta.modified())
+            .ok()
+            .map(|date| DateTime::<Local>::from(date).fixed_offset());
+        let msg = Message::from(bytes);
 
         let has_attachment = {
             let attachments = msg.attachments();
@@ -57,6 +66,8 @@ fn try_from(entry: MaildirEntry) -> Result<Self> {
         let flags = Flags::try_from(entry)?;
         let mut env = Envelope::from_msg(id, flags, msg);
         env.has_attachment = has_attachment;
+        env.size = size;
+        env.internal_date = internal_date;
         Ok(env)
     }
 }
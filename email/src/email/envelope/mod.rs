@@ -78,6 +78,18 @@ pub struct Envelope {
     /// An attachment is defined here as a MIME part that is not a
     /// `text/*`.
     pub has_attachment: bool,
+
+    /// The size of the whole message, in bytes.
+    ///
+    /// The size is 0 when the backend cannot determine it.
+    pub size: usize,
+
+    /// The internal date of the message, which is the date the
+    /// message has been received by the backend.
+    ///
+    /// The internal date is `None` when the backend cannot determine
+    /// it.
+    pub internal_date: Option<DateTime<FixedOffset>>,
 }
 
 impl Envelope {
@@ -263,21 +275,65 @@ fn hash<H: Hasher>(&self, state: &mut H) {
 }
 
 /// The list of email envelopes.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
-pub struct Envelopes(Vec<Envelope>);
+#[derive(Clone, Debug, Default, Eq)]
+pub struct Envelopes {
+    envelopes: Vec<Envelope>,
+
+    /// The total number of envelopes matching the listing, before
+    /// pagination.
+    total: Option<usize>,
+}
+
+impl Envelopes {
+    /// Return the total number of envelopes matching the listing,
+    /// before pagination.
+    ///
+    /// Falls back to the number of envelopes of the list when the
+    /// total is unknown.
+    pub fn total(&self) -> usize {
+        self.total.unwrap_or(self.envelopes.len())
+    }
+
+    pub fn set_total(&mut self, total: usize) {
+        self.total = Some(total);
+    }
+
+    pub fn with_total(mut self, total: usize) -> Self {
+        self.set_total(total);
+        self
+    }
+
+    /// Return the number of pages of the given size needed to list
+    /// all envelopes matching the listing.
+    pub fn pages_count(&self, page_size: usize) -> usize {
+        if page_size == 0 {
+            return 1;
+        }
+
+        self.total().div_ceil(page_size).max(1)
+    }
+}
+
+/// Envelopes are compared without their total, which is just a
+/// listing information.
+impl PartialEq for Envelopes {
+    fn eq(&self, other: &Self) -> bool {
+        self.envelopes == other.envelopes
+    }
+}
 
 impl IntoIterator for Envelopes {
     type IntoIter = vec::IntoIter<Self::Item>;
     type Item = Envelope;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.envelopes.into_iter()
     }
 }
 
 impl From<Envelopes> for Vec<Envelope> {
     fn from(val: Envelopes) -> Self {
-        val.0
+        val.envelopes
     }
 }
 
@@ -285,19 +341,22 @@ impl Deref for Envelopes {
     type Target = Vec<Envelope>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.envelopes
     }
 }
 
 impl DerefMut for Envelopes {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.envelopes
     }
 }
 
 impl FromIterator<Envelope> for Envelopes {
     fn from_iter<T: IntoIterator<Item = Envelope>>(iter: T) -> Self {
-        Envelopes(iter.into_iter().collect())
+        Envelopes {
+            envelopes: iter.into_iter().collect(),
+            total: None,
+        }
     }
 }
 
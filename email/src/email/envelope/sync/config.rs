@@ -1,4 +1,6 @@
-use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Local, NaiveDate};
 
 use crate::search_query::filter::SearchEmailsFilterQuery;
 
@@ -26,6 +28,14 @@ pub struct EnvelopeSyncFilters {
 
     /// Filter envelopes with a `Date` header older than the given date.
     pub after: Option<NaiveDate>,
+
+    /// Filter envelopes of the given folders with a `Date` header
+    /// within the given last number of days.
+    ///
+    /// This filter applies in addition to the global `before` and
+    /// `after` filters.
+    #[cfg_attr(feature = "derive", serde(default))]
+    pub within_days: BTreeMap<String, u32>,
 }
 
 impl EnvelopeSyncFilters {
@@ -64,6 +74,53 @@ pub fn with_before(mut self, date: impl Into<NaiveDate>) -> Self {
         self.set_before(date);
         self
     }
+
+    pub fn set_within_days(&mut self, folder: impl ToString, days: u32) {
+        self.within_days.insert(folder.to_string(), days);
+    }
+
+    pub fn with_within_days(mut self, folder: impl ToString, days: u32) -> Self {
+        self.set_within_days(folder, days);
+        self
+    }
+
+    /// Get the number of days envelopes of the given folder should be
+    /// synchronized within, if any.
+    ///
+    /// Folder names are matched case-insensitively.
+    pub fn get_within_days(&self, folder: impl AsRef<str>) -> Option<u32> {
+        let folder = folder.as_ref();
+        self.within_days
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(folder))
+            .map(|(_, days)| *days)
+    }
+
+    /// Build the search emails filter query of the given folder.
+    ///
+    /// The query combines the global `before` and `after` filters
+    /// with the folder window defined in `within_days`, if any.
+    pub fn to_folder_filter_query(
+        &self,
+        folder: impl AsRef<str>,
+    ) -> Option<SearchEmailsFilterQuery> {
+        let filter = Option::<SearchEmailsFilterQuery>::from(self.clone());
+
+        let window = self.get_within_days(folder).map(|days| {
+            let after = Local::now().date_naive() - Duration::days(days as i64);
+            SearchEmailsFilterQuery::AfterDate(after)
+        });
+
+        match (filter, window) {
+            (None, None) => None,
+            (Some(filter), None) => Some(filter),
+            (None, Some(window)) => Some(window),
+            (Some(filter), Some(window)) => Some(SearchEmailsFilterQuery::And(
+                Box::new(filter),
+                Box::new(window),
+            )),
+        }
+    }
 }
 
 impl From<EnvelopeSyncFilters> for Option<SearchEmailsFilterQuery> {
@@ -79,3 +136,39 @@ fn from(f: EnvelopeSyncFilters) -> Self {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local, NaiveDate};
+
+    use super::EnvelopeSyncFilters;
+    use crate::search_query::filter::SearchEmailsFilterQuery;
+
+    #[test]
+    fn folder_filter_query() {
+        let before = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let filters = EnvelopeSyncFilters::default()
+            .with_before(before)
+            .with_within_days("INBOX", 30);
+
+        let after = Local::now().date_naive() - Duration::days(30);
+
+        assert_eq!(
+            filters.to_folder_filter_query("inbox"),
+            Some(SearchEmailsFilterQuery::And(
+                Box::new(SearchEmailsFilterQuery::BeforeDate(before)),
+                Box::new(SearchEmailsFilterQuery::AfterDate(after)),
+            )),
+        );
+
+        assert_eq!(
+            filters.to_folder_filter_query("Archives"),
+            Some(SearchEmailsFilterQuery::BeforeDate(before)),
+        );
+
+        assert_eq!(
+            EnvelopeSyncFilters::default().to_folder_filter_query("INBOX"),
+            None,
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use super::event::EnvelopeEventKind;
 use crate::watch::config::WatchHook;
 
 /// Configuration dedicated to envelope changes.
@@ -12,6 +13,37 @@ pub struct WatchEnvelopeConfig {
     /// received.
     pub received: Option<WatchHook>,
 
+    /// Watch hook configuration for when the flags of an envelope
+    /// changed.
+    pub flags_changed: Option<WatchHook>,
+
+    /// Watch hook configuration for when an envelope has been
+    /// removed.
+    pub removed: Option<WatchHook>,
+
+    /// Watch hook configuration for when an envelope has been moved
+    /// from a watched folder to another.
+    pub moved: Option<WatchHook>,
+
     /// Watch hook configuration hook for any other case.
+    ///
+    /// This hook is executed for events that do not have a dedicated
+    /// hook configured.
     pub any: Option<WatchHook>,
 }
+
+impl WatchEnvelopeConfig {
+    /// Find the watch hook matching the given event kind.
+    ///
+    /// Falls back to the [`any`](Self::any) hook.
+    pub fn find_hook(&self, kind: EnvelopeEventKind) -> Option<&WatchHook> {
+        let hook = match kind {
+            EnvelopeEventKind::Received => self.received.as_ref(),
+            EnvelopeEventKind::FlagsChanged => self.flags_changed.as_ref(),
+            EnvelopeEventKind::Removed => self.removed.as_ref(),
+            EnvelopeEventKind::Moved => self.moved.as_ref(),
+        };
+
+        hook.or(self.any.as_ref())
+    }
+}
@@ -0,0 +1,367 @@
+//! # Envelope event
+//!
+//! Module dedicated to envelope events. Events are computed by
+//! comparing the envelopes of a watched folder before and after a
+//! change, then they are either sent to an [`EnvelopeEventStream`]
+//! or used to execute watch hooks.
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+use tokio::sync::mpsc;
+
+use crate::{envelope::Envelope, flag::Flags};
+
+/// The envelope event kind.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum EnvelopeEventKind {
+    Received,
+    FlagsChanged,
+    Removed,
+    Moved,
+}
+
+impl fmt::Display for EnvelopeEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Received => write!(f, "received"),
+            Self::FlagsChanged => write!(f, "flags-changed"),
+            Self::Removed => write!(f, "removed"),
+            Self::Moved => write!(f, "moved"),
+        }
+    }
+}
+
+/// The envelope event.
+///
+/// Represents a change that occurred in a watched folder.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EnvelopeEvent {
+    /// A new envelope has been received in the given folder.
+    Received { folder: String, envelope: Envelope },
+
+    /// The flags of the given envelope changed.
+    FlagsChanged {
+        folder: String,
+        envelope: Envelope,
+        old: Flags,
+        new: Flags,
+    },
+
+    /// The given envelope has been removed from the given folder.
+    Removed { folder: String, envelope: Envelope },
+
+    /// The given envelope has been moved from one watched folder to
+    /// another.
+    ///
+    /// The envelope is the one found in the target folder.
+    Moved {
+        from: String,
+        to: String,
+        envelope: Envelope,
+    },
+}
+
+impl EnvelopeEvent {
+    pub fn kind(&self) -> EnvelopeEventKind {
+        match self {
+            Self::Received { .. } => EnvelopeEventKind::Received,
+            Self::FlagsChanged { .. } => EnvelopeEventKind::FlagsChanged,
+            Self::Removed { .. } => EnvelopeEventKind::Removed,
+            Self::Moved { .. } => EnvelopeEventKind::Moved,
+        }
+    }
+
+    /// Return the envelope concerned by the event.
+    pub fn envelope(&self) -> &Envelope {
+        match self {
+            Self::Received { envelope, .. } => envelope,
+            Self::FlagsChanged { envelope, .. } => envelope,
+            Self::Removed { envelope, .. } => envelope,
+            Self::Moved { envelope, .. } => envelope,
+        }
+    }
+
+    /// Return the folder concerned by the event.
+    ///
+    /// For moved envelopes, this is the target folder.
+    pub fn folder(&self) -> &str {
+        match self {
+            Self::Received { folder, .. } => folder,
+            Self::FlagsChanged { folder, .. } => folder,
+            Self::Removed { folder, .. } => folder,
+            Self::Moved { to, .. } => to,
+        }
+    }
+
+    /// Compute the events that turn the given previous envelopes
+    /// into the given next envelopes.
+    ///
+    /// Envelopes are indexed by identifier. Events are sorted by
+    /// envelope identifier, removals first. Identifiers are compared
+    /// numerically when they are numbers (like IMAP UIDs), so that
+    /// events follow the arrival order of messages.
+    pub fn diff(
+        folder: &str,
+        prev: &HashMap<String, Envelope>,
+        next: &HashMap<String, Envelope>,
+    ) -> Vec<Self> {
+        let mut removed: Vec<_> = prev
+            .iter()
+            .filter(|(id, _)| !next.contains_key(*id))
+            .collect();
+        removed.sort_by(|(a, _), (b, _)| cmp_ids(a, b));
+
+        let mut changed: Vec<_> = next.iter().collect();
+        changed.sort_by(|(a, _), (b, _)| cmp_ids(a, b));
+
+        let removed = removed.into_iter().map(|(_, envelope)| Self::Removed {
+            folder: folder.to_owned(),
+            envelope: envelope.clone(),
+        });
+
+        let changed = changed
+            .into_iter()
+            .filter_map(|(id, envelope)| match prev.get(id) {
+                None => Some(Self::Received {
+                    folder: folder.to_owned(),
+                    envelope: envelope.clone(),
+                }),
+                Some(prev) if prev.flags != envelope.flags => Some(Self::FlagsChanged {
+                    folder: folder.to_owned(),
+                    envelope: envelope.clone(),
+                    old: prev.flags.clone(),
+                    new: envelope.flags.clone(),
+                }),
+                Some(_) => None,
+            });
+
+        removed.chain(changed).collect()
+    }
+
+    /// Merge removals and receptions of the same message across
+    /// different folders into moves.
+    ///
+    /// Messages are matched using their Message-ID, so envelopes
+    /// without Message-ID are never considered as moved.
+    pub fn detect_moves(events: Vec<Self>) -> Vec<Self> {
+        let mut removed: HashMap<String, (usize, String)> = HashMap::new();
+
+        for (i, event) in events.iter().enumerate() {
+            if let Self::Removed { folder, envelope } = event {
+                if !envelope.message_id.is_empty() {
+                    removed.insert(envelope.message_id.clone(), (i, folder.clone()));
+                }
+            }
+        }
+
+        let mut moved = Vec::new();
+        let mut events: Vec<Option<Self>> = events.into_iter().map(Some).collect();
+
+        for i in 0..events.len() {
+            let Some(Self::Received { folder, envelope }) = &events[i] else {
+                continue;
+            };
+
+            let Some((j, from)) = removed.get(&envelope.message_id) else {
+                continue;
+            };
+
+            if from == folder {
+                continue;
+            }
+
+            let (j, from) = (*j, from.clone());
+            removed.remove(&envelope.message_id);
+
+            if let Some(Self::Received { folder, envelope }) = events[i].take() {
+                events[j] = None;
+                moved.push((
+                    i,
+                    Self::Moved {
+                        from,
+                        to: folder,
+                        envelope,
+                    },
+                ));
+            }
+        }
+
+        for (i, event) in moved {
+            events[i] = Some(event);
+        }
+
+        events.into_iter().flatten().collect()
+    }
+}
+
+/// Compare the given envelope identifiers.
+///
+/// Numeric identifiers are compared numerically and come first,
+/// other identifiers are compared as strings.
+fn cmp_ids(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Create a new envelope event channel.
+///
+/// The sender is given to a watcher, the stream is consumed by the
+/// library user.
+pub fn channel() -> (EnvelopeEventSender, EnvelopeEventStream) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (EnvelopeEventSender(tx), EnvelopeEventStream(rx))
+}
+
+/// The sending half of an envelope event channel.
+#[derive(Clone, Debug)]
+pub struct EnvelopeEventSender(mpsc::UnboundedSender<EnvelopeEvent>);
+
+impl EnvelopeEventSender {
+    /// Send the given event.
+    ///
+    /// Returns `false` if the stream has been dropped.
+    pub fn send(&self, event: EnvelopeEvent) -> bool {
+        self.0.send(event).is_ok()
+    }
+}
+
+/// The stream of envelope events.
+///
+/// The stream ends once the watcher stops.
+#[derive(Debug)]
+pub struct EnvelopeEventStream(mpsc::UnboundedReceiver<EnvelopeEvent>);
+
+impl Stream for EnvelopeEventStream {
+    type Item = EnvelopeEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().0.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{EnvelopeEvent, EnvelopeEventKind};
+    use crate::{
+        envelope::Envelope,
+        flag::{Flag, Flags},
+    };
+
+    fn envelope(id: &str, message_id: &str, flags: impl IntoIterator<Item = Flag>) -> Envelope {
+        Envelope {
+            id: id.into(),
+            message_id: message_id.into(),
+            flags: Flags::from_iter(flags),
+            ..Default::default()
+        }
+    }
+
+    fn index(envelopes: impl IntoIterator<Item = Envelope>) -> HashMap<String, Envelope> {
+        HashMap::from_iter(envelopes.into_iter().map(|e| (e.id.clone(), e)))
+    }
+
+    #[test]
+    fn diff() {
+        let prev = index([
+            envelope("1", "<a@localhost>", []),
+            envelope("2", "<b@localhost>", []),
+            envelope("3", "<c@localhost>", [Flag::Seen]),
+        ]);
+
+        let next = index([
+            envelope("2", "<b@localhost>", [Flag::Seen]),
+            envelope("3", "<c@localhost>", [Flag::Seen]),
+            envelope("4", "<d@localhost>", []),
+        ]);
+
+        let events = EnvelopeEvent::diff("INBOX", &prev, &next);
+        let kinds: Vec<_> = events.iter().map(EnvelopeEvent::kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                EnvelopeEventKind::Removed,
+                EnvelopeEventKind::FlagsChanged,
+                EnvelopeEventKind::Received,
+            ]
+        );
+
+        assert_eq!(
+            events[1],
+            EnvelopeEvent::FlagsChanged {
+                folder: "INBOX".into(),
+                envelope: envelope("2", "<b@localhost>", [Flag::Seen]),
+                old: Flags::default(),
+                new: Flags::from_iter([Flag::Seen]),
+            }
+        );
+    }
+
+    #[test]
+    fn diff_numeric_ids() {
+        let prev = index([]);
+        let next = index([
+            envelope("10", "<b@localhost>", []),
+            envelope("9", "<a@localhost>", []),
+            envelope("100", "<c@localhost>", []),
+        ]);
+
+        let events = EnvelopeEvent::diff("INBOX", &prev, &next);
+        let ids: Vec<_> = events.iter().map(|e| e.envelope().id.as_str()).collect();
+
+        assert_eq!(ids, vec!["9", "10", "100"]);
+    }
+
+    #[test]
+    fn detect_moves() {
+        let events = vec![
+            EnvelopeEvent::Removed {
+                folder: "INBOX".into(),
+                envelope: envelope("1", "<a@localhost>", []),
+            },
+            EnvelopeEvent::Removed {
+                folder: "INBOX".into(),
+                envelope: envelope("2", "<b@localhost>", []),
+            },
+            EnvelopeEvent::Received {
+                folder: "Archives".into(),
+                envelope: envelope("7", "<a@localhost>", []),
+            },
+        ];
+
+        let events = EnvelopeEvent::detect_moves(events);
+
+        assert_eq!(
+            events,
+            vec![
+                EnvelopeEvent::Removed {
+                    folder: "INBOX".into(),
+                    envelope: envelope("2", "<b@localhost>", []),
+                },
+                EnvelopeEvent::Moved {
+                    from: "INBOX".into(),
+                    to: "Archives".into(),
+                    envelope: envelope("7", "<a@localhost>", []),
+                },
+            ]
+        );
+    }
+}
@@ -1,21 +1,144 @@
-use std::collections::HashMap;
+#[cfg(feature = "notify")]
+use std::sync::Arc;
+use std::{collections::HashMap, num::NonZeroU32, time::Duration};
 
 use async_trait::async_trait;
-use tokio::sync::oneshot::{Receiver, Sender};
+use tokio::{
+    select,
+    sync::oneshot::{Receiver, Sender},
+    time::sleep,
+};
 use tracing::{debug, info};
 use utf7_imap::encode_utf7_imap as encode_utf7;
 
-use super::WatchEnvelopes;
-use crate::{envelope::Envelope, imap::ImapContext, AnyResult};
+use super::{EnvelopeEvent, EnvelopeEventSender, WatchEnvelopes};
+#[cfg(feature = "notify")]
+use crate::watch::notify::WatchNotifier;
+use crate::{
+    envelope::Envelope,
+    folder::status::FolderStatus,
+    imap::{ImapClient, ImapContext},
+    quota::QuotaWatcher,
+    AnyResult,
+};
+
+/// The default interval used to poll the status of watched folders,
+/// in seconds.
+const DEFAULT_POLL_INTERVAL: u64 = 60;
+
+/// The state of a watched folder.
+struct WatchedFolder {
+    /// The folder name, after alias resolution.
+    alias: String,
+    /// The UTF-7 encoded folder name.
+    name: String,
+    status: FolderStatus,
+    envelopes: FolderEnvelopes,
+}
+
+/// The envelopes of a watched folder, alongside the values needed to
+/// refresh them incrementally.
+#[derive(Clone, Debug, Default)]
+struct FolderEnvelopes {
+    uid_validity: Option<NonZeroU32>,
+    uid_next: Option<NonZeroU32>,
+    envelopes: HashMap<String, Envelope>,
+}
+
+impl FolderEnvelopes {
+    fn new(
+        uid_validity: Option<NonZeroU32>,
+        uid_next: Option<NonZeroU32>,
+        envelopes: HashMap<String, Envelope>,
+    ) -> Self {
+        Self {
+            uid_validity,
+            uid_next,
+            envelopes,
+        }
+    }
+
+    /// Examine the given folder then fetch all its envelopes.
+    async fn fetch(client: &mut ImapClient, folder_encoded: &str) -> AnyResult<Self> {
+        let data = client.examine_mailbox(folder_encoded).await?;
+
+        let envelopes = if data.exists.unwrap_or_default() == 0 {
+            Default::default()
+        } else {
+            let envelopes = client.fetch_all_envelopes().await?;
+            HashMap::from_iter(envelopes.into_iter().map(|e| (e.id.clone(), e)))
+        };
+
+        Ok(Self::new(data.uid_validity, data.uid_next, envelopes))
+    }
+
+    /// Examine the given folder then refresh envelopes
+    /// incrementally.
+    ///
+    /// Only envelopes of messages having a UID greater than or equal
+    /// to the previous UIDNEXT are fetched. Other messages are
+    /// refreshed by fetching their flags only, which also reveals
+    /// removed messages. All envelopes are fetched again when the
+    /// UIDVALIDITY changed.
+    async fn refresh(&self, client: &mut ImapClient, folder_encoded: &str) -> AnyResult<Self> {
+        let data = client.examine_mailbox(folder_encoded).await?;
+
+        let (Some(prev_uid_next), Some(uid_next)) = (self.uid_next, data.uid_next) else {
+            debug!("UIDNEXT not available, fetching all envelopes");
+            return Self::fetch(client, folder_encoded).await;
+        };
+
+        if self.uid_validity != data.uid_validity {
+            debug!("UIDVALIDITY changed, fetching all envelopes");
+            return Self::fetch(client, folder_encoded).await;
+        }
+
+        let mut envelopes = HashMap::with_capacity(self.envelopes.len());
+
+        if data.exists.unwrap_or_default() > 0 {
+            let flags = client.fetch_flags("1:*".try_into().unwrap()).await?;
+
+            for (id, flags) in flags {
+                if let Some(envelope) = self.envelopes.get(&id) {
+                    let mut envelope = envelope.clone();
+                    envelope.flags = flags;
+                    envelopes.insert(id, envelope);
+                }
+            }
+
+            if uid_next > prev_uid_next {
+                debug!("fetching envelopes from UID {prev_uid_next}");
+                let uids = format!("{prev_uid_next}:*").as_str().try_into().unwrap();
+                let next_envelopes = client.fetch_envelopes_map(uids).await?;
+
+                // the range `n:*` always contains the message with the
+                // greatest UID, even if it is lower than `n`
+                envelopes.extend(next_envelopes.into_iter().filter(|(id, _)| {
+                    id.parse::<u32>()
+                        .map(|uid| uid >= prev_uid_next.get())
+                        .unwrap_or_default()
+                }));
+            }
+        }
+
+        Ok(Self::new(data.uid_validity, Some(uid_next), envelopes))
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct WatchImapEnvelopes {
     ctx: ImapContext,
+    #[cfg(feature = "notify")]
+    notifier: Arc<WatchNotifier>,
 }
 
 impl WatchImapEnvelopes {
     pub fn new(ctx: &ImapContext) -> Self {
-        Self { ctx: ctx.clone() }
+        Self {
+            ctx: ctx.clone(),
+            #[cfg(feature = "notify")]
+            notifier: Default::default(),
+        }
     }
 
     pub fn new_boxed(ctx: &ImapContext) -> Box<dyn WatchEnvelopes> {
@@ -29,6 +152,7 @@ pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn WatchEnvelopes>> {
     pub async fn watch_envelopes_loop(
         &self,
         folder: &str,
+        sender: Option<&EnvelopeEventSender>,
         wait_for_shutdown_request: &mut Receiver<()>,
     ) -> AnyResult<()> {
         info!("watching imap folder {folder} for envelope changes");
@@ -40,39 +164,194 @@ pub async fn watch_envelopes_loop(
         let folder_encoded = encode_utf7(folder.clone());
         debug!("utf7 encoded folder: {folder_encoded}");
 
-        let envelopes_count = client
-            .examine_mailbox(folder_encoded)
-            .await?
-            .exists
-            .unwrap() as usize;
+        let mut envelopes = FolderEnvelopes::fetch(&mut client, &folder_encoded).await?;
 
-        let envelopes = if envelopes_count == 0 {
-            Default::default()
-        } else {
-            client.fetch_all_envelopes().await?
-        };
-
-        let mut envelopes: HashMap<String, Envelope> =
-            HashMap::from_iter(envelopes.into_iter().map(|e| (e.id.clone(), e)));
+        let mut quota = QuotaWatcher::default();
 
         loop {
             info!("starting new IMAP IDLE loop…");
             client.idle(wait_for_shutdown_request).await?;
             info!("received IDLE change notification or timeout");
 
-            let next_envelopes = client.fetch_all_envelopes().await?;
-            let next_envelopes: HashMap<String, Envelope> =
-                HashMap::from_iter(next_envelopes.into_iter().map(|e| (e.id.clone(), e)));
+            let next_envelopes = envelopes.refresh(&mut client, &folder_encoded).await?;
+            let events =
+                EnvelopeEvent::diff(&folder, &envelopes.envelopes, &next_envelopes.envelopes);
+            self.exec_hooks(config, events, sender).await;
 
-            self.exec_hooks(config, &envelopes, &next_envelopes).await;
+            let interval = config.find_quota_watch_interval();
+            if interval.is_some_and(|interval| quota.is_due(interval)) {
+                match client.get_quota_root(&folder_encoded).await {
+                    Ok(next_quota) => {
+                        config.exec_quota_hook(quota.last(), &next_quota).await;
+                        quota.checked(Some(next_quota));
+                    }
+                    Err(err) => {
+                        debug!(?err, "cannot get imap quota, skipping quota hook");
+                        quota.checked(None);
+                    }
+                }
+            }
 
             envelopes = next_envelopes;
         }
     }
+
+    /// Watch the given folders by polling their status.
+    ///
+    /// Folders are polled one after the other, every
+    /// [`ImapWatchConfig::find_poll_interval`](crate::imap::config::ImapWatchConfig::find_poll_interval)
+    /// seconds. A client is taken from the pool for each polling
+    /// round only, so it remains available for other features in
+    /// between.
+    ///
+    /// TODO: watch folders using NOTIFY SET (STATUS) (RFC 5465) when
+    /// advertised, and keep polling as a fallback, once supported by
+    /// imap-types, which can neither encode the NOTIFY command nor
+    /// parse its responses yet.
+    pub async fn watch_folders_envelopes_with_polling(
+        &self,
+        folders: &[String],
+        sender: Option<&EnvelopeEventSender>,
+        wait_for_shutdown_request: &mut Receiver<()>,
+    ) -> AnyResult<()> {
+        info!("watching imap folders {folders:?} for envelope changes using STATUS polling");
+
+        let config = &self.ctx.account_config;
+
+        let interval = self
+            .ctx
+            .imap_config
+            .find_watch_poll_interval()
+            .unwrap_or(DEFAULT_POLL_INTERVAL);
+        let interval = Duration::from_secs(interval);
+
+        let mut watched = Vec::with_capacity(folders.len());
+        {
+            let mut client = self.ctx.client().await;
+            for folder in folders {
+                watched.push(self.init_watched_folder(&mut client, folder).await?);
+            }
+        }
+
+        loop {
+            select! {
+                _ = sleep(interval) => (),
+                _ = &mut *wait_for_shutdown_request => {
+                    debug!("shutdown requested, stopping polling…");
+                    break Ok(());
+                }
+            }
+
+            let mut client = self.ctx.client().await;
+            let events = poll_watched_folders(&mut client, &mut watched).await?;
+            drop(client);
+
+            self.exec_hooks(config, events, sender).await;
+        }
+    }
+
+    async fn watch_folders_loop(
+        &self,
+        folders: &[String],
+        sender: Option<&EnvelopeEventSender>,
+        wait_for_shutdown_request: &mut Receiver<()>,
+    ) -> AnyResult<()> {
+        match folders {
+            [folder] => {
+                self.watch_envelopes_loop(folder, sender, wait_for_shutdown_request)
+                    .await
+            }
+            folders => {
+                self.watch_folders_envelopes_with_polling(
+                    folders,
+                    sender,
+                    wait_for_shutdown_request,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn init_watched_folder(
+        &self,
+        client: &mut ImapClient,
+        folder: &str,
+    ) -> AnyResult<WatchedFolder> {
+        let config = &self.ctx.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        let status = client.status_mailbox(&folder_encoded).await?;
+        let envelopes = FolderEnvelopes::fetch(client, &folder_encoded).await?;
+
+        Ok(WatchedFolder {
+            alias: folder,
+            name: folder_encoded,
+            status,
+            envelopes,
+        })
+    }
+}
+
+/// Poll the status of the given watched folders, then refresh the
+/// envelopes of the changed ones.
+async fn poll_watched_folders(
+    client: &mut ImapClient,
+    watched: &mut [WatchedFolder],
+) -> AnyResult<Vec<EnvelopeEvent>> {
+    let mut changes = Vec::new();
+
+    for (i, folder) in watched.iter().enumerate() {
+        let status = client.status_mailbox(&folder.name).await?;
+
+        if status == folder.status {
+            continue;
+        }
+
+        debug!("folder {} changed, refreshing envelopes", folder.name);
+        let next_envelopes = folder.envelopes.refresh(client, &folder.name).await?;
+        changes.push((i, status, next_envelopes));
+    }
+
+    let events = diff_folders(changes.iter().map(|(i, _, next_envelopes)| {
+        let folder = &watched[*i];
+        (folder.alias.as_str(), &folder.envelopes, next_envelopes)
+    }));
+
+    for (i, status, envelopes) in changes {
+        watched[i].status = status;
+        watched[i].envelopes = envelopes;
+    }
+
+    Ok(events)
+}
+
+/// Compute the events of the given changed folders.
+///
+/// Folders of the same polling round are diffed together, so
+/// messages moved from a watched folder to another are detected.
+fn diff_folders<'a>(
+    changes: impl IntoIterator<Item = (&'a str, &'a FolderEnvelopes, &'a FolderEnvelopes)>,
+) -> Vec<EnvelopeEvent> {
+    let events = changes
+        .into_iter()
+        .flat_map(|(folder, prev, next)| {
+            EnvelopeEvent::diff(folder, &prev.envelopes, &next.envelopes)
+        })
+        .collect();
+
+    EnvelopeEvent::detect_moves(events)
 }
 
 #[async_trait]
 impl WatchEnvelopes for WatchImapEnvelopes {
+    #[cfg(feature = "notify")]
+    fn notifier(&self) -> Option<&WatchNotifier> {
+        Some(&self.notifier)
+    }
+
     async fn watch_envelopes(
         &self,
         folder: &str,
@@ -80,7 +359,38 @@ async fn watch_envelopes(
         shutdown: Sender<()>,
     ) -> AnyResult<()> {
         let res = self
-            .watch_envelopes_loop(folder, &mut wait_for_shutdown_request)
+            .watch_envelopes_loop(folder, None, &mut wait_for_shutdown_request)
+            .await;
+
+        shutdown.send(()).unwrap();
+
+        res
+    }
+
+    async fn watch_folders_envelopes(
+        &self,
+        folders: &[String],
+        mut wait_for_shutdown_request: Receiver<()>,
+        shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        let res = self
+            .watch_folders_loop(folders, None, &mut wait_for_shutdown_request)
+            .await;
+
+        shutdown.send(()).unwrap();
+
+        res
+    }
+
+    async fn watch_envelopes_events(
+        &self,
+        folders: &[String],
+        events: EnvelopeEventSender,
+        mut wait_for_shutdown_request: Receiver<()>,
+        shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        let res = self
+            .watch_folders_loop(folders, Some(&events), &mut wait_for_shutdown_request)
             .await;
 
         shutdown.send(()).unwrap();
@@ -88,3 +398,64 @@ async fn watch_envelopes(
         res
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{diff_folders, FolderEnvelopes};
+    use crate::envelope::{watch::EnvelopeEvent, Envelope};
+
+    fn folder_envelopes(
+        envelopes: impl IntoIterator<Item = (&'static str, &'static str)>,
+    ) -> FolderEnvelopes {
+        let envelopes = envelopes.into_iter().map(|(id, message_id)| {
+            let envelope = Envelope {
+                id: id.into(),
+                message_id: message_id.into(),
+                ..Default::default()
+            };
+            (envelope.id.clone(), envelope)
+        });
+
+        FolderEnvelopes {
+            envelopes: HashMap::from_iter(envelopes),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn diff_folders_together() {
+        let inbox = folder_envelopes([("1", "<a@localhost>"), ("2", "<b@localhost>")]);
+        let next_inbox = folder_envelopes([("2", "<b@localhost>")]);
+        let archives = folder_envelopes([]);
+        let next_archives = folder_envelopes([("5", "<a@localhost>")]);
+
+        let events = diff_folders([
+            ("INBOX", &inbox, &next_inbox),
+            ("Archives", &archives, &next_archives),
+        ]);
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            EnvelopeEvent::Moved { from, to, envelope }
+                if from == "INBOX" && to == "Archives" && envelope.id == "5"
+        ));
+    }
+
+    #[test]
+    fn diff_folders_separately() {
+        let inbox = folder_envelopes([("1", "<a@localhost>")]);
+        let next_inbox = folder_envelopes([]);
+
+        let events = diff_folders([("INBOX", &inbox, &next_inbox)]);
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            EnvelopeEvent::Removed { folder, envelope }
+                if folder == "INBOX" && envelope.id == "1"
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "notify")]
+use std::sync::Arc;
 use std::{collections::HashMap, sync::mpsc};
 
 use async_trait::async_trait;
@@ -5,21 +7,30 @@
 use tokio::sync::oneshot::{Receiver, Sender};
 use tracing::{debug, info, trace};
 
-use super::WatchEnvelopes;
+use super::{EnvelopeEvent, EnvelopeEventSender, WatchEnvelopes};
+#[cfg(feature = "notify")]
+use crate::watch::notify::WatchNotifier;
 use crate::{
     email::error::Error,
     envelope::{Envelope, Envelopes},
     maildir::MaildirContextSync,
+    quota::{Quota, QuotaWatcher},
     AnyResult,
 };
 
 pub struct WatchMaildirEnvelopes {
     ctx: MaildirContextSync,
+    #[cfg(feature = "notify")]
+    notifier: Arc<WatchNotifier>,
 }
 
 impl WatchMaildirEnvelopes {
     pub fn new(ctx: &MaildirContextSync) -> Self {
-        Self { ctx: ctx.clone() }
+        Self {
+            ctx: ctx.clone(),
+            #[cfg(feature = "notify")]
+            notifier: Default::default(),
+        }
     }
 
     pub fn new_boxed(ctx: &MaildirContextSync) -> Box<dyn WatchEnvelopes> {
@@ -29,15 +40,11 @@ pub fn new_boxed(ctx: &MaildirContextSync) -> Box<dyn WatchEnvelopes> {
     pub fn some_new_boxed(ctx: &MaildirContextSync) -> Option<Box<dyn WatchEnvelopes>> {
         Some(Self::new_boxed(ctx))
     }
-}
 
-#[async_trait]
-impl WatchEnvelopes for WatchMaildirEnvelopes {
-    async fn watch_envelopes(
+    pub async fn watch_envelopes_loop(
         &self,
         folder: &str,
-        _wait_for_shutdown_request: Receiver<()>,
-        _shutdown: Sender<()>,
+        sender: Option<&EnvelopeEventSender>,
     ) -> AnyResult<()> {
         info!("maildir: watching folder {folder} for email changes");
 
@@ -58,6 +65,9 @@ async fn watch_envelopes(
             .map_err(Error::NotifyFailure)?;
         debug!("watching maildir folder {folder:?}…");
 
+        let mut quota = QuotaWatcher::default();
+        let quota_config = config.quota.clone().unwrap_or_default();
+
         for res in rx {
             match res {
                 Ok(_evt) => {
@@ -68,7 +78,30 @@ async fn watch_envelopes(
                     let next_envelopes: HashMap<String, Envelope> =
                         HashMap::from_iter(next_envelopes.into_iter().map(|e| (e.id.clone(), e)));
 
-                    self.exec_hooks(config, &envelopes, &next_envelopes).await;
+                    let events = EnvelopeEvent::diff(folder, &envelopes, &next_envelopes);
+                    self.exec_hooks(config, events, sender).await;
+
+                    // without configured limit, the maildir quota
+                    // cannot cross any threshold
+                    let interval = config.find_quota_watch_interval();
+                    let is_due = interval.is_some_and(|interval| quota.is_due(interval));
+                    if is_due && quota_config.has_local_limit() {
+                        let root = session.root.path().to_owned();
+                        match tokio::task::spawn_blocking(move || Quota::from_maildir_root(root))
+                            .await
+                        {
+                            Ok(mut next_quota) => {
+                                quota_config.apply_local_limits(&mut next_quota);
+                                config.exec_quota_hook(quota.last(), &next_quota).await;
+                                quota.checked(Some(next_quota));
+                            }
+                            Err(_err) => {
+                                debug!("cannot compute maildir quota, skipping it: {_err}");
+                                debug!("{_err:?}");
+                                quota.checked(None);
+                            }
+                        }
+                    }
 
                     envelopes = next_envelopes;
                 }
@@ -82,3 +115,33 @@ async fn watch_envelopes(
         Ok(())
     }
 }
+
+#[async_trait]
+impl WatchEnvelopes for WatchMaildirEnvelopes {
+    #[cfg(feature = "notify")]
+    fn notifier(&self) -> Option<&WatchNotifier> {
+        Some(&self.notifier)
+    }
+
+    async fn watch_envelopes(
+        &self,
+        folder: &str,
+        _wait_for_shutdown_request: Receiver<()>,
+        _shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        self.watch_envelopes_loop(folder, None).await
+    }
+
+    async fn watch_envelopes_events(
+        &self,
+        folders: &[String],
+        events: EnvelopeEventSender,
+        _wait_for_shutdown_request: Receiver<()>,
+        _shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        match folders {
+            [folder] => self.watch_envelopes_loop(folder, Some(&events)).await,
+            folders => Err(Error::WatchMultipleFoldersNotSupportedError(folders.len()).into()),
+        }
+    }
+}
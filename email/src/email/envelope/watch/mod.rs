@@ -1,16 +1,21 @@
 pub mod config;
+pub mod event;
 #[cfg(feature = "imap")]
 pub mod imap;
 #[cfg(feature = "maildir")]
 pub mod maildir;
-
-use std::collections::HashMap;
+#[cfg(feature = "notmuch")]
+pub mod notmuch;
 
 use async_trait::async_trait;
 use tokio::sync::oneshot::{Receiver, Sender};
 use tracing::{debug, info};
 
-use crate::{account::config::AccountConfig, envelope::Envelope, AnyResult};
+#[doc(inline)]
+pub use self::event::{EnvelopeEvent, EnvelopeEventKind, EnvelopeEventSender, EnvelopeEventStream};
+#[cfg(feature = "notify")]
+use crate::watch::notify::WatchNotifier;
+use crate::{account::config::AccountConfig, email::error::Error, AnyResult};
 
 #[async_trait]
 pub trait WatchEnvelopes: Send + Sync {
@@ -22,24 +27,127 @@ async fn watch_envelopes(
         shutdown: Sender<()>,
     ) -> AnyResult<()>;
 
+    /// Watch the given folders for envelopes changes.
+    ///
+    /// Backends that do not support watching multiple folders at
+    /// once can only watch a single folder.
+    async fn watch_folders_envelopes(
+        &self,
+        folders: &[String],
+        wait_for_shutdown_request: Receiver<()>,
+        shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        match folders {
+            [folder] => {
+                self.watch_envelopes(folder, wait_for_shutdown_request, shutdown)
+                    .await
+            }
+            folders => Err(Error::WatchMultipleFoldersNotSupportedError(folders.len()).into()),
+        }
+    }
+
+    /// Watch the given folders for envelopes changes, and send the
+    /// resulting events to the given sender.
+    ///
+    /// Watch hooks are executed as well. See [`event::channel`] to
+    /// create the sender alongside its [`EnvelopeEventStream`].
+    async fn watch_envelopes_events(
+        &self,
+        _folders: &[String],
+        _events: EnvelopeEventSender,
+        _wait_for_shutdown_request: Receiver<()>,
+        _shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        Err(Error::WatchEnvelopeEventsNotSupportedError.into())
+    }
+
+    /// Get the notifier owned by the watcher, if any.
+    ///
+    /// Without notifier, watch notifications cannot be batched.
+    #[cfg(feature = "notify")]
+    fn notifier(&self) -> Option<&WatchNotifier> {
+        None
+    }
+
+    /// Execute the watch hooks matching the given events, then send
+    /// them to the given sender, if any.
     async fn exec_hooks(
         &self,
         config: &AccountConfig,
-        prev_envelopes: &HashMap<String, Envelope>,
-        next_envelopes: &HashMap<String, Envelope>,
+        events: Vec<EnvelopeEvent>,
+        sender: Option<&EnvelopeEventSender>,
     ) {
         debug!("executing watch hooks…");
-        for (id, envelope) in next_envelopes {
-            // a new envelope has been added
-            if !prev_envelopes.contains_key(id) {
-                info!(id, "new message detected");
-                debug!("processing received envelope event…");
-                config.exec_received_envelope_hook(envelope).await;
-            } else {
-                // TODO
-                // debug!("processing any envelope event…");
-                // config.exec_any_envelope_hook(envelope).await;
+
+        for event in events {
+            let id = &event.envelope().id;
+            let kind = event.kind();
+            info!(
+                id,
+                folder = event.folder(),
+                "{kind} envelope event detected"
+            );
+
+            #[cfg(feature = "notify")]
+            config
+                .exec_envelope_event_hook(&event, self.notifier())
+                .await;
+            #[cfg(not(feature = "notify"))]
+            config.exec_envelope_event_hook(&event).await;
+
+            if let Some(sender) = sender {
+                if !sender.send(event) {
+                    debug!("envelope event stream dropped, skipping event");
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use tokio::sync::oneshot::{self, Receiver, Sender};
+
+    use super::WatchEnvelopes;
+    use crate::AnyResult;
+
+    struct WatchSingleFolder;
+
+    #[async_trait]
+    impl WatchEnvelopes for WatchSingleFolder {
+        async fn watch_envelopes(
+            &self,
+            _folder: &str,
+            _wait_for_shutdown_request: Receiver<()>,
+            shutdown: Sender<()>,
+        ) -> AnyResult<()> {
+            shutdown.send(()).unwrap();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn watch_folders_envelopes_fallback() {
+        let folders = vec![String::from("INBOX")];
+        let (_, wait_for_shutdown_request) = oneshot::channel();
+        let (shutdown, shutdown_received) = oneshot::channel();
+
+        WatchSingleFolder
+            .watch_folders_envelopes(&folders, wait_for_shutdown_request, shutdown)
+            .await
+            .unwrap();
+
+        assert!(shutdown_received.await.is_ok());
+
+        let folders = vec![String::from("INBOX"), String::from("Archives")];
+        let (_, wait_for_shutdown_request) = oneshot::channel();
+        let (shutdown, _) = oneshot::channel();
+
+        let res = WatchSingleFolder
+            .watch_folders_envelopes(&folders, wait_for_shutdown_request, shutdown)
+            .await;
+
+        assert!(res.is_err());
+    }
+}
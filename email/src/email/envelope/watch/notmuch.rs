@@ -0,0 +1,268 @@
+#[cfg(feature = "notify")]
+use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use tokio::{
+    select,
+    sync::oneshot::{Receiver, Sender},
+    time::sleep,
+};
+use tracing::{debug, info};
+
+use super::{EnvelopeEvent, EnvelopeEventSender, WatchEnvelopes};
+#[cfg(feature = "notify")]
+use crate::watch::notify::WatchNotifier;
+use crate::{
+    email::error::Error,
+    envelope::{Envelope, Envelopes},
+    folder::FolderKind,
+    notmuch::{NotmuchContext, NotmuchContextSync},
+    AnyResult,
+};
+
+/// The default interval used to poll the Notmuch database revision,
+/// in seconds.
+const DEFAULT_POLL_INTERVAL: u64 = 10;
+
+/// The envelopes of a watched folder, alongside the database
+/// revision they have been read at.
+#[derive(Clone, Debug, Default)]
+struct FolderEnvelopes {
+    uuid: String,
+    revision: u64,
+    envelopes: HashMap<String, Envelope>,
+}
+
+impl FolderEnvelopes {
+    /// Read all the envelopes matching the given folder query.
+    fn fetch(ctx: &NotmuchContext, query: &str) -> AnyResult<Self> {
+        let db = ctx.open_db()?;
+        let revision = db.revision();
+
+        let msgs = db
+            .create_query(query)
+            .map_err(Error::NotMuchFailure)?
+            .search_messages()
+            .map_err(Error::NotMuchFailure)?;
+        let envelopes = Envelopes::from_notmuch_msgs(msgs)
+            .into_iter()
+            .map(|e| (e.id.clone(), e))
+            .collect();
+
+        db.close().map_err(Error::NotMuchFailure)?;
+
+        Ok(Self {
+            uuid: revision.uuid,
+            revision: revision.revision as u64,
+            envelopes,
+        })
+    }
+
+    /// Refresh envelopes using the database revision.
+    ///
+    /// Only messages added or retagged since the last revision are
+    /// read, using the `lastmod:` query prefix. Removed messages do
+    /// not have any revision anymore, so they are detected by
+    /// comparing the number of messages matching the folder query.
+    /// All envelopes are read again when the database UUID
+    /// changed. Returns `None` when the revision did not change.
+    fn refresh(&self, ctx: &NotmuchContext, query: &str) -> AnyResult<Option<Self>> {
+        let db = ctx.open_db()?;
+        let revision = db.revision();
+
+        if revision.uuid != self.uuid {
+            debug!("notmuch database uuid changed, reading all envelopes");
+            db.close().map_err(Error::NotMuchFailure)?;
+            return Ok(Some(Self::fetch(ctx, query)?));
+        }
+
+        let next_revision = revision.revision as u64;
+
+        if next_revision == self.revision {
+            db.close().map_err(Error::NotMuchFailure)?;
+            return Ok(None);
+        }
+
+        let mut envelopes = self.envelopes.clone();
+
+        let lastmod_query = format!("({query}) and lastmod:{}..", self.revision + 1);
+        debug!("reading envelopes matching query {lastmod_query}");
+
+        let msgs = db
+            .create_query(&lastmod_query)
+            .map_err(Error::NotMuchFailure)?
+            .search_messages()
+            .map_err(Error::NotMuchFailure)?;
+        envelopes.extend(
+            Envelopes::from_notmuch_msgs(msgs)
+                .into_iter()
+                .map(|e| (e.id.clone(), e)),
+        );
+
+        let count = db
+            .create_query(query)
+            .map_err(Error::NotMuchFailure)?
+            .count_messages()
+            .map_err(Error::NotMuchFailure)? as usize;
+
+        if count != envelopes.len() {
+            debug!("messages have been removed, reading identifiers");
+
+            let ids: HashSet<String> = db
+                .create_query(query)
+                .map_err(Error::NotMuchFailure)?
+                .search_messages()
+                .map_err(Error::NotMuchFailure)?
+                .map(|msg| msg.id().to_string())
+                .collect();
+
+            envelopes.retain(|id, _| ids.contains(id));
+        }
+
+        db.close().map_err(Error::NotMuchFailure)?;
+
+        Ok(Some(Self {
+            uuid: revision.uuid,
+            revision: next_revision,
+            envelopes,
+        }))
+    }
+}
+
+#[derive(Clone)]
+pub struct WatchNotmuchEnvelopes {
+    ctx: NotmuchContextSync,
+    #[cfg(feature = "notify")]
+    notifier: Arc<WatchNotifier>,
+}
+
+impl WatchNotmuchEnvelopes {
+    pub fn new(ctx: &NotmuchContextSync) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            #[cfg(feature = "notify")]
+            notifier: Default::default(),
+        }
+    }
+
+    pub fn new_boxed(ctx: &NotmuchContextSync) -> Box<dyn WatchEnvelopes> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &NotmuchContextSync) -> Option<Box<dyn WatchEnvelopes>> {
+        Some(Self::new_boxed(ctx))
+    }
+
+    pub async fn watch_envelopes_loop(
+        &self,
+        folder: &str,
+        sender: Option<&EnvelopeEventSender>,
+        wait_for_shutdown_request: &mut Receiver<()>,
+    ) -> AnyResult<()> {
+        info!("watching notmuch folder {folder} for envelope changes");
+
+        let config = &self.ctx.account_config;
+
+        let interval = self
+            .ctx
+            .notmuch_config
+            .find_watch_poll_interval()
+            .unwrap_or(DEFAULT_POLL_INTERVAL);
+        let interval = Duration::from_secs(interval);
+
+        let (query, mut envelopes) = {
+            let ctx = self.ctx.lock().await;
+            let query = folder_query(&ctx, folder);
+            let envelopes = FolderEnvelopes::fetch(&ctx, &query)?;
+            (query, envelopes)
+        };
+
+        loop {
+            select! {
+                _ = sleep(interval) => (),
+                _ = &mut *wait_for_shutdown_request => {
+                    debug!("shutdown requested, stopping polling…");
+                    return Ok(());
+                }
+            }
+
+            let next_envelopes = {
+                let ctx = self.ctx.lock().await;
+                envelopes.refresh(&ctx, &query)?
+            };
+
+            let Some(next_envelopes) = next_envelopes else {
+                continue;
+            };
+
+            debug!(
+                "notmuch database revision changed from {} to {}",
+                envelopes.revision, next_envelopes.revision
+            );
+
+            let events =
+                EnvelopeEvent::diff(folder, &envelopes.envelopes, &next_envelopes.envelopes);
+            self.exec_hooks(config, events, sender).await;
+
+            envelopes = next_envelopes;
+        }
+    }
+}
+
+#[async_trait]
+impl WatchEnvelopes for WatchNotmuchEnvelopes {
+    #[cfg(feature = "notify")]
+    fn notifier(&self) -> Option<&WatchNotifier> {
+        Some(&self.notifier)
+    }
+
+    async fn watch_envelopes(
+        &self,
+        folder: &str,
+        mut wait_for_shutdown_request: Receiver<()>,
+        shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        let res = self
+            .watch_envelopes_loop(folder, None, &mut wait_for_shutdown_request)
+            .await;
+
+        shutdown.send(()).unwrap();
+
+        res
+    }
+
+    async fn watch_envelopes_events(
+        &self,
+        folders: &[String],
+        events: EnvelopeEventSender,
+        mut wait_for_shutdown_request: Receiver<()>,
+        shutdown: Sender<()>,
+    ) -> AnyResult<()> {
+        let res = match folders {
+            [folder] => {
+                self.watch_envelopes_loop(folder, Some(&events), &mut wait_for_shutdown_request)
+                    .await
+            }
+            folders => Err(Error::WatchMultipleFoldersNotSupportedError(folders.len()).into()),
+        };
+
+        shutdown.send(()).unwrap();
+
+        res
+    }
+}
+
+/// Build the Notmuch query matching messages of the given folder.
+fn folder_query(ctx: &NotmuchContext, folder: &str) -> String {
+    let folder = ctx.account_config.get_folder_alias(folder);
+
+    if ctx.maildirpp() && FolderKind::matches_inbox(&folder) {
+        String::from("folder:\"\"")
+    } else {
+        format!("folder:{folder:?}")
+    }
+}
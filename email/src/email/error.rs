@@ -109,6 +109,9 @@ pub enum Error {
     #[cfg(feature = "maildir")]
     #[error("cannot add maildir message to folder {1} with flags {2}")]
     StoreWithFlagsMaildirError(#[source] maildirs::Error, String, Flags),
+    #[cfg(feature = "maildir")]
+    #[error("cannot set internal date of maildir message at {1}")]
+    SetMaildirMessageDateError(#[source] io::Error, PathBuf),
     #[error("cannot get added imap message uid from range {0}")]
     GetAddedMessageUidFromRangeImapError(String),
     #[error("cannot get added imap message uid: extension UIDPLUS may be missing on the server")]
@@ -181,6 +184,20 @@ pub enum Error {
     ListRightEnvelopesCachedError(#[source] AnyBoxedError),
     #[error("cannot list envelopes from right sync backend")]
     ListRightEnvelopesError(#[source] AnyBoxedError),
+    #[error("cannot synchronize placeholder of message {0}: message not downloaded yet")]
+    CopyPlaceholderMessageError(String),
+    #[cfg(all(feature = "maildir", feature = "sync"))]
+    #[error("cannot fetch message {0} from placeholder: Message-ID mismatch ({1} != {2})")]
+    FetchPlaceholderMessageIdMismatchError(String, String, String),
+    #[cfg(all(feature = "maildir", feature = "sync"))]
+    #[error("cannot replace placeholder {1} by message {0}")]
+    ReplacePlaceholderMessageError(#[source] io::Error, String, PathBuf),
+    #[cfg(feature = "watch")]
+    #[error("cannot watch {0} folders at once: only one folder can be watched")]
+    WatchMultipleFoldersNotSupportedError(usize),
+    #[cfg(feature = "watch")]
+    #[error("cannot watch envelope events: feature not supported by the backend")]
+    WatchEnvelopeEventsNotSupportedError,
 
     #[cfg(feature = "maildir")]
     #[error(transparent)]
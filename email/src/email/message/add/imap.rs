@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
 use tracing::{debug, info};
 use utf7_imap::encode_utf7_imap as encode_utf7;
 
@@ -53,4 +54,27 @@ async fn add_message_with_flags(
 
         Ok(SingleId::from(uid.to_string()))
     }
+
+    async fn add_message_with_flags_and_date(
+        &self,
+        folder: &str,
+        msg: &[u8],
+        flags: &Flags,
+        date: &DateTime<FixedOffset>,
+    ) -> AnyResult<()> {
+        info!("adding imap message to folder {folder} with flags {flags} and date {date}");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        client
+            .add_message_with_date(&folder_encoded, flags.to_imap_flags_iter(), *date, msg)
+            .await?;
+
+        Ok(())
+    }
 }
@@ -1,4 +1,7 @@
+use std::{fs::File, time::SystemTime};
+
 use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
 use tracing::info;
 
 use super::{AddMessage, Flags};
@@ -49,4 +52,38 @@ async fn add_message_with_flags(
 
         Ok(SingleId::from(entry.id().unwrap()))
     }
+
+    async fn add_message_with_flags_and_date(
+        &self,
+        folder: &str,
+        raw_msg: &[u8],
+        flags: &Flags,
+        date: &DateTime<FixedOffset>,
+    ) -> AnyResult<()> {
+        info!("adding maildir message to folder {folder} with flags {flags} and date {date}");
+
+        let ctx = self.ctx.lock().await;
+        let mdir = ctx.get_maildir_from_folder_alias(folder)?;
+
+        let entry = mdir
+            .write_cur(
+                raw_msg,
+                flags
+                    .iter()
+                    .filter_map(|flag| maildirs::Flag::try_from(flag).ok()),
+            )
+            .map_err(|err| {
+                Error::StoreWithFlagsMaildirError(err, folder.to_owned(), flags.clone())
+            })?;
+
+        // NOTE: the internal date of a Maildir message is its
+        // modification time
+        File::options()
+            .write(true)
+            .open(entry.path())
+            .and_then(|file| file.set_modified(SystemTime::from(*date)))
+            .map_err(|err| Error::SetMaildirMessageDateError(err, entry.path().to_owned()))?;
+
+        Ok(())
+    }
 }
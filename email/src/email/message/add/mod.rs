@@ -7,6 +7,7 @@
 pub mod notmuch;
 
 use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
 
 use crate::{
     envelope::SingleId,
@@ -25,6 +26,23 @@ async fn add_message_with_flags(
         flags: &Flags,
     ) -> AnyResult<SingleId>;
 
+    /// Add the given raw email message with the given flags and the
+    /// given internal date to the given folder.
+    ///
+    /// The internal date is the date the message has been received
+    /// by the backend. Backends that cannot set it fall back to
+    /// [`AddMessage::add_message_with_flags`].
+    async fn add_message_with_flags_and_date(
+        &self,
+        folder: &str,
+        msg: &[u8],
+        flags: &Flags,
+        _date: &DateTime<FixedOffset>,
+    ) -> AnyResult<()> {
+        self.add_message_with_flags(folder, msg, flags).await?;
+        Ok(())
+    }
+
     /// Add the given raw email message with the given flag to the
     /// given folder.
     async fn add_message_with_flag(
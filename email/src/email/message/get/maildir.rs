@@ -1,6 +1,17 @@
+#[cfg(feature = "sync")]
+use std::{fs, sync::Arc};
+
 use async_trait::async_trait;
+#[cfg(feature = "sync")]
+use tracing::{debug, info};
 
 use super::{DefaultGetMessages, GetMessages, Messages};
+#[cfg(feature = "sync")]
+use crate::{
+    envelope::Envelope,
+    message::{sync::placeholder::MessagePlaceholder, Message},
+    Error,
+};
 use crate::{
     envelope::Id,
     flag::{
@@ -51,3 +62,110 @@ async fn add_flags(&self, folder: &str, id: &Id, flags: &Flags) -> AnyResult<()>
 
 #[async_trait]
 impl DefaultGetMessages for GetMaildirMessages {}
+
+/// Get Maildir messages, replacing placeholders by real messages.
+///
+/// When a Maildir is synchronized using a partial
+/// [`MessageSyncStrategy`], some messages are stored as
+/// [placeholders](MessagePlaceholder). This feature fetches the real
+/// message from the given remote backend on first read, then replaces
+/// the placeholder in place (keeping its identifier and its flags).
+///
+/// [`MessageSyncStrategy`]: crate::message::sync::config::MessageSyncStrategy
+#[cfg(feature = "sync")]
+#[derive(Clone)]
+pub struct GetMaildirMessagesWithPlaceholders {
+    ctx: MaildirContextSync,
+    get_messages: GetMaildirMessages,
+    remote: Arc<dyn PeekMessages>,
+}
+
+#[cfg(feature = "sync")]
+impl GetMaildirMessagesWithPlaceholders {
+    pub fn new(ctx: &MaildirContextSync, remote: Arc<dyn PeekMessages>) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            get_messages: GetMaildirMessages::new(ctx),
+            remote,
+        }
+    }
+
+    pub fn new_boxed(
+        ctx: &MaildirContextSync,
+        remote: Arc<dyn PeekMessages>,
+    ) -> Box<dyn GetMessages> {
+        Box::new(Self::new(ctx, remote))
+    }
+
+    pub fn some_new_boxed(
+        ctx: &MaildirContextSync,
+        remote: Arc<dyn PeekMessages>,
+    ) -> Option<Box<dyn GetMessages>> {
+        Some(Self::new_boxed(ctx, remote))
+    }
+
+    /// Replace placeholders matching the given id by real messages
+    /// fetched from the remote backend.
+    async fn resolve_placeholders(&self, folder: &str, id: &Id) -> AnyResult<()> {
+        let ctx = self.ctx.lock().await;
+        let mdir = ctx.get_maildir_from_folder_alias(folder)?;
+
+        let entries = mdir
+            .read()
+            .map_err(Error::ListMaildirEntriesError)?
+            .filter(|entry| match entry.id() {
+                Ok(entry_id) => id.iter().any(|id| id == entry_id),
+                Err(_) => false,
+            });
+
+        for entry in entries {
+            let msg = Message::from(entry.read().map_err(Error::MaildirsError)?);
+
+            let Some(placeholder) = MessagePlaceholder::from_msg(&msg) else {
+                continue;
+            };
+
+            info!(
+                "fetching message {} ({} bytes) from placeholder",
+                placeholder.id, placeholder.size
+            );
+
+            let remote_id = Id::single(&placeholder.id);
+            let msgs = self.remote.peek_messages(folder, &remote_id).await?;
+            let msg = msgs
+                .first()
+                .ok_or_else(|| Error::FindMessageError(placeholder.id.clone()))?;
+
+            let envelope = Envelope::from_msg(&placeholder.id, Flags::default(), msg.raw()?.into());
+            if envelope.message_id != placeholder.message_id {
+                let err = Error::FetchPlaceholderMessageIdMismatchError(
+                    placeholder.id,
+                    placeholder.message_id,
+                    envelope.message_id,
+                );
+                return Err(err.into());
+            }
+
+            let path = entry.path().to_owned();
+            fs::write(&path, msg.raw()?).map_err(|err| {
+                Error::ReplacePlaceholderMessageError(err, placeholder.id.clone(), path.clone())
+            })?;
+
+            debug!(
+                "replaced placeholder {path:?} by message {}",
+                placeholder.id
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sync")]
+#[async_trait]
+impl GetMessages for GetMaildirMessagesWithPlaceholders {
+    async fn get_messages(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
+        self.resolve_placeholders(folder, id).await?;
+        self.get_messages.get_messages(folder, id).await
+    }
+}
@@ -1,5 +1,5 @@
 use imap_client::imap_next::imap_types::fetch::{
-    MacroOrMessageDataItemNames, MessageDataItem, MessageDataItemName,
+    MacroOrMessageDataItemNames, MessageDataItem, MessageDataItemName, Section,
 };
 use once_cell::sync::Lazy;
 
@@ -26,6 +26,15 @@
     }])
 });
 
+/// Same as [`PEEK_MESSAGES`], but for the header block only.
+pub static PEEK_MESSAGES_HEADERS: Lazy<MacroOrMessageDataItemNames<'static>> = Lazy::new(|| {
+    MacroOrMessageDataItemNames::MessageDataItemNames(vec![MessageDataItemName::BodyExt {
+        section: Some(Section::Header(None)),
+        partial: None,
+        peek: true,
+    }])
+});
+
 impl<'a> TryFrom<&'a [MessageDataItem<'_>]> for Message<'a> {
     type Error = Error;
 
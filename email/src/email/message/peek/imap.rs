@@ -37,19 +37,37 @@ async fn peek_messages(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
         let folder_encoded = encode_utf7(folder.clone());
         debug!("utf7 encoded folder: {folder_encoded}");
 
-        let uids: SequenceSet = match id {
-            Id::Single(id) => Sequence::try_from(id.as_str()).unwrap().into(),
-            Id::Multiple(ids) => ids
-                .iter()
-                .filter_map(|id| Sequence::try_from(id.as_str()).ok())
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap(),
-        };
+        client.select_mailbox(&folder_encoded).await?;
+        let msgs = client.peek_messages(to_sequence_set(id)).await?;
+
+        Ok(msgs)
+    }
+
+    async fn peek_messages_headers(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
+        info!("peeking imap messages headers {id} from folder {folder}");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
 
         client.select_mailbox(&folder_encoded).await?;
-        let msgs = client.peek_messages(uids).await?;
+        let msgs = client.peek_messages_headers(to_sequence_set(id)).await?;
 
         Ok(msgs)
     }
 }
+
+fn to_sequence_set(id: &Id) -> SequenceSet {
+    match id {
+        Id::Single(id) => Sequence::try_from(id.as_str()).unwrap().into(),
+        Id::Multiple(ids) => ids
+            .iter()
+            .filter_map(|id| Sequence::try_from(id.as_str()).ok())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap(),
+    }
+}
@@ -20,4 +20,14 @@ pub trait PeekMessages: Send + Sync {
     /// automatically added to envelopes, see
     /// [`GetMessages`](super::get::GetMessages).
     async fn peek_messages(&self, folder: &str, id: &Id) -> AnyResult<Messages>;
+
+    /// Peek the header block of email messages from the given folder
+    /// matching the given ids.
+    ///
+    /// The default implementation peeks entire messages, which is
+    /// fine for local backends. Remote backends should only fetch
+    /// headers.
+    async fn peek_messages_headers(&self, folder: &str, id: &Id) -> AnyResult<Messages> {
+        self.peek_messages(folder, id).await
+    }
 }
@@ -7,6 +7,51 @@
 pub struct MessageSyncConfig {
     #[cfg_attr(feature = "derive", serde(default))]
     pub permissions: MessageSyncPermissions,
+
+    #[cfg_attr(feature = "derive", serde(default))]
+    pub strategy: MessageSyncStrategy,
+}
+
+/// The message synchronization strategy.
+///
+/// Defines which messages are fully downloaded when synchronized to
+/// a backend. Messages that are not fully downloaded are replaced by
+/// [placeholders](super::placeholder::MessagePlaceholder), which can
+/// be fetched lazily later on.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum MessageSyncStrategy {
+    /// Download all messages entirely.
+    #[default]
+    Full,
+
+    /// Download only message headers.
+    HeadersOnly,
+
+    /// Download entirely messages which size (in bytes) is less than
+    /// or equal to the given one, and only headers for others.
+    MaxSize(usize),
+}
+
+impl MessageSyncStrategy {
+    /// Return `true` if a message of the given size (in bytes) should
+    /// be downloaded entirely.
+    ///
+    /// A size of 0 means that the size is unknown, in which case the
+    /// message is always downloaded entirely (except for
+    /// [`MessageSyncStrategy::HeadersOnly`]).
+    pub fn allows_body(&self, size: usize) -> bool {
+        match self {
+            Self::Full => true,
+            Self::HeadersOnly => false,
+            Self::MaxSize(_) if size == 0 => true,
+            Self::MaxSize(max) => size <= *max,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -0,0 +1,278 @@
+//! # Message placeholder
+//!
+//! Module dedicated to message placeholders. A placeholder is a
+//! lightweight message containing only the headers of a message that
+//! has not been downloaded yet during the synchronization, see
+//! [`MessageSyncStrategy`](super::config::MessageSyncStrategy).
+//!
+//! Placeholders keep enough information to fetch the real message
+//! later on, on first read.
+
+use crate::{envelope::Envelope, message::Message};
+
+/// The header containing the identifier of the real message, on the
+/// side it has been synchronized from.
+pub const PLACEHOLDER_ID_HEADER: &str = "X-Sync-Placeholder-Id";
+
+/// The header containing the size of the real message, in bytes.
+pub const PLACEHOLDER_SIZE_HEADER: &str = "X-Sync-Placeholder-Size";
+
+/// The message placeholder.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MessagePlaceholder {
+    /// The identifier of the real message, on the side it has been
+    /// synchronized from.
+    pub id: String,
+
+    /// The Message-ID header of the real message.
+    pub message_id: String,
+
+    /// The size of the real message, in bytes.
+    pub size: usize,
+}
+
+impl MessagePlaceholder {
+    /// Build a raw placeholder message from the given envelope and
+    /// the given raw headers of the real message.
+    ///
+    /// The placeholder message keeps the real header block, so that
+    /// it can be listed, searched and threaded like the real message,
+    /// plus the placeholder headers. Anything after the header block
+    /// is discarded. Since the body is replaced by a plain text
+    /// notice, the MIME headers of the real message (`MIME-Version`
+    /// and `Content-*`) are replaced as well.
+    pub fn from_headers_to_msg(envelope: &Envelope, headers: &[u8]) -> Vec<u8> {
+        let headers = header_block(headers);
+        let eol: &[u8] = if headers.ends_with(b"\r\n") || headers.is_empty() {
+            b"\r\n"
+        } else {
+            b"\n"
+        };
+
+        let mut msg = Vec::with_capacity(headers.len());
+        let mut keep = true;
+
+        for line in headers.split_inclusive(|b| *b == b'\n') {
+            // folded lines belong to the previous header
+            if !line.starts_with(b" ") && !line.starts_with(b"\t") {
+                keep = !is_mime_header(line);
+            }
+
+            if keep {
+                msg.extend_from_slice(line);
+            }
+        }
+
+        if !msg.is_empty() && !msg.ends_with(b"\n") {
+            msg.extend_from_slice(eol);
+        }
+
+        for header in [
+            String::from("MIME-Version: 1.0"),
+            String::from("Content-Type: text/plain; charset=utf-8"),
+            String::from("Content-Transfer-Encoding: 7bit"),
+            format!("{PLACEHOLDER_ID_HEADER}: {}", envelope.id),
+            format!("{PLACEHOLDER_SIZE_HEADER}: {}", envelope.size),
+        ] {
+            msg.extend_from_slice(header.as_bytes());
+            msg.extend_from_slice(eol);
+        }
+
+        msg.extend_from_slice(eol);
+        msg.extend_from_slice(
+            format!(
+                "This message ({} bytes) has not been downloaded yet.",
+                envelope.size
+            )
+            .as_bytes(),
+        );
+        msg.extend_from_slice(eol);
+
+        msg
+    }
+
+    /// Extract the placeholder from the given message.
+    ///
+    /// Returns `None` if the given message is not a placeholder.
+    pub fn from_msg(msg: &Message) -> Option<Self> {
+        let parsed = msg.parsed().ok()?;
+        let id = parsed.header_raw(PLACEHOLDER_ID_HEADER)?.trim().to_owned();
+        let size = parsed
+            .header_raw(PLACEHOLDER_SIZE_HEADER)
+            .and_then(|size| size.trim().parse().ok())
+            .unwrap_or_default();
+        let message_id = parsed
+            .message_id()
+            .map(|mid| format!("<{mid}>"))
+            .unwrap_or_default();
+
+        Some(Self {
+            id,
+            message_id,
+            size,
+        })
+    }
+
+    /// Return `true` if the given message is a placeholder.
+    pub fn is_placeholder(msg: &Message) -> bool {
+        Self::from_msg(msg).is_some()
+    }
+}
+
+/// Return the header block of the given raw message, without the
+/// empty line separating it from the body.
+fn header_block(msg: &[u8]) -> &[u8] {
+    let crlf = msg.windows(4).position(|w| w == b"\r\n\r\n").map(|i| i + 2);
+    let lf = msg.windows(2).position(|w| w == b"\n\n").map(|i| i + 1);
+
+    let end = match (crlf, lf) {
+        (Some(crlf), Some(lf)) => crlf.min(lf),
+        (end, None) | (None, end) => end.unwrap_or(msg.len()),
+    };
+
+    &msg[..end]
+}
+
+/// Return `true` if the given header line is a MIME header
+/// describing the body (`MIME-Version` or `Content-*`).
+fn is_mime_header(line: &[u8]) -> bool {
+    let name = line.split(|b| *b == b':').next().unwrap_or_default();
+    let name = name.trim_ascii().to_ascii_lowercase();
+    name == b"mime-version" || name.starts_with(b"content-")
+}
+
+#[cfg(test)]
+mod tests {
+    use concat_with::concat_line;
+
+    use crate::{envelope::Envelope, message::Message};
+
+    use super::MessagePlaceholder;
+
+    #[test]
+    fn placeholder_round_trip() {
+        let envelope = Envelope {
+            id: "42".into(),
+            size: 1024,
+            ..Default::default()
+        };
+
+        let headers = concat_line!(
+            "Message-ID: <abc@localhost>",
+            "References: <parent@localhost>",
+            "From: alice@localhost",
+            "To: bob@localhost",
+            "Subject: Hello",
+            "",
+            "Hello, world!",
+        );
+
+        let msg = MessagePlaceholder::from_headers_to_msg(&envelope, headers.as_bytes());
+
+        assert_eq!(
+            String::from_utf8_lossy(&msg),
+            concat_line!(
+                "Message-ID: <abc@localhost>",
+                "References: <parent@localhost>",
+                "From: alice@localhost",
+                "To: bob@localhost",
+                "Subject: Hello",
+                "MIME-Version: 1.0",
+                "Content-Type: text/plain; charset=utf-8",
+                "Content-Transfer-Encoding: 7bit",
+                "X-Sync-Placeholder-Id: 42",
+                "X-Sync-Placeholder-Size: 1024",
+                "",
+                "This message (1024 bytes) has not been downloaded yet.",
+                "",
+            ),
+        );
+
+        let msg = Message::from(msg);
+
+        assert_eq!(
+            MessagePlaceholder::from_msg(&msg),
+            Some(MessagePlaceholder {
+                id: "42".into(),
+                message_id: "<abc@localhost>".into(),
+                size: 1024,
+            })
+        );
+
+        let envelope = Envelope::from_msg("1", Default::default(), msg);
+        assert_eq!(envelope.message_id, "<abc@localhost>");
+        assert_eq!(envelope.subject, "Hello");
+    }
+
+    #[test]
+    fn placeholder_keeps_crlf() {
+        let envelope = Envelope {
+            id: "42".into(),
+            size: 1024,
+            ..Default::default()
+        };
+
+        let headers = "Message-ID: <abc@localhost>\r\nSubject: Hello\r\n\r\n";
+        let msg = MessagePlaceholder::from_headers_to_msg(&envelope, headers.as_bytes());
+
+        assert!(String::from_utf8_lossy(&msg)
+            .starts_with("Message-ID: <abc@localhost>\r\nSubject: Hello\r\nMIME-Version: 1.0\r\n"));
+    }
+
+    #[test]
+    fn placeholder_replaces_mime_headers() {
+        let envelope = Envelope {
+            id: "42".into(),
+            size: 1024,
+            ..Default::default()
+        };
+
+        let headers = concat_line!(
+            "Message-ID: <abc@localhost>",
+            "MIME-Version: 1.0",
+            "Subject: Hello",
+            "Content-Type: multipart/mixed;",
+            "\tboundary=\"boundary\"",
+            "Content-Transfer-Encoding: base64",
+            "",
+            "--boundary",
+            "Content-Type: text/plain",
+            "Content-Transfer-Encoding: base64",
+            "",
+            "SGVsbG8sIHdvcmxkIQ==",
+            "--boundary--",
+        );
+
+        let msg = MessagePlaceholder::from_headers_to_msg(&envelope, headers.as_bytes());
+
+        assert_eq!(
+            String::from_utf8_lossy(&msg),
+            concat_line!(
+                "Message-ID: <abc@localhost>",
+                "Subject: Hello",
+                "MIME-Version: 1.0",
+                "Content-Type: text/plain; charset=utf-8",
+                "Content-Transfer-Encoding: 7bit",
+                "X-Sync-Placeholder-Id: 42",
+                "X-Sync-Placeholder-Size: 1024",
+                "",
+                "This message (1024 bytes) has not been downloaded yet.",
+                "",
+            ),
+        );
+
+        let msg = Message::from(msg);
+        let body = msg.parsed().unwrap().body_text(0).unwrap().into_owned();
+        assert_eq!(
+            body.trim(),
+            "This message (1024 bytes) has not been downloaded yet."
+        );
+        assert!(MessagePlaceholder::is_placeholder(&msg));
+    }
+
+    #[test]
+    fn regular_message_is_not_placeholder() {
+        let msg = Message::from("Message-ID: <abc@localhost>\nSubject: Hello\n\nHello!\n");
+        assert!(!MessagePlaceholder::is_placeholder(&msg));
+    }
+}
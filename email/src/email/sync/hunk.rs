@@ -101,6 +101,17 @@ pub fn folder(&self) -> &str {
             Self::Delete(folder, _, _) => folder.as_str(),
         }
     }
+
+    /// Return the size of the message to transfer, in bytes.
+    ///
+    /// Only [`EmailSyncHunk::CopyThenCache`] transfers messages, the
+    /// size of other hunks is always 0.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::CopyThenCache(_, envelope, _, _, _) => envelope.size,
+            _ => 0,
+        }
+    }
 }
 
 /// The email synchronization cache hunk.
@@ -26,7 +26,7 @@
         Envelope, Id, SingleId,
     },
     flag::{add::AddFlags, set::SetFlags, Flag},
-    message::{add::AddMessage, peek::PeekMessages},
+    message::{add::AddMessage, peek::PeekMessages, sync::placeholder::MessagePlaceholder},
     search_query::SearchEmailsQuery,
     sync::{pool::SyncPoolContext, SyncDestination, SyncEvent},
     AnyBoxedError,
@@ -56,7 +56,7 @@ pub(crate) async fn sync<L, R>(
                             page: 0,
                             page_size: 0,
                             query: Some(SearchEmailsQuery {
-                                filter: ctx.envelope_filters.clone().into(),
+                                filter: ctx.envelope_filters.to_folder_filter_query(&folder_ref),
                                 sort: None,
                             }),
                         },
@@ -91,7 +91,7 @@ pub(crate) async fn sync<L, R>(
                             page: 0,
                             page_size: 0,
                             query: Some(SearchEmailsQuery {
-                                filter: ctx.envelope_filters.clone().into(),
+                                filter: ctx.envelope_filters.to_folder_filter_query(&folder_ref),
                                 sort: None,
                             }),
                         },
@@ -126,7 +126,7 @@ pub(crate) async fn sync<L, R>(
                             page: 0,
                             page_size: 0,
                             query: Some(SearchEmailsQuery {
-                                filter: ctx.envelope_filters.clone().into(),
+                                filter: ctx.envelope_filters.to_folder_filter_query(&folder_ref),
                                 sort: None,
                             }),
                         },
@@ -161,7 +161,7 @@ pub(crate) async fn sync<L, R>(
                             page: 0,
                             page_size: 0,
                             query: Some(SearchEmailsQuery {
-                                filter: ctx.envelope_filters.clone().into(),
+                                filter: ctx.envelope_filters.to_folder_filter_query(&folder_ref),
                                 sort: None,
                             }),
                         },
@@ -261,27 +261,46 @@ pub(crate) async fn sync<L, R>(
                         refresh_source_cache,
                     ) => {
                         let id = Id::single(&envelope.id);
-                        let msgs = match source {
-                            SyncDestination::Left => {
-                                if refresh_source_cache {
-                                    let flags = envelope.flags.clone();
-                                    let msg = envelope.to_sync_cache_msg();
+
+                        if refresh_source_cache {
+                            let flags = envelope.flags.clone();
+                            let msg = envelope.to_sync_cache_msg();
+                            match source {
+                                SyncDestination::Left => {
                                     ctx.left_cache
                                         .add_message_with_flags(&folder, msg.as_bytes(), &flags)
                                         .await?;
-                                };
-                                ctx.left.peek_messages(&folder, &id).await?
-                            }
-                            SyncDestination::Right => {
-                                if refresh_source_cache {
-                                    let flags = envelope.flags.clone();
-                                    let msg = envelope.to_sync_cache_msg();
+                                }
+                                SyncDestination::Right => {
                                     ctx.right_cache
                                         .add_message_with_flags(&folder, msg.as_bytes(), &flags)
                                         .await?;
-                                };
+                                }
+                            };
+                        }
+
+                        let strategy = match target {
+                            SyncDestination::Left => &ctx.left_message_strategy,
+                            SyncDestination::Right => &ctx.right_message_strategy,
+                        };
+
+                        // NOTE: messages that should not be entirely
+                        // downloaded are replaced by placeholders,
+                        // which can be fetched lazily later on
+                        let allows_body = strategy.allows_body(envelope.size);
+                        let msgs = match (source, allows_body) {
+                            (SyncDestination::Left, true) => {
+                                ctx.left.peek_messages(&folder, &id).await?
+                            }
+                            (SyncDestination::Left, false) => {
+                                ctx.left.peek_messages_headers(&folder, &id).await?
+                            }
+                            (SyncDestination::Right, true) => {
                                 ctx.right.peek_messages(&folder, &id).await?
                             }
+                            (SyncDestination::Right, false) => {
+                                ctx.right.peek_messages_headers(&folder, &id).await?
+                            }
                         };
 
                         let msgs = msgs.to_vec();
@@ -289,11 +308,25 @@ pub(crate) async fn sync<L, R>(
                             .first()
                             .ok_or_else(|| Error::FindMessageError(envelope.id.clone()))?;
 
+                        // NOTE: placeholders should never be spread
+                        // to other backends, since they do not
+                        // contain the real message
+                        if MessagePlaceholder::is_placeholder(msg) {
+                            let err = Error::CopyPlaceholderMessageError(envelope.id.clone());
+                            return Err(err.into());
+                        }
+
+                        let msg = if allows_body {
+                            msg.raw()?.to_vec()
+                        } else {
+                            MessagePlaceholder::from_headers_to_msg(&envelope, msg.raw()?)
+                        };
+
                         match target {
                             SyncDestination::Left => {
                                 let id = ctx
                                     .left
-                                    .add_message_with_flags(&folder, msg.raw()?, &envelope.flags)
+                                    .add_message_with_flags(&folder, &msg, &envelope.flags)
                                     .await?;
                                 let envelope =
                                     ctx.left.get_envelope(&folder, &SingleId::from(id)).await?;
@@ -306,7 +339,7 @@ pub(crate) async fn sync<L, R>(
                             SyncDestination::Right => {
                                 let id = ctx
                                     .right
-                                    .add_message_with_flags(&folder, msg.raw()?, &envelope.flags)
+                                    .add_message_with_flags(&folder, &msg, &envelope.flags)
                                     .await?;
                                 let envelope =
                                     ctx.right.get_envelope(&folder, &SingleId::from(id)).await?;
@@ -317,6 +350,10 @@ pub(crate) async fn sync<L, R>(
                                     .await?;
                             }
                         };
+
+                        SyncEvent::TransferredEmail(folder, msg.len())
+                            .emit(&ctx.handler)
+                            .await;
                     }
                     EmailSyncHunk::Uncache(folder, id, SyncDestination::Left) => {
                         ctx.left_cache
@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use tracing::{debug, info};
+use utf7_imap::encode_utf7_imap as encode_utf7;
+
+use super::{FolderAnnotations, GetFolderAnnotations, SetFolderAnnotation};
+use crate::{
+    folder::{Error, Result},
+    imap::ImapContext,
+    AnyResult,
+};
+
+/// The IMAP metadata entry under which folder annotations are
+/// stored, as a vendor-specific private entry (RFC 5464).
+pub const IMAP_ANNOTATIONS_ENTRY: &str = "/private/vendor/pimalaya";
+
+/// Map the given annotation key to an IMAP metadata entry.
+///
+/// Annotations are always stored under [`IMAP_ANNOTATIONS_ENTRY`],
+/// which is the only entry read back by
+/// [`GetImapFolderAnnotations`]. Keys starting with a slash, like raw
+/// IMAP metadata entries, are therefore rejected.
+pub fn imap_entry_from_annotation_key(key: &str) -> Result<String> {
+    if key.starts_with('/') {
+        Err(Error::InvalidImapAnnotationKeyError(key.to_owned()))
+    } else {
+        Ok(format!("{IMAP_ANNOTATIONS_ENTRY}/{key}"))
+    }
+}
+
+/// Map the given IMAP metadata entry to an annotation key.
+pub fn annotation_key_from_imap_entry(entry: &str) -> String {
+    match entry.strip_prefix(IMAP_ANNOTATIONS_ENTRY) {
+        Some(key) if key.starts_with('/') => key[1..].to_owned(),
+        _ => entry.to_owned(),
+    }
+}
+
+impl FolderAnnotations {
+    /// Build folder annotations from IMAP metadata entries.
+    ///
+    /// Entries without value are ignored.
+    pub fn from_imap_metadata(entries: Vec<(String, Option<String>)>) -> Self {
+        entries
+            .into_iter()
+            .filter_map(|(entry, val)| Some((annotation_key_from_imap_entry(&entry), val?)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GetImapFolderAnnotations {
+    ctx: ImapContext,
+}
+
+impl GetImapFolderAnnotations {
+    pub fn new(ctx: &ImapContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &ImapContext) -> Box<dyn GetFolderAnnotations> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn GetFolderAnnotations>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl GetFolderAnnotations for GetImapFolderAnnotations {
+    async fn get_folder_annotations(&self, folder: &str) -> AnyResult<FolderAnnotations> {
+        info!("getting annotations of imap folder {folder}");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        let entries = client
+            .get_metadata(folder_encoded, [IMAP_ANNOTATIONS_ENTRY.to_owned()])
+            .await?;
+
+        Ok(FolderAnnotations::from_imap_metadata(entries))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetImapFolderAnnotation {
+    ctx: ImapContext,
+}
+
+impl SetImapFolderAnnotation {
+    pub fn new(ctx: &ImapContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &ImapContext) -> Box<dyn SetFolderAnnotation> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn SetFolderAnnotation>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl SetFolderAnnotation for SetImapFolderAnnotation {
+    async fn set_folder_annotation(
+        &self,
+        folder: &str,
+        key: &str,
+        val: Option<&str>,
+    ) -> AnyResult<()> {
+        info!("setting annotation {key} of imap folder {folder}");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        let entry = imap_entry_from_annotation_key(key)?;
+        let val = val.map(ToOwned::to_owned);
+
+        client.set_metadata(folder_encoded, [(entry, val)]).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{annotation_key_from_imap_entry, imap_entry_from_annotation_key};
+
+    #[test]
+    fn annotation_key_round_trip() {
+        let entry = imap_entry_from_annotation_key("color").unwrap();
+        assert_eq!(entry, "/private/vendor/pimalaya/color");
+        assert_eq!(annotation_key_from_imap_entry(&entry), "color");
+    }
+
+    #[test]
+    fn reject_raw_entries() {
+        assert!(imap_entry_from_annotation_key("/shared/comment").is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use tracing::info;
+
+use super::{FolderAnnotations, GetFolderAnnotations, SetFolderAnnotation};
+use crate::{
+    folder::{Error, Result},
+    maildir::{MaildirContext, MaildirContextSync},
+    AnyResult,
+};
+
+/// The name of the sidecar file containing the folder annotations,
+/// located in the maildir folder directory.
+pub const MAILDIR_ANNOTATIONS_FILE_NAME: &str = "pimalaya-annotations";
+
+impl FolderAnnotations {
+    /// Read folder annotations from the given sidecar file.
+    ///
+    /// A missing file is considered as empty annotations.
+    pub fn read_maildir_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(Error::ReadMaildirAnnotationsError(err, path.to_owned())),
+        }
+    }
+
+    /// Write folder annotations to the given sidecar file.
+    ///
+    /// The file is removed when there is no annotation left.
+    pub fn write_maildir_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+
+        let res = if self.is_empty() {
+            match fs::remove_file(path) {
+                Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+                res => res,
+            }
+        } else {
+            fs::write(path, self.to_string())
+        };
+
+        res.map_err(|err| Error::WriteMaildirAnnotationsError(err, path.to_owned()))
+    }
+}
+
+/// Get the path of the annotations sidecar file of the given folder.
+///
+/// An empty folder name targets the maildir root directory.
+fn annotations_path(ctx: &MaildirContext, folder: &str) -> AnyResult<PathBuf> {
+    let dir = if folder.is_empty() {
+        ctx.root.path().to_owned()
+    } else {
+        ctx.get_maildir_from_folder_alias(folder)?.path().to_owned()
+    };
+
+    Ok(dir.join(MAILDIR_ANNOTATIONS_FILE_NAME))
+}
+
+pub struct GetMaildirFolderAnnotations {
+    ctx: MaildirContextSync,
+}
+
+impl GetMaildirFolderAnnotations {
+    pub fn new(ctx: &MaildirContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &MaildirContextSync) -> Box<dyn GetFolderAnnotations> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &MaildirContextSync) -> Option<Box<dyn GetFolderAnnotations>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl GetFolderAnnotations for GetMaildirFolderAnnotations {
+    async fn get_folder_annotations(&self, folder: &str) -> AnyResult<FolderAnnotations> {
+        info!("getting annotations of maildir folder {folder}");
+
+        let ctx = self.ctx.lock().await;
+        let path = annotations_path(&ctx, folder)?;
+        let annotations = FolderAnnotations::read_maildir_file(path)?;
+
+        Ok(annotations)
+    }
+}
+
+pub struct SetMaildirFolderAnnotation {
+    ctx: MaildirContextSync,
+}
+
+impl SetMaildirFolderAnnotation {
+    pub fn new(ctx: &MaildirContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &MaildirContextSync) -> Box<dyn SetFolderAnnotation> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &MaildirContextSync) -> Option<Box<dyn SetFolderAnnotation>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl SetFolderAnnotation for SetMaildirFolderAnnotation {
+    async fn set_folder_annotation(
+        &self,
+        folder: &str,
+        key: &str,
+        val: Option<&str>,
+    ) -> AnyResult<()> {
+        info!("setting annotation {key} of maildir folder {folder}");
+
+        let ctx = self.ctx.lock().await;
+        let path = annotations_path(&ctx, folder)?;
+
+        let mut annotations = FolderAnnotations::read_maildir_file(&path)?;
+        annotations.set(key, val);
+        annotations.write_maildir_file(&path)?;
+
+        Ok(())
+    }
+}
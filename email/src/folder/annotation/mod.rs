@@ -0,0 +1,213 @@
+//! # Folder annotation
+//!
+//! Module dedicated to folder annotations. Annotations are key-value
+//! settings attached to a folder, like its color, its display name
+//! or its notification preference. They are stored alongside the
+//! folder so that they follow the user between devices.
+//!
+//! IMAP annotations are stored on the server using the `METADATA`
+//! extension (RFC 5464), whereas Maildir annotations are stored in a
+//! sidecar file located in the folder directory.
+
+#[cfg(feature = "imap")]
+pub mod imap;
+#[cfg(feature = "maildir")]
+pub mod maildir;
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use async_trait::async_trait;
+
+use crate::AnyResult;
+
+/// The annotation key of the folder color.
+pub const COLOR: &str = "color";
+
+/// The annotation key of the folder display name.
+pub const DISPLAY_NAME: &str = "display-name";
+
+/// The annotation key of the folder notification preference.
+pub const NOTIFY: &str = "notify";
+
+/// The folder annotations.
+///
+/// Annotations are sorted by key.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct FolderAnnotations(BTreeMap<String, String>);
+
+impl FolderAnnotations {
+    /// Parse folder annotations from the given string.
+    ///
+    /// The string contains one annotation per line, composed of the
+    /// key and the value separated by a tab. Backslashes, tabs and
+    /// line breaks are escaped. Malformed lines are ignored.
+    pub fn parse(content: &str) -> Self {
+        content
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(key, val)| (unescape(key), unescape(val)))
+            .collect()
+    }
+
+    pub fn color(&self) -> Option<&str> {
+        self.get(COLOR).map(String::as_str)
+    }
+
+    pub fn display_name(&self) -> Option<&str> {
+        self.get(DISPLAY_NAME).map(String::as_str)
+    }
+
+    /// Return the notification preference of the folder, if any.
+    ///
+    /// Values `false`, `no`, `off` and `0` are considered as
+    /// disabled, any other value is considered as enabled.
+    pub fn notify(&self) -> Option<bool> {
+        self.get(NOTIFY).map(|val| {
+            !matches!(
+                val.trim().to_ascii_lowercase().as_str(),
+                "false" | "no" | "off" | "0"
+            )
+        })
+    }
+
+    /// Set the value of the given annotation key.
+    ///
+    /// The annotation is removed if the value is `None`.
+    pub fn set(&mut self, key: impl ToString, val: Option<impl ToString>) {
+        match val {
+            Some(val) => {
+                self.insert(key.to_string(), val.to_string());
+            }
+            None => {
+                self.remove(&key.to_string());
+            }
+        }
+    }
+}
+
+impl Deref for FolderAnnotations {
+    type Target = BTreeMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for FolderAnnotations {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl FromIterator<(String, String)> for FolderAnnotations {
+    fn from_iter<T: IntoIterator<Item = (String, String)>>(iter: T) -> Self {
+        Self(BTreeMap::from_iter(iter))
+    }
+}
+
+impl fmt::Display for FolderAnnotations {
+    /// Format folder annotations the way
+    /// [`FolderAnnotations::parse`] expects.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, val) in self.iter() {
+            writeln!(f, "{}\t{}", escape(key), escape(val))?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+#[async_trait]
+pub trait GetFolderAnnotations: Send + Sync {
+    /// Get the annotations of the given folder.
+    ///
+    /// An empty folder name targets the annotations of the whole
+    /// account, when supported by the backend.
+    async fn get_folder_annotations(&self, folder: &str) -> AnyResult<FolderAnnotations>;
+}
+
+#[async_trait]
+pub trait SetFolderAnnotation: Send + Sync {
+    /// Set the annotation of the given folder matching the given
+    /// key.
+    ///
+    /// The annotation is removed if the value is `None`.
+    async fn set_folder_annotation(
+        &self,
+        folder: &str,
+        key: &str,
+        val: Option<&str>,
+    ) -> AnyResult<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FolderAnnotations;
+
+    #[test]
+    fn parse() {
+        let annotations =
+            FolderAnnotations::parse("color\t#ff0000\nmalformed\ndisplay-name\tMy\\tInbox\\n\n");
+
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations.color(), Some("#ff0000"));
+        assert_eq!(annotations.display_name(), Some("My\tInbox\n"));
+        assert_eq!(annotations.notify(), None);
+    }
+
+    #[test]
+    fn to_string() {
+        let mut annotations = FolderAnnotations::default();
+        annotations.set("display-name", Some("a\\b\tc\nd"));
+        annotations.set("notify", Some("off"));
+        annotations.set("color", Some("blue"));
+        annotations.set("color", None::<String>);
+
+        assert_eq!(annotations.notify(), Some(false));
+        assert_eq!(
+            annotations.to_string(),
+            "display-name\ta\\\\b\\tc\\nd\nnotify\toff\n"
+        );
+        assert_eq!(
+            FolderAnnotations::parse(&annotations.to_string()),
+            annotations
+        );
+    }
+}
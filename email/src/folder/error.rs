@@ -24,6 +24,40 @@ pub enum Error {
     #[error("cannot delete maildir INBOX at {0}")]
     DeleteMaildirInboxForbiddenError(std::path::PathBuf),
     #[cfg(feature = "maildir")]
+    #[error("cannot rename maildir INBOX at {0}")]
+    RenameMaildirInboxForbiddenError(std::path::PathBuf),
+    #[cfg(feature = "maildir")]
+    #[error("cannot rename maildir folder: folder {0} already exists")]
+    RenameMaildirFolderAlreadyExistsError(String),
+    #[cfg(feature = "maildir")]
+    #[error("cannot get maildir folder {1}")]
+    GetMaildirFolderError(#[source] maildirs::Error, String),
+    #[cfg(feature = "maildir")]
+    #[error("cannot rename maildir folder from {1} to {2}")]
+    RenameMaildirFolderError(
+        #[source] std::io::Error,
+        std::path::PathBuf,
+        std::path::PathBuf,
+    ),
+    #[cfg(feature = "notmuch")]
+    #[error("cannot index renamed notmuch message at {1}")]
+    IndexRenamedNotmuchMessageError(#[source] notmuch::Error, std::path::PathBuf),
+    #[cfg(feature = "maildir")]
+    #[error("cannot read entries of maildir folder {1}")]
+    ReadMaildirFolderEntriesError(#[source] maildirs::Error, String),
+    #[cfg(feature = "imap")]
+    #[error("invalid imap folder annotation key {0}: raw metadata entries are not supported")]
+    InvalidImapAnnotationKeyError(String),
+    #[cfg(feature = "maildir")]
+    #[error("cannot read maildir folder annotations at {1}")]
+    ReadMaildirAnnotationsError(#[source] std::io::Error, std::path::PathBuf),
+    #[cfg(feature = "maildir")]
+    #[error("cannot write maildir folder annotations at {1}")]
+    WriteMaildirAnnotationsError(#[source] std::io::Error, std::path::PathBuf),
+    #[cfg(feature = "notmuch")]
+    #[error("cannot count notmuch messages matching query {1}")]
+    CountNotmuchMessagesError(#[source] notmuch::Error, String),
+    #[cfg(feature = "maildir")]
     #[error("maildir: cannot list current folder from {1}")]
     ListCurrentFolderMaildirError(#[source] maildirs::Error, std::path::PathBuf),
     #[cfg(feature = "maildir")]
@@ -46,9 +80,6 @@ pub enum Error {
     ListRightFoldersError(#[source] AnyBoxedError),
 
     // ======== v2
-    #[error("cannot parse IMAP mailbox {0}: mailbox not selectable")]
-    ParseImapFolderNotSelectableError(String),
-
     #[cfg(feature = "maildir")]
     #[error(transparent)]
     MaildirsError(#[from] maildirs::Error),
@@ -1,12 +1,10 @@
 use imap_client::imap_next::imap_types::{
-    core::{Atom, QuotedChar},
-    flag::FlagNameAttribute,
-    mailbox::Mailbox,
+    core::QuotedChar, flag::FlagNameAttribute, mailbox::Mailbox,
 };
 use tracing::debug;
 use utf7_imap::decode_utf7_imap as decode_utf7;
 
-use super::{Error, FolderKind, Result};
+use super::{FolderAttribute, FolderAttributes, FolderKind, Result};
 use crate::{
     account::config::AccountConfig,
     folder::{Folder, Folders},
@@ -27,6 +25,24 @@ pub fn from_imap_mailboxes(config: &AccountConfig, mboxes: ImapMailboxes) -> Sel
             })
             .collect()
     }
+
+    /// Parse folders from IMAP mailboxes returned by the LSUB
+    /// command.
+    ///
+    /// Selectable folders are marked as subscribed. Non-selectable
+    /// folders are only parents of subscribed folders, so they are
+    /// kept as such (RFC 3501).
+    pub fn from_imap_subscribed_mailboxes(config: &AccountConfig, mboxes: ImapMailboxes) -> Self {
+        let mut folders = Self::from_imap_mailboxes(config, mboxes);
+
+        for folder in folders.iter_mut() {
+            if folder.is_selectable() {
+                folder.attrs.insert(FolderAttribute::Subscribed);
+            }
+        }
+
+        folders
+    }
 }
 
 pub type ImapMailbox = (
@@ -38,27 +54,23 @@ pub fn from_imap_mailboxes(config: &AccountConfig, mboxes: ImapMailboxes) -> Sel
 impl Folder {
     fn try_from_imap_mailbox(
         config: &AccountConfig,
-        (mbox, _delim, attrs): &ImapMailbox,
+        (mbox, delim, imap_attrs): &ImapMailbox,
     ) -> Result<Self> {
         let mbox = match mbox {
             Mailbox::Inbox => String::from("INBOX"),
             Mailbox::Other(mbox) => String::from_utf8_lossy(mbox.as_ref()).to_string(),
         };
 
-        // exit straight if the mailbox is not selectable.
-        // TODO: make this behaviour customizable?
-        if attrs.contains(&FlagNameAttribute::Noselect) {
-            return Err(Error::ParseImapFolderNotSelectableError(mbox.clone()));
-        }
-
         let name = decode_utf7(mbox.into());
+        let delim = delim.as_ref().map(|delim| delim.inner());
+        let attrs = FolderAttributes::from_iter(imap_attrs.iter().map(folder_attr_from_imap_attr));
 
         let kind = config
             .find_folder_kind_from_alias(&name)
-            .or_else(|| find_folder_kind_from_imap_attrs(attrs.as_ref()))
+            .or_else(|| attrs.find_folder_kind())
             .or_else(|| name.parse().ok());
 
-        let desc = attrs.iter().fold(String::default(), |mut desc, attr| {
+        let desc = imap_attrs.iter().fold(String::default(), |mut desc, attr| {
             if !desc.is_empty() {
                 desc.push_str(", ")
             }
@@ -66,20 +78,55 @@ fn try_from_imap_mailbox(
             desc
         });
 
-        Ok(Folder { kind, name, desc })
+        Ok(Folder {
+            kind,
+            name,
+            desc,
+            delim,
+            attrs,
+        })
+    }
+}
+
+/// Map the given IMAP mailbox attribute to a folder attribute.
+///
+/// Attributes are matched case-insensitively, as stated by the RFC
+/// 3501. Special-use attributes are defined by the RFC 6154.
+pub fn folder_attr_from_imap_attr(attr: &FlagNameAttribute) -> FolderAttribute {
+    if attr == &FlagNameAttribute::Noselect {
+        return FolderAttribute::NoSelect;
+    }
+
+    if attr == &FlagNameAttribute::Noinferiors {
+        return FolderAttribute::NoInferiors;
+    }
+
+    let attr = attr.to_string();
+
+    match attr.trim_start_matches('\\').to_ascii_lowercase().as_str() {
+        "noselect" => FolderAttribute::NoSelect,
+        "noinferiors" => FolderAttribute::NoInferiors,
+        "haschildren" => FolderAttribute::HasChildren,
+        "hasnochildren" => FolderAttribute::HasNoChildren,
+        "nonexistent" => FolderAttribute::NonExistent,
+        "marked" => FolderAttribute::Marked,
+        "unmarked" => FolderAttribute::Unmarked,
+        "subscribed" => FolderAttribute::Subscribed,
+        "remote" => FolderAttribute::Remote,
+        "all" => FolderAttribute::All,
+        "archive" => FolderAttribute::Archive,
+        "drafts" => FolderAttribute::Drafts,
+        "flagged" => FolderAttribute::Flagged,
+        "junk" => FolderAttribute::Junk,
+        "sent" => FolderAttribute::Sent,
+        "trash" => FolderAttribute::Trash,
+        _ => FolderAttribute::Other(attr),
     }
 }
 
 pub fn find_folder_kind_from_imap_attrs(attrs: &[FlagNameAttribute]) -> Option<FolderKind> {
-    attrs.iter().find_map(|attr| {
-        if attr == &FlagNameAttribute::from(Atom::try_from("Sent").unwrap()) {
-            Some(FolderKind::Sent)
-        } else if attr == &FlagNameAttribute::from(Atom::try_from("Drafts").unwrap()) {
-            Some(FolderKind::Drafts)
-        } else if attr == &FlagNameAttribute::from(Atom::try_from("Trash").unwrap()) {
-            Some(FolderKind::Trash)
-        } else {
-            None
-        }
-    })
+    attrs
+        .iter()
+        .map(folder_attr_from_imap_attr)
+        .find_map(|attr| attr.as_folder_kind())
 }
@@ -10,4 +10,10 @@ pub struct FolderListConfig {
     /// A page size of 0 disables the pagination and displays all
     /// available folders.
     pub page_size: Option<usize>,
+
+    /// List only subscribed folders.
+    ///
+    /// This option only applies to backends supporting folder
+    /// subscriptions, like IMAP.
+    pub subscribed_only: Option<bool>,
 }
@@ -1,8 +1,8 @@
 use async_trait::async_trait;
-use tracing::info;
+use tracing::{debug, info};
 
 use super::{Folders, ListFolders};
-use crate::{imap::ImapContext, AnyResult};
+use crate::{folder::FolderAttribute, imap::ImapContext, AnyResult};
 
 #[derive(Debug, Clone)]
 pub struct ListImapFolders {
@@ -31,7 +31,46 @@ async fn list_folders(&self) -> AnyResult<Folders> {
         let config = &self.ctx.account_config;
         let mut client = self.ctx.client().await;
 
+        if config.is_folder_list_subscribed_only() {
+            let folders = client.list_subscribed_mailboxes(config).await?;
+            return Ok(folders);
+        }
+
         let folders = client.list_all_mailboxes(config).await?;
+        Ok(folders)
+    }
+
+    /// List imap folders, marking subscribed ones using the `LSUB`
+    /// command.
+    async fn list_folders_with_subscriptions(&self) -> AnyResult<Folders> {
+        info!("listing imap folders with subscriptions");
+
+        let config = &self.ctx.account_config;
+        let mut client = self.ctx.client().await;
+
+        if config.is_folder_list_subscribed_only() {
+            let folders = client.list_subscribed_mailboxes(config).await?;
+            return Ok(folders);
+        }
+
+        let mut folders = client.list_all_mailboxes(config).await?;
+
+        match client.list_subscribed_mailboxes(config).await {
+            Ok(subscribed) => {
+                for folder in folders.iter_mut() {
+                    let is_subscribed = subscribed
+                        .iter()
+                        .any(|sub| sub.is_subscribed() && sub.name == folder.name);
+
+                    if is_subscribed {
+                        folder.attrs.insert(FolderAttribute::Subscribed);
+                    }
+                }
+            }
+            Err(err) => {
+                debug!(?err, "cannot list subscribed imap folders, skipping");
+            }
+        }
 
         Ok(folders)
     }
@@ -15,4 +15,15 @@
 pub trait ListFolders: Send + Sync {
     /// List all available folders (alias mailboxes).
     async fn list_folders(&self) -> AnyResult<Folders>;
+
+    /// List all available folders, marking subscribed ones with
+    /// [`FolderAttribute::Subscribed`](super::FolderAttribute).
+    ///
+    /// The default implementation lists folders, which is fine for
+    /// backends without folder subscriptions. Backends supporting
+    /// subscriptions should retrieve the subscription state, which
+    /// may cost an extra request.
+    async fn list_folders_with_subscriptions(&self) -> AnyResult<Folders> {
+        self.list_folders().await
+    }
 }
@@ -18,7 +18,15 @@ impl Folders {
     ///
     /// Folders are parsed in parallel, using [`rayon`]. Only parses
     /// direct submaildirs (no recursion).
+    ///
+    /// The hierarchy delimiter is `.` for Maildir++, otherwise `/`.
     pub fn from_maildir_context(ctx: &MaildirContext) -> Self {
+        let delim = if ctx.maildir_config.maildirpp {
+            '.'
+        } else {
+            '/'
+        };
+
         Folders::from_iter(ctx.root.iter().map(|entry| {
             Folder {
                 kind: ctx
@@ -27,6 +35,8 @@ pub fn from_maildir_context(ctx: &MaildirContext) -> Self {
                     .or_else(|| entry.name.parse().ok()),
                 name: entry.name,
                 desc: entry.maildir.path().display().to_string(),
+                delim: Some(delim),
+                attrs: Default::default(),
             }
         }))
     }
@@ -45,6 +55,11 @@ pub fn try_from_maildir(config: &AccountConfig, mdir: Maildir) -> Result<Self> {
             .or_else(|| name.parse().ok());
         let desc = mdir.path().display().to_string();
 
-        Ok(Folder { kind, name, desc })
+        Ok(Folder {
+            kind,
+            name,
+            desc,
+            ..Default::default()
+        })
     }
 }
@@ -3,16 +3,19 @@
 //! Module dedicated to folder (as known as mailbox) management.
 //!
 //! The main entities are [`FolderKind`], [`Folder`] and [`Folders`].
+//! Folders can be arranged as a tree using the [`tree`] module.
 //!
 //! The [`config`] module exposes all the folder configuration used by
 //! the account configuration.
 //!
 //! Backend features reside in their own module as well: [`add`],
-//! [`list`], [`expunge`], [`purge`], [`delete`].
+//! [`list`], [`status`], [`expunge`], [`purge`], [`rename`],
+//! [`subscribe`], [`delete`].
 //!
 //! Finally, the [`sync`] module contains everything needed to
 //! synchronize a remote folder with a local one.
 pub mod add;
+pub mod annotation;
 pub mod config;
 pub mod delete;
 mod error;
@@ -23,10 +26,15 @@
 #[cfg(feature = "maildir")]
 pub mod maildir;
 pub mod purge;
+pub mod rename;
+pub mod status;
+pub mod subscribe;
 #[cfg(feature = "sync")]
 pub mod sync;
+pub mod tree;
 
 use std::{
+    collections::BTreeSet,
     fmt,
     hash::Hash,
     ops::{Deref, DerefMut},
@@ -44,6 +52,10 @@
 pub const DRAFT: &str = "Drafts";
 pub const DRAFTS: &str = "Drafts";
 pub const TRASH: &str = "Trash";
+pub const ARCHIVE: &str = "Archive";
+pub const JUNK: &str = "Junk";
+pub const ALL: &str = "All";
+pub const FLAGGED: &str = "Flagged";
 
 /// The folder kind enumeration.
 ///
@@ -54,6 +66,10 @@
 /// [`FolderConfig::aliases`](crate::folder::config::FolderConfig)
 /// allows users to map custom folder names but also to map the
 /// following folder kinds.
+///
+/// Special-use kinds (archive, junk, all and flagged) are only set
+/// from IMAP special-use attributes: folder names are never parsed
+/// into them.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum FolderKind {
     /// The kind of folder that contains received emails.
@@ -80,6 +96,30 @@ pub enum FolderKind {
     /// in this folder are supposed to be deleted.
     Trash,
 
+    /// The kind of folder that contains archived emails.
+    ///
+    /// This kind of folder matches the `\Archive` IMAP special-use
+    /// attribute (RFC 6154).
+    Archive,
+
+    /// The kind of folder that contains junk emails.
+    ///
+    /// This kind of folder matches the `\Junk` IMAP special-use
+    /// attribute (RFC 6154).
+    Junk,
+
+    /// The kind of virtual folder that contains all emails.
+    ///
+    /// This kind of folder matches the `\All` IMAP special-use
+    /// attribute (RFC 6154).
+    All,
+
+    /// The kind of virtual folder that contains flagged emails.
+    ///
+    /// This kind of folder matches the `\Flagged` IMAP special-use
+    /// attribute (RFC 6154).
+    Flagged,
+
     /// The user-defined kind of folder.
     ///
     /// This kind of folder represents the alias as defined by the
@@ -112,6 +152,30 @@ pub fn is_trash(&self) -> bool {
         matches!(self, FolderKind::Trash)
     }
 
+    /// Return `true` if the current folder kind matches the Archive
+    /// variant.
+    pub fn is_archive(&self) -> bool {
+        matches!(self, FolderKind::Archive)
+    }
+
+    /// Return `true` if the current folder kind matches the Junk
+    /// variant.
+    pub fn is_junk(&self) -> bool {
+        matches!(self, FolderKind::Junk)
+    }
+
+    /// Return `true` if the current folder kind matches the All
+    /// variant.
+    pub fn is_all(&self) -> bool {
+        matches!(self, FolderKind::All)
+    }
+
+    /// Return `true` if the current folder kind matches the Flagged
+    /// variant.
+    pub fn is_flagged(&self) -> bool {
+        matches!(self, FolderKind::Flagged)
+    }
+
     /// Return `true` if the current folder kind matches the
     /// UserDefined variant.
     pub fn is_user_defined(&self) -> bool {
@@ -161,6 +225,10 @@ pub fn as_str(&self) -> &str {
             Self::Sent => SENT,
             Self::Drafts => DRAFTS,
             Self::Trash => TRASH,
+            Self::Archive => ARCHIVE,
+            Self::Junk => JUNK,
+            Self::All => ALL,
+            Self::Flagged => FLAGGED,
             Self::UserDefined(alias) => alias.as_str(),
         }
     }
@@ -196,6 +264,155 @@ fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     }
 }
 
+/// The folder attribute enumeration.
+///
+/// Attributes give extra information about a folder: its position in
+/// the folders hierarchy and its special use (RFC 6154).
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum FolderAttribute {
+    /// The folder cannot be selected, it is only a container for
+    /// other folders.
+    NoSelect,
+
+    /// The folder cannot have any child.
+    NoInferiors,
+
+    /// The folder has at least one child.
+    HasChildren,
+
+    /// The folder has no child.
+    HasNoChildren,
+
+    /// The folder does not exist, it is only part of the hierarchy
+    /// of an existing child.
+    NonExistent,
+
+    /// The folder has been marked as interesting by the server.
+    Marked,
+
+    /// The folder has not been marked as interesting by the server.
+    Unmarked,
+
+    /// The folder is subscribed.
+    Subscribed,
+
+    /// The folder is a remote one.
+    Remote,
+
+    /// The folder contains all emails (special use).
+    All,
+
+    /// The folder contains archived emails (special use).
+    Archive,
+
+    /// The folder contains drafts (special use).
+    Drafts,
+
+    /// The folder contains flagged emails (special use).
+    Flagged,
+
+    /// The folder contains junk emails (special use).
+    Junk,
+
+    /// The folder contains sent emails (special use).
+    Sent,
+
+    /// The folder contains trashed emails (special use).
+    Trash,
+
+    /// Any other attribute, as advertised by the backend.
+    Other(String),
+}
+
+impl FolderAttribute {
+    /// Return `true` if the attribute is a special-use one, as
+    /// defined in RFC 6154.
+    pub fn is_special_use(&self) -> bool {
+        self.as_folder_kind().is_some()
+    }
+
+    /// Return the folder kind matching the special-use attribute, if
+    /// any.
+    pub fn as_folder_kind(&self) -> Option<FolderKind> {
+        match self {
+            Self::All => Some(FolderKind::All),
+            Self::Archive => Some(FolderKind::Archive),
+            Self::Drafts => Some(FolderKind::Drafts),
+            Self::Flagged => Some(FolderKind::Flagged),
+            Self::Junk => Some(FolderKind::Junk),
+            Self::Sent => Some(FolderKind::Sent),
+            Self::Trash => Some(FolderKind::Trash),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FolderAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoSelect => write!(f, "NoSelect"),
+            Self::NoInferiors => write!(f, "NoInferiors"),
+            Self::HasChildren => write!(f, "HasChildren"),
+            Self::HasNoChildren => write!(f, "HasNoChildren"),
+            Self::NonExistent => write!(f, "NonExistent"),
+            Self::Marked => write!(f, "Marked"),
+            Self::Unmarked => write!(f, "Unmarked"),
+            Self::Subscribed => write!(f, "Subscribed"),
+            Self::Remote => write!(f, "Remote"),
+            Self::All => write!(f, "All"),
+            Self::Archive => write!(f, "Archive"),
+            Self::Drafts => write!(f, "Drafts"),
+            Self::Flagged => write!(f, "Flagged"),
+            Self::Junk => write!(f, "Junk"),
+            Self::Sent => write!(f, "Sent"),
+            Self::Trash => write!(f, "Trash"),
+            Self::Other(attr) => write!(f, "{attr}"),
+        }
+    }
+}
+
+/// The set of folder attributes.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct FolderAttributes(BTreeSet<FolderAttribute>);
+
+impl FolderAttributes {
+    /// Return the folder kind matching the first special-use
+    /// attribute, if any.
+    pub fn find_folder_kind(&self) -> Option<FolderKind> {
+        self.iter().find_map(FolderAttribute::as_folder_kind)
+    }
+}
+
+impl Deref for FolderAttributes {
+    type Target = BTreeSet<FolderAttribute>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for FolderAttributes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl FromIterator<FolderAttribute> for FolderAttributes {
+    fn from_iter<T: IntoIterator<Item = FolderAttribute>>(iter: T) -> Self {
+        Self(BTreeSet::from_iter(iter))
+    }
+}
+
 /// The folder structure.
 ///
 /// The folder is just a container for emails. Depending on the
@@ -214,6 +431,15 @@ pub struct Folder {
     /// The description depends on the backend used: it can be IMAP
     /// attributes or Maildir path.
     pub desc: String,
+
+    /// The folder hierarchy delimiter.
+    ///
+    /// The delimiter separates the folder name into hierarchy
+    /// levels. A folder without delimiter is a flat one.
+    pub delim: Option<char>,
+
+    /// The folder attributes.
+    pub attrs: FolderAttributes,
 }
 
 impl Folder {
@@ -249,6 +475,46 @@ pub fn is_trash(&self) -> bool {
             .unwrap_or_default()
     }
 
+    /// Return `true` if the folder can be selected, which means it
+    /// can contain emails.
+    pub fn is_selectable(&self) -> bool {
+        !self.attrs.contains(&FolderAttribute::NoSelect)
+            && !self.attrs.contains(&FolderAttribute::NonExistent)
+    }
+
+    /// Return `true` if the folder is subscribed.
+    pub fn is_subscribed(&self) -> bool {
+        self.attrs.contains(&FolderAttribute::Subscribed)
+    }
+
+    /// Return `true` if the folder is known to have children.
+    pub fn has_children(&self) -> bool {
+        self.attrs.contains(&FolderAttribute::HasChildren)
+    }
+
+    /// Return the hierarchy levels of the folder name, split by the
+    /// folder delimiter.
+    pub fn path(&self) -> Vec<&str> {
+        match self.delim {
+            Some(delim) => self.name.split(delim).collect(),
+            None => vec![self.name.as_str()],
+        }
+    }
+
+    /// Return the last hierarchy level of the folder name.
+    pub fn basename(&self) -> &str {
+        match self.delim {
+            Some(delim) => self.name.rsplit(delim).next().unwrap_or(&self.name),
+            None => &self.name,
+        }
+    }
+
+    /// Return the name of the parent folder, if any.
+    pub fn parent_name(&self) -> Option<&str> {
+        let delim = self.delim?;
+        self.name.rsplit_once(delim).map(|(parent, _)| parent)
+    }
+
     /// Return the folder kind as string slice if existing, otherwise
     /// return the folder name as string slice.
     pub fn get_kind_or_name(&self) -> &str {
@@ -341,6 +607,7 @@ fn folder_inbox_foo() -> Folder {
             kind: Some(FolderKind::Inbox),
             name: "foo".to_owned(),
             desc: "1".to_owned(),
+            ..Default::default()
         }
     }
     fn folder_none_foo() -> Folder {
@@ -348,6 +615,7 @@ fn folder_none_foo() -> Folder {
             kind: None,
             name: "foo".to_owned(),
             desc: "2".to_owned(),
+            ..Default::default()
         }
     }
     fn folder_none_bar() -> Folder {
@@ -355,6 +623,7 @@ fn folder_none_bar() -> Folder {
             kind: None,
             name: "bar".to_owned(),
             desc: "3".to_owned(),
+            ..Default::default()
         }
     }
     fn folder_inbox_bar() -> Folder {
@@ -362,6 +631,7 @@ fn folder_inbox_bar() -> Folder {
             kind: Some(FolderKind::Inbox),
             name: "bar".to_owned(),
             desc: "4".to_owned(),
+            ..Default::default()
         }
     }
 
@@ -400,4 +670,26 @@ fn folder_none_foo_not_equals_none_bar_test() {
     fn folder_none_foo_not_equals_none_bar_test_hash() {
         assert_ne!(hash(folder_none_foo()), hash(folder_none_bar()));
     }
+
+    #[test]
+    fn folder_hierarchy() {
+        let folder = Folder {
+            name: "a/b/c".to_owned(),
+            delim: Some('/'),
+            ..Default::default()
+        };
+
+        assert_eq!(folder.path(), vec!["a", "b", "c"]);
+        assert_eq!(folder.basename(), "c");
+        assert_eq!(folder.parent_name(), Some("a/b"));
+
+        let folder = Folder {
+            name: "a/b".to_owned(),
+            ..Default::default()
+        };
+
+        assert_eq!(folder.path(), vec!["a/b"]);
+        assert_eq!(folder.basename(), "a/b");
+        assert_eq!(folder.parent_name(), None);
+    }
 }
@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use tracing::{debug, info};
+use utf7_imap::encode_utf7_imap as encode_utf7;
+
+use super::RenameFolder;
+use crate::{imap::ImapContext, AnyResult};
+
+#[derive(Debug)]
+pub struct RenameImapFolder {
+    ctx: ImapContext,
+}
+
+impl RenameImapFolder {
+    pub fn new(ctx: &ImapContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &ImapContext) -> Box<dyn RenameFolder> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn RenameFolder>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl RenameFolder for RenameImapFolder {
+    async fn rename_folder(&self, folder: &str, new_folder: &str) -> AnyResult<()> {
+        info!("renaming imap folder {folder} to {new_folder}");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        let new_folder = config.get_folder_alias(new_folder);
+        let new_folder_encoded = encode_utf7(new_folder.clone());
+        debug!("utf7 encoded new folder: {new_folder_encoded}");
+
+        client
+            .rename_mailbox(&folder_encoded, &new_folder_encoded)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use tracing::info;
+
+use super::RenameFolder;
+use crate::{
+    folder::{
+        error::{Error, Result},
+        FolderKind,
+    },
+    maildir::{MaildirContext, MaildirContextSync},
+    AnyResult,
+};
+
+pub struct RenameMaildirFolder {
+    ctx: MaildirContextSync,
+}
+
+impl RenameMaildirFolder {
+    pub fn new(ctx: &MaildirContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &MaildirContextSync) -> Box<dyn RenameFolder> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &MaildirContextSync) -> Option<Box<dyn RenameFolder>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl RenameFolder for RenameMaildirFolder {
+    async fn rename_folder(&self, folder: &str, new_folder: &str) -> AnyResult<()> {
+        info!("renaming maildir folder {folder} to {new_folder}");
+
+        let ctx = self.ctx.lock().await;
+        rename_maildir_folder(&ctx, folder, new_folder)?;
+
+        Ok(())
+    }
+}
+
+/// Rename the given Maildir folder, including its subfolders.
+///
+/// Subfolders are nested directories when Maildir++ is disabled, so
+/// they are renamed together with their parent. When Maildir++ is
+/// enabled, subfolders are sibling directories sharing the same
+/// prefix, so they are renamed one by one.
+///
+/// Returns the list of renamed directories, as source and target
+/// paths.
+pub fn rename_maildir_folder(
+    ctx: &MaildirContext,
+    folder: &str,
+    new_folder: &str,
+) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let config = &ctx.account_config;
+    let maildirpp = ctx.maildir_config.maildirpp;
+
+    let folder = config.get_folder_alias(folder);
+    let new_folder = config.get_folder_alias(new_folder);
+
+    if maildirpp && (FolderKind::matches_inbox(&folder) || FolderKind::matches_inbox(&new_folder)) {
+        let path = ctx.root.path().to_owned();
+        return Err(Error::RenameMaildirInboxForbiddenError(path));
+    }
+
+    if ctx.root.get(&new_folder).is_ok() {
+        return Err(Error::RenameMaildirFolderAlreadyExistsError(new_folder));
+    }
+
+    let from = ctx
+        .root
+        .get(&folder)
+        .map_err(|err| Error::GetMaildirFolderError(err, folder.clone()))?
+        .path()
+        .to_owned();
+
+    // NOTE: the target path is computed by creating the target
+    // folder, which also creates missing parent folders. The empty
+    // target folder is then replaced by the source one.
+    let to = ctx
+        .root
+        .create(&new_folder)
+        .map_err(|err| {
+            Error::CreateFolderStructureMaildirError(err, ctx.root.path().join(&new_folder))
+        })?
+        .path()
+        .to_owned();
+
+    fs::remove_dir_all(&to)
+        .map_err(|err| Error::RenameMaildirFolderError(err, from.clone(), to.clone()))?;
+
+    let mut renamed = vec![(from.clone(), to.clone())];
+
+    if maildirpp {
+        renamed.extend(find_maildirpp_subfolders(ctx.root.path(), &from, &to)?);
+    }
+
+    for (from, to) in &renamed {
+        fs::rename(from, to)
+            .map_err(|err| Error::RenameMaildirFolderError(err, from.clone(), to.clone()))?;
+    }
+
+    Ok(renamed)
+}
+
+/// Find Maildir++ subfolders of the given folder, and compute their
+/// new path.
+///
+/// Maildir++ subfolders are directories named after their parent
+/// directory, followed by a dot and the subfolder name.
+fn find_maildirpp_subfolders(
+    root: &Path,
+    from: &Path,
+    to: &Path,
+) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let (Some(from_name), Some(to_name)) = (from.file_name(), to.file_name()) else {
+        return Ok(Vec::new());
+    };
+
+    let prefix = format!("{}.", from_name.to_string_lossy());
+    let to_name = to_name.to_string_lossy();
+
+    let entries = fs::read_dir(root)
+        .map_err(|err| Error::RenameMaildirFolderError(err, from.to_owned(), to.to_owned()))?;
+
+    let subfolders = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let suffix = name.to_str()?.strip_prefix(&prefix)?;
+            let to = root.join(format!("{to_name}.{suffix}"));
+            Some((entry.path(), to))
+        })
+        .collect();
+
+    Ok(subfolders)
+}
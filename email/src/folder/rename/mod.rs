@@ -0,0 +1,20 @@
+#[cfg(feature = "imap")]
+pub mod imap;
+#[cfg(feature = "maildir")]
+pub mod maildir;
+#[cfg(feature = "notmuch")]
+pub mod notmuch;
+
+use async_trait::async_trait;
+
+use crate::AnyResult;
+
+#[async_trait]
+pub trait RenameFolder: Send + Sync {
+    /// Rename the given folder.
+    ///
+    /// The new folder name can be located under another parent
+    /// folder, which moves the folder in the hierarchy. Emails and
+    /// subfolders contained in the given folder are preserved.
+    async fn rename_folder(&self, folder: &str, new_folder: &str) -> AnyResult<()>;
+}
@@ -0,0 +1,89 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use tracing::{debug, info};
+
+use super::{maildir::rename_maildir_folder, RenameFolder};
+use crate::{folder::error::Error, notmuch::NotmuchContextSync, AnyResult};
+
+pub struct RenameNotmuchFolder {
+    ctx: NotmuchContextSync,
+}
+
+impl RenameNotmuchFolder {
+    pub fn new(ctx: &NotmuchContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &NotmuchContextSync) -> Box<dyn RenameFolder> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &NotmuchContextSync) -> Option<Box<dyn RenameFolder>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl RenameFolder for RenameNotmuchFolder {
+    async fn rename_folder(&self, folder: &str, new_folder: &str) -> AnyResult<()> {
+        info!("renaming notmuch folder {folder} to {new_folder} via maildir");
+
+        let ctx = self.ctx.lock().await;
+        let renamed = rename_maildir_folder(&ctx.mdir_ctx, folder, new_folder)?;
+
+        // the database references messages by their path, so
+        // renamed messages need to be indexed again, then their old
+        // path removed from the database
+        let db = ctx.open_db()?;
+
+        for (from, to) in renamed {
+            for path in find_maildir_entries(&to) {
+                let Ok(rel) = path.strip_prefix(&to) else {
+                    continue;
+                };
+
+                db.index_file(&path, None)
+                    .map_err(|err| Error::IndexRenamedNotmuchMessageError(err, path.clone()))?;
+
+                if let Err(err) = db.remove_message(from.join(rel)) {
+                    debug!(?err, ?path, "cannot remove renamed notmuch message");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Recursively find Maildir entries located under the given path.
+fn find_maildir_entries(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    let mut paths = Vec::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            paths.extend(find_maildir_entries(&path));
+            continue;
+        }
+
+        let in_mdir = path
+            .parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == "cur" || name == "new");
+
+        if in_mdir {
+            paths.push(path);
+        }
+    }
+
+    paths
+}
@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use futures::future::join_all;
+use imap_client::imap_next::imap_types::status::StatusDataItem;
+use tracing::{debug, info};
+use utf7_imap::encode_utf7_imap as encode_utf7;
+
+use super::{FolderStatus, FolderWithStatus, GetFolderStatus, ListFoldersWithStatus};
+use crate::{imap::ImapContext, AnyResult};
+
+impl FolderStatus {
+    /// Build a folder status from IMAP STATUS data items.
+    pub fn from_imap_status_items(items: &[StatusDataItem]) -> Self {
+        let mut status = FolderStatus::default();
+
+        for item in items {
+            match item {
+                StatusDataItem::Messages(count) => status.total = *count,
+                StatusDataItem::Recent(count) => status.recent = *count,
+                StatusDataItem::Unseen(count) => status.unseen = *count,
+                StatusDataItem::UidNext(uid) => status.uid_next = Some(uid.get()),
+                StatusDataItem::UidValidity(uid) => status.uid_validity = Some(uid.get()),
+                _ => (),
+            }
+        }
+
+        status
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GetImapFolderStatus {
+    ctx: ImapContext,
+}
+
+impl GetImapFolderStatus {
+    pub fn new(ctx: &ImapContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &ImapContext) -> Box<dyn GetFolderStatus> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn GetFolderStatus>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl GetFolderStatus for GetImapFolderStatus {
+    async fn get_folder_status(&self, folder: &str) -> AnyResult<FolderStatus> {
+        info!("getting imap folder {folder} status");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        // NOTE: the size is left empty until imap-types supports
+        // the STATUS=SIZE extension (RFC 8438)
+        let status = client.status_mailbox(&folder_encoded).await?;
+
+        Ok(status)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ListImapFoldersWithStatus {
+    ctx: ImapContext,
+}
+
+impl ListImapFoldersWithStatus {
+    pub fn new(ctx: &ImapContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &ImapContext) -> Box<dyn ListFoldersWithStatus> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn ListFoldersWithStatus>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl ListFoldersWithStatus for ListImapFoldersWithStatus {
+    async fn list_folders_with_status(&self) -> AnyResult<Vec<FolderWithStatus>> {
+        info!("listing imap folders with status");
+
+        let config = &self.ctx.account_config;
+        let folders = self.ctx.client().await.list_all_mailboxes(config).await?;
+
+        // NOTE: imap-types does not support the LIST RETURN options
+        // yet, so LIST-STATUS (RFC 5819) cannot be used: one STATUS
+        // command is sent per folder instead, spread across the
+        // clients of the pool.
+        let statuses = join_all(folders.iter().filter(|folder| folder.is_selectable()).map(
+            |folder| async move {
+                let mbox = encode_utf7(folder.name.clone());
+                let mut client = self.ctx.client().await;
+                let status = client.status_mailbox(&mbox).await;
+                (folder.clone(), status)
+            },
+        ))
+        .await;
+
+        let mut folders = Vec::with_capacity(statuses.len());
+
+        for (folder, status) in statuses {
+            folders.push((folder, status?));
+        }
+
+        Ok(folders)
+    }
+}
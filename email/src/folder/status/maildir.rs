@@ -0,0 +1,128 @@
+use std::fs;
+
+use async_trait::async_trait;
+use maildirs::Maildir;
+use tracing::info;
+
+use super::{FolderStatus, FolderWithStatus, GetFolderStatus, ListFoldersWithStatus};
+use crate::{
+    folder::{Error, Folders, Result},
+    maildir::MaildirContextSync,
+    AnyResult,
+};
+
+impl FolderStatus {
+    /// Build a folder status from the entries of the given maildir.
+    ///
+    /// Entries located in the `new` directory are considered as
+    /// recent and unseen. Entries located in the `cur` directory are
+    /// considered as unseen if they do not have the seen flag.
+    pub fn from_maildir(mdir: &Maildir, name: &str) -> Result<Self> {
+        let entries = mdir
+            .read()
+            .map_err(|err| Error::ReadMaildirFolderEntriesError(err, name.to_owned()))?;
+
+        let mut status = FolderStatus {
+            size: Some(0),
+            ..Default::default()
+        };
+
+        for entry in entries {
+            status.total += 1;
+
+            let is_new = entry
+                .path()
+                .parent()
+                .and_then(|dir| dir.file_name())
+                .map(|dir| dir == "new")
+                .unwrap_or_default();
+
+            if is_new {
+                status.recent += 1;
+                status.unseen += 1;
+            } else {
+                let seen = entry
+                    .flags()
+                    .map(|flags| flags.contains(&maildirs::Flag::Seen))
+                    .unwrap_or_default();
+
+                if !seen {
+                    status.unseen += 1;
+                }
+            }
+
+            if let (Some(size), Ok(metadata)) = (status.size.as_mut(), fs::metadata(entry.path())) {
+                *size += metadata.len();
+            }
+        }
+
+        Ok(status)
+    }
+}
+
+pub struct GetMaildirFolderStatus {
+    ctx: MaildirContextSync,
+}
+
+impl GetMaildirFolderStatus {
+    pub fn new(ctx: &MaildirContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &MaildirContextSync) -> Box<dyn GetFolderStatus> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &MaildirContextSync) -> Option<Box<dyn GetFolderStatus>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl GetFolderStatus for GetMaildirFolderStatus {
+    async fn get_folder_status(&self, folder: &str) -> AnyResult<FolderStatus> {
+        info!("getting maildir folder {folder} status");
+
+        let ctx = self.ctx.lock().await;
+        let mdir = ctx.get_maildir_from_folder_alias(folder)?;
+        let status = FolderStatus::from_maildir(&mdir, folder)?;
+
+        Ok(status)
+    }
+}
+
+pub struct ListMaildirFoldersWithStatus {
+    ctx: MaildirContextSync,
+}
+
+impl ListMaildirFoldersWithStatus {
+    pub fn new(ctx: &MaildirContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &MaildirContextSync) -> Box<dyn ListFoldersWithStatus> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &MaildirContextSync) -> Option<Box<dyn ListFoldersWithStatus>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl ListFoldersWithStatus for ListMaildirFoldersWithStatus {
+    async fn list_folders_with_status(&self) -> AnyResult<Vec<FolderWithStatus>> {
+        info!("listing maildir folders with status");
+
+        let ctx = self.ctx.lock().await;
+        let mut folders = Vec::new();
+
+        for folder in Folders::from_maildir_context(&ctx) {
+            let mdir = ctx.get_maildir_from_folder_name(&folder.name)?;
+            let status = FolderStatus::from_maildir(&mdir, &folder.name)?;
+            folders.push((folder, status));
+        }
+
+        Ok(folders)
+    }
+}
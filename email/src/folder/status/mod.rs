@@ -0,0 +1,73 @@
+//! # Folder status
+//!
+//! Module dedicated to folder status. The main structure of this
+//! module is [`FolderStatus`], which gathers message counts and sizes
+//! of a folder without having to list its envelopes.
+//!
+//! The [`GetFolderStatus`] feature returns the status of one folder,
+//! whereas the [`ListFoldersWithStatus`] feature returns all
+//! selectable folders alongside their status.
+
+#[cfg(feature = "imap")]
+pub mod imap;
+#[cfg(feature = "maildir")]
+pub mod maildir;
+#[cfg(feature = "notmuch")]
+pub mod notmuch;
+
+use async_trait::async_trait;
+
+use super::Folder;
+use crate::AnyResult;
+
+/// The folder status.
+///
+/// Fields that are not supported by a backend are left to their
+/// default value.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct FolderStatus {
+    /// The total number of messages in the folder.
+    pub total: u32,
+
+    /// The number of messages without the seen flag.
+    pub unseen: u32,
+
+    /// The number of recent messages.
+    ///
+    /// For Maildir, recent messages are the ones located in the
+    /// `new` directory.
+    pub recent: u32,
+
+    /// The next unique identifier value (IMAP only).
+    pub uid_next: Option<u32>,
+
+    /// The unique identifier validity value (IMAP only).
+    pub uid_validity: Option<u32>,
+
+    /// The total size of the folder messages, in bytes.
+    ///
+    /// The size is not available for IMAP, since imap-types does not
+    /// support the `STATUS=SIZE` extension (RFC 8438) yet, and
+    /// summing the sizes of all messages would be too costly.
+    pub size: Option<u64>,
+}
+
+/// The folder associated to its status.
+pub type FolderWithStatus = (Folder, FolderStatus);
+
+#[async_trait]
+pub trait GetFolderStatus: Send + Sync {
+    /// Get the status of the given folder.
+    async fn get_folder_status(&self, folder: &str) -> AnyResult<FolderStatus>;
+}
+
+#[async_trait]
+pub trait ListFoldersWithStatus: Send + Sync {
+    /// List all available selectable folders alongside their status.
+    async fn list_folders_with_status(&self) -> AnyResult<Vec<FolderWithStatus>>;
+}
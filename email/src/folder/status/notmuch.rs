@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use notmuch::Database;
+use tracing::{debug, info};
+
+use super::{FolderStatus, FolderWithStatus, GetFolderStatus, ListFoldersWithStatus};
+use crate::{
+    folder::{Error, FolderKind, Folders, Result},
+    notmuch::NotmuchContextSync,
+    AnyResult,
+};
+
+impl FolderStatus {
+    /// Build a folder status by counting the notmuch messages of the
+    /// given folder.
+    ///
+    /// Notmuch does not have the notion of recent messages, nor
+    /// unique identifiers.
+    pub fn from_notmuch_db(db: &Database, folder: &str, maildirpp: bool) -> Result<Self> {
+        let query = if maildirpp && FolderKind::matches_inbox(folder) {
+            String::from("folder:\"\"")
+        } else {
+            format!("folder:{folder:?}")
+        };
+
+        let count = |query: String| {
+            debug!("counting notmuch messages matching query {query}");
+            db.create_query(&query)
+                .and_then(|query| query.count_messages())
+                .map_err(|err| Error::CountNotmuchMessagesError(err, query))
+        };
+
+        let total = count(query.clone())?;
+        let unseen = count(format!("{query} and tag:unread"))?;
+
+        Ok(FolderStatus {
+            total,
+            unseen,
+            ..Default::default()
+        })
+    }
+}
+
+pub struct GetNotmuchFolderStatus {
+    ctx: NotmuchContextSync,
+}
+
+impl GetNotmuchFolderStatus {
+    pub fn new(ctx: &NotmuchContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &NotmuchContextSync) -> Box<dyn GetFolderStatus> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &NotmuchContextSync) -> Option<Box<dyn GetFolderStatus>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl GetFolderStatus for GetNotmuchFolderStatus {
+    async fn get_folder_status(&self, folder: &str) -> AnyResult<FolderStatus> {
+        info!("getting notmuch folder {folder} status");
+
+        let ctx = self.ctx.lock().await;
+        let folder = ctx.account_config.get_folder_alias(folder);
+        let db = ctx.open_db()?;
+
+        let status = FolderStatus::from_notmuch_db(&db, &folder, ctx.maildirpp())?;
+        db.close()
+            .map_err(crate::notmuch::Error::CloseDatabaseError)?;
+
+        Ok(status)
+    }
+}
+
+pub struct ListNotmuchFoldersWithStatus {
+    ctx: NotmuchContextSync,
+}
+
+impl ListNotmuchFoldersWithStatus {
+    pub fn new(ctx: &NotmuchContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &NotmuchContextSync) -> Box<dyn ListFoldersWithStatus> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &NotmuchContextSync) -> Option<Box<dyn ListFoldersWithStatus>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl ListFoldersWithStatus for ListNotmuchFoldersWithStatus {
+    async fn list_folders_with_status(&self) -> AnyResult<Vec<FolderWithStatus>> {
+        info!("listing notmuch folders with status");
+
+        let ctx = self.ctx.lock().await;
+        let db = ctx.open_db()?;
+        let mut folders = Vec::new();
+
+        for folder in Folders::from_maildir_context(&ctx.mdir_ctx) {
+            let status = FolderStatus::from_notmuch_db(&db, &folder.name, ctx.maildirpp())?;
+            folders.push((folder, status));
+        }
+
+        db.close()
+            .map_err(crate::notmuch::Error::CloseDatabaseError)?;
+
+        Ok(folders)
+    }
+}
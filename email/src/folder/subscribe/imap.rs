@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use tracing::{debug, info};
+use utf7_imap::encode_utf7_imap as encode_utf7;
+
+use super::{SubscribeFolder, UnsubscribeFolder};
+use crate::{imap::ImapContext, AnyResult};
+
+#[derive(Debug, Clone)]
+pub struct SubscribeImapFolder {
+    ctx: ImapContext,
+}
+
+impl SubscribeImapFolder {
+    pub fn new(ctx: &ImapContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &ImapContext) -> Box<dyn SubscribeFolder> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn SubscribeFolder>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl SubscribeFolder for SubscribeImapFolder {
+    async fn subscribe_folder(&self, folder: &str) -> AnyResult<()> {
+        info!("subscribing to imap folder {folder}");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        client.subscribe_mailbox(folder_encoded).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnsubscribeImapFolder {
+    ctx: ImapContext,
+}
+
+impl UnsubscribeImapFolder {
+    pub fn new(ctx: &ImapContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &ImapContext) -> Box<dyn UnsubscribeFolder> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn UnsubscribeFolder>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl UnsubscribeFolder for UnsubscribeImapFolder {
+    async fn unsubscribe_folder(&self, folder: &str) -> AnyResult<()> {
+        info!("unsubscribing from imap folder {folder}");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        client.unsubscribe_mailbox(folder_encoded).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+#[cfg(feature = "imap")]
+pub mod imap;
+
+use async_trait::async_trait;
+
+use crate::AnyResult;
+
+#[async_trait]
+pub trait SubscribeFolder: Send + Sync {
+    /// Subscribe to the given folder.
+    ///
+    /// Subscribed folders can be listed using
+    /// [`FolderListConfig::subscribed_only`](super::list::config::FolderListConfig),
+    /// and synchronized using
+    /// [`FolderSyncStrategy::Subscribed`](super::sync::config::FolderSyncStrategy).
+    async fn subscribe_folder(&self, folder: &str) -> AnyResult<()>;
+}
+
+#[async_trait]
+pub trait UnsubscribeFolder: Send + Sync {
+    /// Unsubscribe from the given folder.
+    async fn unsubscribe_folder(&self, folder: &str) -> AnyResult<()>;
+}
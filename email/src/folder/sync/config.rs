@@ -2,6 +2,8 @@
 
 use std::collections::BTreeSet;
 
+use crate::folder::Folder;
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(
     feature = "derive",
@@ -34,14 +36,42 @@ pub enum FolderSyncStrategy {
     /// Synchronizes all folders except the ones matching the given
     /// names.
     Exclude(BTreeSet<String>),
+
+    /// Synchronizes only subscribed folders.
+    ///
+    /// This strategy needs to be resolved into an
+    /// [`FolderSyncStrategy::Include`] one before being used, see
+    /// [`FolderSyncStrategy::from_subscribed_folders`]. Unresolved,
+    /// it matches no folder.
+    Subscribed,
 }
 
 impl FolderSyncStrategy {
+    /// Build an include strategy from the subscribed folders of the
+    /// given list.
+    ///
+    /// Both folder names and folder kinds are included, so that the
+    /// strategy matches whatever the folder is referenced by.
+    pub fn from_subscribed_folders<'a>(folders: impl IntoIterator<Item = &'a Folder>) -> Self {
+        let names = folders
+            .into_iter()
+            .filter(|folder| folder.is_subscribed())
+            .flat_map(|folder| [folder.name.clone(), folder.get_kind_or_name().to_owned()])
+            .collect();
+
+        FolderSyncStrategy::Include(names)
+    }
+
+    pub fn is_subscribed(&self) -> bool {
+        matches!(self, FolderSyncStrategy::Subscribed)
+    }
+
     pub fn matches(&self, folder: &str) -> bool {
         match self {
             FolderSyncStrategy::All => true,
             FolderSyncStrategy::Include(folders) => folders.contains(folder),
             FolderSyncStrategy::Exclude(folders) => !folders.contains(folder),
+            FolderSyncStrategy::Subscribed => false,
         }
     }
 }
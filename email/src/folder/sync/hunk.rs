@@ -32,6 +32,10 @@ pub enum FolderSyncHunk {
     /// The given folder needs to be removed from the cache for the
     /// given destination.
     Uncache(FolderName, SyncDestination),
+
+    /// The given folder needs to be renamed to the given new folder
+    /// name to the given destination.
+    Rename(FolderName, FolderName, SyncDestination),
 }
 
 impl FolderSyncHunk {
@@ -41,6 +45,7 @@ pub fn is_left(&self) -> bool {
             Self::Cache(_, SyncDestination::Left) => true,
             Self::Delete(_, SyncDestination::Left) => true,
             Self::Uncache(_, SyncDestination::Left) => true,
+            Self::Rename(_, _, SyncDestination::Left) => true,
             _ => false,
         }
     }
@@ -51,6 +56,7 @@ pub fn is_right(&self) -> bool {
             Self::Cache(_, SyncDestination::Right) => true,
             Self::Delete(_, SyncDestination::Right) => true,
             Self::Uncache(_, SyncDestination::Right) => true,
+            Self::Rename(_, _, SyncDestination::Right) => true,
             _ => false,
         }
     }
@@ -61,6 +67,7 @@ pub fn folder(&self) -> &str {
             Self::Cache(folder, _) => folder.as_str(),
             Self::Delete(folder, _) => folder.as_str(),
             Self::Uncache(folder, _) => folder.as_str(),
+            Self::Rename(_, folder, _) => folder.as_str(),
         }
     }
 }
@@ -76,6 +83,9 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Self::Uncache(folder, target) => {
                 write!(f, "Removing {target} folder {folder} from cache")
             }
+            Self::Rename(folder, new_folder, target) => {
+                write!(f, "Renaming {target} folder {folder} to {new_folder}")
+            }
         }
     }
 }
@@ -15,12 +15,14 @@
 
 use self::{hunk::FolderSyncHunk, report::FolderSyncReport};
 use super::{
-    add::AddFolder, delete::DeleteFolder, expunge::ExpungeFolder, list::ListFolders, Folder,
+    add::AddFolder, delete::DeleteFolder, expunge::ExpungeFolder, list::ListFolders,
+    rename::RenameFolder, Folder,
 };
 #[doc(inline)]
 pub use super::{Error, Result};
 use crate::{
     backend::context::BackendContextBuilder,
+    envelope::list::{ListEnvelopes, ListEnvelopesOptions},
     sync::{pool::SyncPoolContext, SyncDestination, SyncEvent},
 };
 
@@ -43,6 +45,7 @@ pub(crate) async fn sync<L, R>(
         let names = HashSet::<String>::from_iter(
             folders
                 .iter()
+                .filter(|folder| folder.is_selectable())
                 .map(Folder::get_kind_or_name)
                 // TODO: instead of fetching all the folders then
                 // filtering them here, it could be better to filter
@@ -75,6 +78,7 @@ pub(crate) async fn sync<L, R>(
         let names = HashSet::<String>::from_iter(
             folders
                 .iter()
+                .filter(|folder| folder.is_selectable())
                 .map(Folder::get_kind_or_name)
                 // TODO: instead of fetching all the folders then
                 // filtering them here, it could be better to filter
@@ -107,6 +111,7 @@ pub(crate) async fn sync<L, R>(
         let names = HashSet::<String>::from_iter(
             folders
                 .iter()
+                .filter(|folder| folder.is_selectable())
                 .map(Folder::get_kind_or_name)
                 // TODO: instead of fetching all the folders then
                 // filtering them here, it could be better to filter
@@ -139,6 +144,7 @@ pub(crate) async fn sync<L, R>(
         let names: HashSet<String> = HashSet::from_iter(
             folders
                 .iter()
+                .filter(|folder| folder.is_selectable())
                 .map(Folder::get_kind_or_name)
                 // TODO: instead of fetching all the folders then
                 // filtering them here, it could be better to filter
@@ -179,6 +185,32 @@ pub(crate) async fn sync<L, R>(
         right_folders?,
     );
 
+    for target in [SyncDestination::Left, SyncDestination::Right] {
+        let Some((folder, new_folder)) = patch::find_rename(&patch, &target) else {
+            continue;
+        };
+
+        let (ids, new_ids) = match target {
+            SyncDestination::Left => tokio::join!(
+                list_message_ids(&ctx_ref.left, &folder),
+                list_message_ids(&ctx_ref.right, &new_folder),
+            ),
+            SyncDestination::Right => tokio::join!(
+                list_message_ids(&ctx_ref.right, &folder),
+                list_message_ids(&ctx_ref.left, &new_folder),
+            ),
+        };
+
+        if patch::is_rename(
+            ids.iter().map(String::as_str),
+            new_ids.iter().map(String::as_str),
+        ) {
+            patch::rename(&mut patch, &folder, &new_folder, target);
+        } else {
+            debug!("folders {folder} and {new_folder} share no message, skipping rename");
+        }
+    }
+
     ctx_ref.apply_folder_permissions(&mut patch);
 
     SyncEvent::GeneratedFolderPatch(patch.clone())
@@ -230,6 +262,12 @@ pub(crate) async fn sync<L, R>(
                     FolderSyncHunk::Delete(folder, SyncDestination::Right) => {
                         ctx.right.delete_folder(&folder).await?;
                     }
+                    FolderSyncHunk::Rename(folder, new_folder, SyncDestination::Left) => {
+                        ctx.left.rename_folder(&folder, &new_folder).await?;
+                    }
+                    FolderSyncHunk::Rename(folder, new_folder, SyncDestination::Right) => {
+                        ctx.right.rename_folder(&folder, &new_folder).await?;
+                    }
                 };
 
                 Ok(())
@@ -267,6 +305,24 @@ pub(crate) async fn sync<L, R>(
     Ok(report)
 }
 
+/// List the Message-ID of all envelopes from the given folder.
+///
+/// Used as evidence for folder rename detection. Errors are not
+/// fatal: a folder that cannot be listed is simply not renamed.
+async fn list_message_ids(backend: &impl ListEnvelopes, folder: &str) -> Vec<String> {
+    match backend
+        .list_envelopes(folder, ListEnvelopesOptions::default())
+        .await
+    {
+        Ok(envelopes) => envelopes.into_iter().map(|e| e.message_id).collect(),
+        Err(err) => {
+            debug!("cannot list envelopes of folder {folder}: {err}");
+            trace!("{err:?}");
+            vec![]
+        }
+    }
+}
+
 pub(crate) async fn expunge<L, R>(
     ctx_ref: Arc<SyncPoolContext<L::Context, R::Context>>,
     folders: &HashSet<String>,
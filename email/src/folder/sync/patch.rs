@@ -6,7 +6,7 @@
 //! You also have access to a [`FolderSyncPatchManager`] which helps
 //! you to build and to apply a folder patch.
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use super::hunk::{FolderName, FolderSyncHunk, FoldersName};
 use crate::sync::SyncDestination;
@@ -147,6 +147,82 @@ pub fn build(
     BTreeMap::from_iter(patches)
 }
 
+/// Find a folder rename candidate for the given destination.
+///
+/// Folder names alone cannot tell a rename apart from an unrelated
+/// deletion followed by a creation, so only the unambiguous case is
+/// returned: exactly one folder needs to be deleted from and exactly
+/// one folder needs to be created to the given destination. The
+/// candidate must then be confirmed with [`is_rename`] before being
+/// applied with [`rename`].
+pub fn find_rename(
+    patches: &FolderSyncPatches,
+    target: &SyncDestination,
+) -> Option<(FolderName, FolderName)> {
+    let find_folders = |hunk: fn(FolderName, SyncDestination) -> FolderSyncHunk| {
+        patches
+            .iter()
+            .filter(|(folder, patch)| patch.contains(&hunk(folder.to_string(), target.clone())))
+            .map(|(folder, _)| folder.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let deleted = find_folders(FolderSyncHunk::Delete);
+    let created = find_folders(FolderSyncHunk::Create);
+
+    match (deleted.as_slice(), created.as_slice()) {
+        ([folder], [new_folder]) => Some((folder.clone(), new_folder.clone())),
+        _ => None,
+    }
+}
+
+/// Check if two folders contain the same messages.
+///
+/// The Message-ID of the envelopes listed from the folder to delete
+/// and from the folder to create are compared: at least one real
+/// Message-ID needs to be shared for the folders to be considered
+/// as the same folder. Generated Message-IDs are ignored, since they
+/// do not identify a message.
+pub fn is_rename<'a>(
+    message_ids: impl IntoIterator<Item = &'a str>,
+    new_message_ids: impl IntoIterator<Item = &'a str>,
+) -> bool {
+    let is_real = |id: &&str| !id.is_empty() && !id.ends_with("@generated>");
+    let message_ids = HashSet::<&str>::from_iter(message_ids.into_iter().filter(is_real));
+
+    new_message_ids
+        .into_iter()
+        .filter(is_real)
+        .any(|id| message_ids.contains(id))
+}
+
+/// Replace the folder deletion and creation hunks by a rename hunk.
+///
+/// Renaming a folder, instead of deleting then re-creating it,
+/// preserves its emails.
+pub fn rename(
+    patches: &mut FolderSyncPatches,
+    folder: &str,
+    new_folder: &str,
+    target: SyncDestination,
+) {
+    if let Some(patch) = patches.get_mut(folder) {
+        patch.remove(&FolderSyncHunk::Delete(folder.to_owned(), target.clone()));
+    }
+
+    if let Some(patch) = patches.get_mut(new_folder) {
+        patch.remove(&FolderSyncHunk::Create(
+            new_folder.to_owned(),
+            target.clone(),
+        ));
+        patch.insert(FolderSyncHunk::Rename(
+            folder.to_owned(),
+            new_folder.to_owned(),
+            target,
+        ));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::{BTreeMap, BTreeSet};
@@ -420,4 +496,91 @@ fn build_folder_patch() {
             BTreeMap::from_iter([("folder".into(), BTreeSet::from_iter([]))])
         );
     }
+
+    #[test]
+    fn build_folder_patch_with_rename() {
+        // left folder renamed from old to new
+        let mut patches = super::build(
+            FoldersName::from_iter(["old".into()]),
+            FoldersName::from_iter(["new".into()]),
+            FoldersName::from_iter(["old".into()]),
+            FoldersName::from_iter(["old".into()]),
+        );
+
+        let (folder, new_folder) = super::find_rename(&patches, &SyncDestination::Right).unwrap();
+        assert_eq!(folder, "old");
+        assert_eq!(new_folder, "new");
+        assert!(super::find_rename(&patches, &SyncDestination::Left).is_none());
+
+        assert!(super::is_rename(
+            ["<a@localhost>", "<b@localhost>"],
+            ["<b@localhost>"]
+        ));
+        super::rename(&mut patches, &folder, &new_folder, SyncDestination::Right);
+
+        assert_eq!(
+            patches,
+            BTreeMap::from_iter([
+                (
+                    "new".into(),
+                    BTreeSet::from_iter([
+                        FolderSyncHunk::Cache("new".into(), SyncDestination::Left),
+                        FolderSyncHunk::Cache("new".into(), SyncDestination::Right),
+                        FolderSyncHunk::Rename("old".into(), "new".into(), SyncDestination::Right),
+                    ]),
+                ),
+                (
+                    "old".into(),
+                    BTreeSet::from_iter([
+                        FolderSyncHunk::Uncache("old".into(), SyncDestination::Left),
+                        FolderSyncHunk::Uncache("old".into(), SyncDestination::Right),
+                    ]),
+                ),
+            ]),
+        );
+
+        // ambiguous renames fall back to delete and create
+        let patches = super::build(
+            FoldersName::from_iter(["a".into(), "b".into()]),
+            FoldersName::from_iter(["c".into()]),
+            FoldersName::from_iter(["a".into(), "b".into()]),
+            FoldersName::from_iter(["a".into(), "b".into()]),
+        );
+
+        assert!(super::find_rename(&patches, &SyncDestination::Right).is_none());
+        assert!(patches["a"].contains(&FolderSyncHunk::Delete("a".into(), SyncDestination::Right)));
+        assert!(patches["b"].contains(&FolderSyncHunk::Delete("b".into(), SyncDestination::Right)));
+        assert!(patches["c"].contains(&FolderSyncHunk::Create("c".into(), SyncDestination::Right)));
+    }
+
+    #[test]
+    fn build_folder_patch_without_rename() {
+        // left folder old deleted and unrelated folder new created
+        let patches = super::build(
+            FoldersName::from_iter(["old".into()]),
+            FoldersName::from_iter(["new".into()]),
+            FoldersName::from_iter(["old".into()]),
+            FoldersName::from_iter(["old".into()]),
+        );
+
+        assert!(super::find_rename(&patches, &SyncDestination::Right).is_some());
+
+        // no shared message, or only generated ones
+        assert!(!super::is_rename(["<a@localhost>"], ["<b@localhost>"]));
+        assert!(!super::is_rename([], []));
+        assert!(!super::is_rename(
+            ["<1234@generated>"],
+            ["<1234@generated>"]
+        ));
+
+        // deletion and creation stay separate
+        assert!(patches["old"].contains(&FolderSyncHunk::Delete(
+            "old".into(),
+            SyncDestination::Right
+        )));
+        assert!(patches["new"].contains(&FolderSyncHunk::Create(
+            "new".into(),
+            SyncDestination::Right
+        )));
+    }
 }
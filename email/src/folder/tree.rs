@@ -0,0 +1,224 @@
+//! # Folder tree
+//!
+//! Module dedicated to the folders hierarchy. The main structure of
+//! this module is [`FolderTree`], which arranges a flat list of
+//! [`Folders`] into a tree, using the hierarchy delimiter of each
+//! folder.
+//!
+//! Intermediate levels that are not part of the list (for example
+//! `a` when only `a/b` is listed) are represented by virtual folders
+//! having the [`FolderAttribute::NoSelect`] and
+//! [`FolderAttribute::NonExistent`] attributes.
+
+use std::ops::Deref;
+
+use super::{Folder, FolderAttribute, FolderAttributes, Folders};
+
+/// The folder tree node.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FolderNode {
+    /// The last hierarchy level of the folder name.
+    pub name: String,
+
+    /// The folder attached to this node.
+    pub folder: Folder,
+
+    /// The children of this node.
+    pub children: Vec<FolderNode>,
+}
+
+impl FolderNode {
+    fn new(name: impl ToString, folder: Folder) -> Self {
+        Self {
+            name: name.to_string(),
+            folder,
+            children: Vec::new(),
+        }
+    }
+
+    fn new_virtual(name: impl ToString, path: &[&str], delim: char) -> Self {
+        let folder = Folder {
+            name: path.join(&delim.to_string()),
+            delim: Some(delim),
+            attrs: FolderAttributes::from_iter([
+                FolderAttribute::NoSelect,
+                FolderAttribute::NonExistent,
+                FolderAttribute::HasChildren,
+            ]),
+            ..Default::default()
+        };
+
+        Self::new(name, folder)
+    }
+
+    fn walk<'a>(&'a self, depth: usize, nodes: &mut Vec<(usize, &'a Folder)>) {
+        nodes.push((depth, &self.folder));
+
+        for child in &self.children {
+            child.walk(depth + 1, nodes)
+        }
+    }
+}
+
+/// The folder tree.
+///
+/// The tree is made of root nodes, kept in the order they were first
+/// encountered in the list of folders.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FolderTree(Vec<FolderNode>);
+
+impl FolderTree {
+    /// Insert the given folder at its place in the tree.
+    ///
+    /// Missing intermediate levels are created as virtual folders. If
+    /// the folder was already inserted as a virtual one, it is
+    /// replaced by the given folder and its children are preserved.
+    pub fn insert(&mut self, folder: Folder) {
+        let path: Vec<String> = folder.path().into_iter().map(ToOwned::to_owned).collect();
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        let Some((last, parents)) = path.split_last() else {
+            return;
+        };
+
+        let mut nodes = &mut self.0;
+
+        for (i, name) in parents.iter().enumerate() {
+            let pos = match nodes.iter().position(|node| node.name == *name) {
+                Some(pos) => pos,
+                None => {
+                    // a folder with parents necessarily has a delimiter
+                    let delim = folder.delim.unwrap_or_default();
+                    nodes.push(FolderNode::new_virtual(name, &path[..=i], delim));
+                    nodes.len() - 1
+                }
+            };
+
+            nodes = &mut nodes[pos].children;
+        }
+
+        match nodes.iter_mut().find(|node| node.name == *last) {
+            Some(node) => node.folder = folder,
+            None => nodes.push(FolderNode::new(last, folder)),
+        }
+    }
+
+    /// Return all the folders of the tree, depth first, alongside
+    /// their depth.
+    pub fn flatten(&self) -> Vec<(usize, &Folder)> {
+        let mut nodes = Vec::new();
+
+        for node in &self.0 {
+            node.walk(0, &mut nodes)
+        }
+
+        nodes
+    }
+}
+
+impl Deref for FolderTree {
+    type Target = Vec<FolderNode>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromIterator<Folder> for FolderTree {
+    fn from_iter<T: IntoIterator<Item = Folder>>(iter: T) -> Self {
+        let mut tree = FolderTree::default();
+
+        for folder in iter {
+            tree.insert(folder)
+        }
+
+        tree
+    }
+}
+
+impl From<Folders> for FolderTree {
+    fn from(folders: Folders) -> Self {
+        FolderTree::from_iter(folders)
+    }
+}
+
+impl Folders {
+    /// Arrange folders as a tree, using their hierarchy delimiter.
+    pub fn to_tree(&self) -> FolderTree {
+        FolderTree::from_iter(self.iter().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FolderTree, Folders};
+    use crate::folder::{Folder, FolderAttribute};
+
+    fn folder(name: &str, delim: Option<char>) -> Folder {
+        Folder {
+            name: name.to_owned(),
+            delim,
+            ..Default::default()
+        }
+    }
+
+    fn names(tree: &FolderTree) -> Vec<(usize, &str)> {
+        tree.flatten()
+            .into_iter()
+            .map(|(depth, folder)| (depth, folder.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn build_tree() {
+        let folders = Folders::from_iter([
+            folder("INBOX", Some('/')),
+            folder("a/b", Some('/')),
+            folder("a", Some('/')),
+            folder("a/c/d", Some('/')),
+            folder("flat/name", None),
+        ]);
+
+        let tree = folders.to_tree();
+
+        assert_eq!(
+            names(&tree),
+            vec![
+                (0, "INBOX"),
+                (0, "a"),
+                (1, "a/b"),
+                (1, "a/c"),
+                (2, "a/c/d"),
+                (0, "flat/name"),
+            ]
+        );
+
+        // the folder `a` replaced its virtual counterpart
+        assert!(tree[1].folder.is_selectable());
+        assert_eq!(tree[1].children.len(), 2);
+
+        // the folder `a/c` is a virtual one
+        let virtual_folder = &tree[1].children[1].folder;
+        assert!(!virtual_folder.is_selectable());
+        assert!(virtual_folder.attrs.contains(&FolderAttribute::NonExistent));
+        assert_eq!(virtual_folder.delim, Some('/'));
+    }
+
+    #[test]
+    fn build_maildirpp_tree() {
+        let tree = FolderTree::from_iter([
+            folder("INBOX", Some('.')),
+            folder("Archives.2023", Some('.')),
+            folder("Archives.2024", Some('.')),
+        ]);
+
+        assert_eq!(
+            names(&tree),
+            vec![
+                (0, "INBOX"),
+                (0, "Archives"),
+                (1, "Archives.2023"),
+                (1, "Archives.2024"),
+            ]
+        );
+    }
+}
@@ -101,6 +101,11 @@ pub async fn build_credentials(&self) -> Result<String> {
     pub fn find_watch_timeout(&self) -> Option<u64> {
         self.watch.as_ref().and_then(|c| c.find_timeout())
     }
+
+    /// Find the IMAP watch poll interval, in seconds.
+    pub fn find_watch_poll_interval(&self) -> Option<u64> {
+        self.watch.as_ref().and_then(|c| c.find_poll_interval())
+    }
 }
 
 #[cfg(feature = "sync")]
@@ -257,6 +262,14 @@ pub struct ImapWatchConfig {
     /// Timeout used to refresh the IDLE command in
     /// background. Defaults to 29 min as defined in the RFC.
     timeout: Option<u64>,
+
+    /// The IMAP watch poll interval, in seconds.
+    ///
+    /// Interval used to poll the status of watched folders when
+    /// multiple folders are watched. Folders are polled one after
+    /// the other, so each folder is polled once per interval.
+    /// Defaults to 60 s.
+    poll_interval: Option<u64>,
 }
 
 impl ImapWatchConfig {
@@ -264,6 +277,11 @@ impl ImapWatchConfig {
     pub fn find_timeout(&self) -> Option<u64> {
         self.timeout
     }
+
+    /// Find the IMAP watch poll interval.
+    pub fn find_poll_interval(&self) -> Option<u64> {
+        self.poll_interval
+    }
 }
 
 /// The IMAP configuration dedicated to extensions.
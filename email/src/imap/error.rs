@@ -4,7 +4,7 @@
     client::tokio::ClientError,
     imap_next::{
         client::Error as ClientFlowError,
-        imap_types::{auth::AuthMechanism, error::ValidationError},
+        imap_types::{auth::AuthMechanism, datetime::error::DateTimeError, error::ValidationError},
     },
     stream::Error as StreamError,
 };
@@ -61,6 +61,16 @@ pub enum Error {
     ParseMailboxError(#[source] ValidationError, String),
     #[error("cannot find UID of appended IMAP message")]
     FindAppendedMessageUidError,
+    #[error("cannot parse IMAP internal date")]
+    ParseInternalDateError(#[source] DateTimeError),
+    #[error("cannot parse IMAP message literal")]
+    ParseMessageError(#[source] ValidationError),
+    #[error("cannot parse IMAP metadata entry {1}")]
+    ParseMetadataEntryError(#[source] ValidationError, String),
+    #[error("cannot parse IMAP metadata value of entry {1}")]
+    ParseMetadataValueError(#[source] ValidationError, String),
+    #[error("cannot parse IMAP metadata entries")]
+    ParseMetadataEntriesError(#[source] ValidationError),
 
     #[error("cannot send IMAP request")]
     RequestRetryError(#[source] ClientError),
@@ -111,6 +121,46 @@ pub enum Error {
     #[error("cannot delete IMAP mailbox: request timed out")]
     DeleteMailboxTimedOutError,
 
+    #[error("cannot rename IMAP mailbox")]
+    RenameMailboxError(#[source] ClientError),
+    #[error("cannot rename IMAP mailbox: request timed out")]
+    RenameMailboxTimedOutError,
+
+    #[error("cannot get IMAP mailbox status")]
+    StatusMailboxError(#[source] ClientError),
+    #[error("cannot get IMAP mailbox status: request timed out")]
+    StatusMailboxTimedOutError,
+
+    #[error("cannot list IMAP subscribed mailboxes")]
+    ListSubscribedMailboxesError(#[source] ClientError),
+    #[error("cannot list IMAP subscribed mailboxes: request timed out")]
+    ListSubscribedMailboxesTimedOutError,
+
+    #[error("cannot subscribe to IMAP mailbox")]
+    SubscribeMailboxError(#[source] ClientError),
+    #[error("cannot subscribe to IMAP mailbox: request timed out")]
+    SubscribeMailboxTimedOutError,
+
+    #[error("cannot unsubscribe from IMAP mailbox")]
+    UnsubscribeMailboxError(#[source] ClientError),
+    #[error("cannot unsubscribe from IMAP mailbox: request timed out")]
+    UnsubscribeMailboxTimedOutError,
+
+    #[error("cannot get IMAP quota root")]
+    GetQuotaRootError(#[source] ClientError),
+    #[error("cannot get IMAP quota root: request timed out")]
+    GetQuotaRootTimedOutError,
+
+    #[error("cannot get IMAP metadata")]
+    GetMetadataError(#[source] ClientError),
+    #[error("cannot get IMAP metadata: request timed out")]
+    GetMetadataTimedOutError,
+
+    #[error("cannot set IMAP metadata")]
+    SetMetadataError(#[source] ClientError),
+    #[error("cannot set IMAP metadata: request timed out")]
+    SetMetadataTimedOutError,
+
     #[error("cannot fetch IMAP messages")]
     FetchMessagesError(#[source] ClientError),
     #[error("cannot fetch IMAP messages: request timed out")]
@@ -148,6 +198,10 @@ pub enum Error {
     SearchMessagesError(#[source] ClientError),
     #[error("cannot sort IMAP messages")]
     SortMessagesError(#[source] ClientError),
+    #[error("cannot fetch IMAP flags")]
+    FetchFlagsError(#[source] ClientError),
+    #[error("cannot fetch IMAP flags: request timed out")]
+    FetchFlagsTimedOutError,
     #[error("cannot sort IMAP envelope UIDs")]
     SortUidsError(#[source] ClientError),
     #[error("cannot sort IMAP envelope UIDs: request timed out")]
@@ -1,5 +1,6 @@
 pub mod config;
 mod error;
+mod tasks;
 
 use std::{
     collections::HashMap, env, fmt, io::ErrorKind::ConnectionReset, num::NonZeroU32, sync::Arc,
@@ -7,23 +8,31 @@
 };
 
 use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
 use futures::{stream::FuturesUnordered, StreamExt};
 use imap_client::{
     client::tokio::{Client, ClientError},
     imap_next::imap_types::{
         auth::AuthMechanism,
-        core::{IString, NString, Vec1},
+        core::{AString, IString, Literal, NString, NString8, Vec1},
+        datetime::DateTime as ImapDateTime,
         extensions::{
+            binary::LiteralOrLiteral8,
+            metadata::{Entry, EntryValue},
             sort::SortCriterion,
             thread::{Thread, ThreadingAlgorithm},
         },
         fetch::MessageDataItem,
         flag::{Flag, StoreType},
+        mailbox::Mailbox,
         search::SearchKey,
         sequence::SequenceSet,
     },
     stream::Error as StreamError,
-    tasks::{tasks::select::SelectDataUnvalidated, SchedulerError},
+    tasks::{
+        tasks::{append::AppendTask, select::SelectDataUnvalidated, TaskError},
+        SchedulerError, Task,
+    },
 };
 use once_cell::sync::Lazy;
 use tokio::{
@@ -33,9 +42,19 @@
 };
 use tracing::{debug, instrument, trace, warn};
 
-use self::config::{ImapAuthConfig, ImapConfig};
 #[doc(inline)]
 pub use self::error::{Error, Result};
+use self::{
+    config::{ImapAuthConfig, ImapConfig},
+    tasks::{
+        lsub::LsubTask,
+        metadata::{GetMetadataTask, SetMetadataTask},
+        quota::GetQuotaRootTask,
+        rename::RenameTask,
+        status::StatusTask,
+        subscribe::{SubscribeTask, UnsubscribeTask},
+    },
+};
 #[cfg(feature = "oauth2")]
 use crate::account::config::oauth2::OAuth2Method;
 #[cfg(feature = "thread")]
@@ -45,12 +64,13 @@
 use crate::{
     account::config::AccountConfig,
     backend::{
+        capability::{ServerCapabilities, ServerCapability},
         context::{BackendContext, BackendContextBuilder},
         feature::{BackendFeature, CheckUp},
     },
     envelope::{
         get::{imap::GetImapEnvelope, GetEnvelope},
-        imap::FETCH_ENVELOPES,
+        imap::{FETCH_ENVELOPES, FETCH_FLAGS},
         list::{imap::ListImapEnvelopes, ListEnvelopes},
         Envelope, Envelopes,
     },
@@ -58,13 +78,27 @@
         add::{imap::AddImapFlags, AddFlags},
         remove::{imap::RemoveImapFlags, RemoveFlags},
         set::{imap::SetImapFlags, SetFlags},
+        Flags,
     },
     folder::{
         add::{imap::AddImapFolder, AddFolder},
+        annotation::{
+            imap::{GetImapFolderAnnotations, SetImapFolderAnnotation},
+            GetFolderAnnotations, SetFolderAnnotation,
+        },
         delete::{imap::DeleteImapFolder, DeleteFolder},
         expunge::{imap::ExpungeImapFolder, ExpungeFolder},
         list::{imap::ListImapFolders, ListFolders},
         purge::{imap::PurgeImapFolder, PurgeFolder},
+        rename::{imap::RenameImapFolder, RenameFolder},
+        status::{
+            imap::{GetImapFolderStatus, ListImapFoldersWithStatus},
+            FolderStatus, GetFolderStatus, ListFoldersWithStatus,
+        },
+        subscribe::{
+            imap::{SubscribeImapFolder, UnsubscribeImapFolder},
+            SubscribeFolder, UnsubscribeFolder,
+        },
         Folders,
     },
     message::{
@@ -72,12 +106,13 @@
         copy::{imap::CopyImapMessages, CopyMessages},
         delete::{imap::DeleteImapMessages, DeleteMessages},
         get::{imap::GetImapMessages, GetMessages},
-        imap::{FETCH_MESSAGES, PEEK_MESSAGES},
+        imap::{FETCH_MESSAGES, PEEK_MESSAGES, PEEK_MESSAGES_HEADERS},
         peek::{imap::PeekImapMessages, PeekMessages},
         r#move::{imap::MoveImapMessages, MoveMessages},
         remove::{imap::RemoveImapMessages, RemoveMessages},
         Messages,
     },
+    quota::{imap::GetImapQuota, GetQuota, Quota},
     retry::{self, Retry, RetryState},
     tls::{Encryption, Tls, TlsProvider},
     AnyResult,
@@ -204,10 +239,41 @@ async fn retry<T>(
         }
     }
 
+    /// Resolve the given low-level IMAP task using the given inner
+    /// client.
+    ///
+    /// This is useful for commands not directly exposed by the inner
+    /// client. The function only borrows the inner client, so that
+    /// its future can be wrapped by the retry timeout.
+    async fn resolve<T, O>(client: &mut Client, task: T) -> std::result::Result<O, ClientError>
+    where
+        T: Task<Output = std::result::Result<O, TaskError>>,
+    {
+        Ok(client.resolve(task).await??)
+    }
+
     pub fn ext_sort_supported(&self) -> bool {
         self.inner.state.ext_sort_supported()
     }
 
+    /// Return `true` if the given capability is advertised by the
+    /// server.
+    pub fn ext_supported(&self, capability: &str) -> bool {
+        self.inner
+            .state
+            .capabilities_iter()
+            .any(|cap| cap.to_string().eq_ignore_ascii_case(capability))
+    }
+
+    /// Return the capabilities advertised by the server.
+    pub fn capabilities(&self) -> ServerCapabilities {
+        self.inner
+            .state
+            .capabilities_iter()
+            .map(|capability| ServerCapability::from_imap(capability.to_string()))
+            .collect()
+    }
+
     #[instrument(skip_all, fields(client = self.id))]
     pub async fn noop(&mut self) -> Result<()> {
         self.retry.reset();
@@ -283,6 +349,10 @@ pub async fn create_mailbox(&mut self, mbox: impl ToString) -> Result<()> {
 
     #[instrument(skip_all, fields(client = self.id))]
     pub async fn list_all_mailboxes(&mut self, config: &AccountConfig) -> Result<Folders> {
+        // TODO: tag folders with their namespace (NAMESPACE, RFC
+        // 2342) and expose their rights (GETACL, MYRIGHTS and SETACL,
+        // RFC 4314) once supported by imap-types, which can neither
+        // encode these commands nor parse their responses yet.
         self.retry.reset();
 
         let mboxes = loop {
@@ -300,6 +370,74 @@ pub async fn list_all_mailboxes(&mut self, config: &AccountConfig) -> Result<Fol
         Ok(folders)
     }
 
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn list_subscribed_mailboxes(&mut self, config: &AccountConfig) -> Result<Folders> {
+        self.retry.reset();
+
+        let mboxes = loop {
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, LsubTask::new()))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::ListSubscribedMailboxesTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::ListSubscribedMailboxesError),
+            }
+        }?;
+
+        let folders = Folders::from_imap_subscribed_mailboxes(config, mboxes);
+
+        Ok(folders)
+    }
+
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn subscribe_mailbox(&mut self, mbox: impl ToString) -> Result<()> {
+        let mbox = mbox.to_string();
+        let mbox =
+            Mailbox::try_from(mbox.clone()).map_err(|err| Error::ParseMailboxError(err, mbox))?;
+
+        self.retry.reset();
+
+        loop {
+            let task = SubscribeTask::new(mbox.clone());
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, task))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::SubscribeMailboxTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::SubscribeMailboxError),
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn unsubscribe_mailbox(&mut self, mbox: impl ToString) -> Result<()> {
+        let mbox = mbox.to_string();
+        let mbox =
+            Mailbox::try_from(mbox.clone()).map_err(|err| Error::ParseMailboxError(err, mbox))?;
+
+        self.retry.reset();
+
+        loop {
+            let task = UnsubscribeTask::new(mbox.clone());
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, task))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::UnsubscribeMailboxTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::UnsubscribeMailboxError),
+            }
+        }
+    }
+
     #[instrument(skip_all, fields(client = self.id))]
     pub async fn expunge_mailbox(&mut self, mbox: impl ToString) -> Result<usize> {
         self.select_mailbox(mbox).await?;
@@ -357,6 +495,190 @@ pub async fn delete_mailbox(&mut self, mbox: impl ToString) -> Result<()> {
         }
     }
 
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn rename_mailbox(&mut self, from: impl ToString, to: impl ToString) -> Result<()> {
+        let from = from.to_string();
+        let from =
+            Mailbox::try_from(from.clone()).map_err(|err| Error::ParseMailboxError(err, from))?;
+        let to = to.to_string();
+        let to = Mailbox::try_from(to.clone()).map_err(|err| Error::ParseMailboxError(err, to))?;
+
+        self.retry.reset();
+
+        loop {
+            let task = RenameTask::new(from.clone(), to.clone());
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, task))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::RenameMailboxTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::RenameMailboxError),
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn status_mailbox(&mut self, mbox: impl ToString) -> Result<FolderStatus> {
+        let mbox = mbox.to_string();
+        let mbox =
+            Mailbox::try_from(mbox.clone()).map_err(|err| Error::ParseMailboxError(err, mbox))?;
+
+        self.retry.reset();
+
+        let items = loop {
+            let task = StatusTask::new(mbox.clone());
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, task))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::StatusMailboxTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::StatusMailboxError),
+            }
+        }?;
+
+        Ok(FolderStatus::from_imap_status_items(&items))
+    }
+
+    /// Get the quota of the given mailbox.
+    ///
+    /// When the mailbox belongs to multiple quota roots, the first
+    /// one is returned.
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn get_quota_root(&mut self, mbox: impl ToString) -> Result<Quota> {
+        let mbox = mbox.to_string();
+        let mbox =
+            Mailbox::try_from(mbox.clone()).map_err(|err| Error::ParseMailboxError(err, mbox))?;
+
+        self.retry.reset();
+
+        let quotas = loop {
+            let task = GetQuotaRootTask::new(mbox.clone());
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, task))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::GetQuotaRootTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::GetQuotaRootError),
+            }
+        }?;
+
+        let quota = quotas
+            .first()
+            .map(|(root, quotas)| Quota::from_imap_quota(root, quotas))
+            .unwrap_or_default();
+
+        Ok(quota)
+    }
+
+    /// Get the metadata of the given mailbox (RFC 5464).
+    ///
+    /// Values of the given entries are returned, as well as values of
+    /// all their descendants. An empty mailbox name targets the
+    /// server metadata.
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn get_metadata(
+        &mut self,
+        mbox: impl ToString,
+        entries: impl IntoIterator<Item = String>,
+    ) -> Result<Vec<(String, Option<String>)>> {
+        let mbox = mbox.to_string();
+        let mbox =
+            Mailbox::try_from(mbox.clone()).map_err(|err| Error::ParseMailboxError(err, mbox))?;
+
+        let entries = entries
+            .into_iter()
+            .map(parse_metadata_entry)
+            .collect::<Result<Vec<_>>>()?;
+
+        if entries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let entries = Vec1::try_from(entries).map_err(Error::ParseMetadataEntriesError)?;
+
+        self.retry.reset();
+
+        loop {
+            let task = GetMetadataTask::new(mbox.clone(), entries.clone());
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, task))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::GetMetadataTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::GetMetadataError),
+            }
+        }
+    }
+
+    /// Set the metadata of the given mailbox (RFC 5464).
+    ///
+    /// Entries without value are removed. An empty mailbox name
+    /// targets the server metadata.
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn set_metadata(
+        &mut self,
+        mbox: impl ToString,
+        entry_values: impl IntoIterator<Item = (String, Option<String>)>,
+    ) -> Result<()> {
+        let mbox = mbox.to_string();
+        let mbox =
+            Mailbox::try_from(mbox.clone()).map_err(|err| Error::ParseMailboxError(err, mbox))?;
+
+        let entry_values = entry_values
+            .into_iter()
+            .map(|(entry, value)| {
+                let value = match value {
+                    None => NString(None),
+                    Some(value) => {
+                        NString(Some(IString::try_from(value).map_err(|err| {
+                            Error::ParseMetadataValueError(err, entry.clone())
+                        })?))
+                    }
+                };
+
+                let entry = parse_metadata_entry(entry)?;
+                let value = NString8::NString(value);
+
+                Ok(EntryValue { entry, value })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if entry_values.is_empty() {
+            return Ok(());
+        }
+
+        let entry_values =
+            Vec1::try_from(entry_values).map_err(Error::ParseMetadataEntriesError)?;
+
+        self.retry.reset();
+
+        loop {
+            let task = SetMetadataTask::new(mbox.clone(), entry_values.clone());
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, task))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::SetMetadataTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::SetMetadataError),
+            }
+        }
+    }
+
     #[instrument(skip_all, fields(client = self.id))]
     pub async fn fetch_envelopes(&mut self, uids: SequenceSet) -> Result<Envelopes> {
         self.retry.reset();
@@ -406,6 +728,50 @@ pub async fn fetch_envelopes_map(
         Ok(map)
     }
 
+    /// Fetch only the flags of the given UIDs, indexed by UID.
+    ///
+    /// This is way lighter than fetching envelopes, which makes it
+    /// suitable for detecting flags changes and removed messages.
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn fetch_flags(&mut self, uids: SequenceSet) -> Result<HashMap<String, Flags>> {
+        self.retry.reset();
+
+        let fetches = loop {
+            let res = self
+                .retry
+                .timeout(self.inner.uid_fetch(uids.clone(), FETCH_FLAGS.clone()))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::FetchFlagsTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::FetchFlagsError),
+            }
+        }?;
+
+        let flags = fetches
+            .into_values()
+            .filter_map(|items| {
+                let mut uid = None;
+                let mut flags = Flags::default();
+
+                for item in items.as_ref() {
+                    match item {
+                        MessageDataItem::Uid(id) => uid = Some(id.to_string()),
+                        MessageDataItem::Flags(fetches) => {
+                            flags = Flags::from_imap_flag_fetches(fetches.as_ref());
+                        }
+                        _ => (),
+                    }
+                }
+
+                Some((uid?, flags))
+            })
+            .collect();
+
+        Ok(flags)
+    }
+
     #[instrument(skip_all, fields(client = self.id))]
     pub async fn fetch_first_envelope(&mut self, uid: u32) -> Result<Envelope> {
         let items = loop {
@@ -534,6 +900,13 @@ pub async fn thread_envelopes(
         }
     }
 
+    /// Enter the IDLE mode until the server notifies changes, the
+    /// IDLE timeout elapses or a shutdown is requested.
+    ///
+    /// The IDLE command goes through the client scheduler. Untagged
+    /// responses received while idling are not returned, since
+    /// imap-client discards them and does not let scheduled tasks
+    /// end the IDLE command: changes need to be fetched afterwards.
     #[instrument(skip_all, fields(client = self.id))]
     pub async fn idle(
         &mut self,
@@ -741,6 +1114,43 @@ pub async fn add_message(
         id.ok_or(Error::FindAppendedMessageUidError)
     }
 
+    /// Add the given message to the given mailbox, using the given
+    /// date as internal date.
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn add_message_with_date(
+        &mut self,
+        mbox: impl ToString,
+        flags: impl IntoIterator<Item = Flag<'static>>,
+        date: DateTime<FixedOffset>,
+        msg: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        let mbox = mbox.to_string();
+        let mbox =
+            Mailbox::try_from(mbox.clone()).map_err(|err| Error::ParseMailboxError(err, mbox))?;
+        let date = ImapDateTime::try_from(date).map_err(Error::ParseInternalDateError)?;
+        let msg = Literal::try_from(msg.as_ref().to_vec()).map_err(Error::ParseMessageError)?;
+        let flags: Vec<_> = flags.into_iter().collect();
+
+        self.retry.reset();
+
+        loop {
+            let task = AppendTask::new(mbox.clone(), LiteralOrLiteral8::Literal(msg.clone()))
+                .with_flags(flags.clone())
+                .with_date(date.clone());
+
+            let res = self
+                .retry
+                .timeout(Self::resolve(&mut self.inner, task))
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::AddMessageTimedOutError),
+                ImapRetryState::Ok(res) => break res.map(|_| ()).map_err(Error::AddMessageError),
+            }
+        }
+    }
+
     #[instrument(skip_all, fields(client = self.id))]
     pub async fn fetch_messages(&mut self, uids: SequenceSet) -> Result<Messages> {
         let mut fetches = loop {
@@ -787,6 +1197,32 @@ pub async fn peek_messages(&mut self, uids: SequenceSet) -> Result<Messages> {
         Ok(Messages::from(fetches))
     }
 
+    #[instrument(skip_all, fields(client = self.id))]
+    pub async fn peek_messages_headers(&mut self, uids: SequenceSet) -> Result<Messages> {
+        let mut fetches = loop {
+            let res = self
+                .retry
+                .timeout(
+                    self.inner
+                        .uid_fetch(uids.clone(), PEEK_MESSAGES_HEADERS.clone()),
+                )
+                .await;
+
+            match self.retry(res).await? {
+                ImapRetryState::Retry => continue,
+                ImapRetryState::TimedOut => break Err(Error::FetchMessagesTimedOutError),
+                ImapRetryState::Ok(res) => break res.map_err(Error::FetchMessagesError),
+            }
+        }?;
+
+        let fetches: Vec<_> = uids
+            .iter(NonZeroU32::MAX)
+            .filter_map(|ref uid| fetches.remove(uid))
+            .collect();
+
+        Ok(Messages::from(fetches))
+    }
+
     #[instrument(skip_all, fields(client = self.id))]
     pub async fn copy_messages(&mut self, uids: SequenceSet, mbox: impl ToString) -> Result<()> {
         loop {
@@ -830,6 +1266,13 @@ fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     }
 }
 
+/// Parse the given metadata entry name (RFC 5464).
+fn parse_metadata_entry(entry: String) -> Result<Entry<'static>> {
+    AString::try_from(entry.clone())
+        .and_then(Entry::try_from)
+        .map_err(|err| Error::ParseMetadataEntryError(err, entry))
+}
+
 /// The sync version of the IMAP backend context.
 ///
 /// This is just an IMAP session wrapped into a mutex, so the same
@@ -934,6 +1377,16 @@ fn list_folders(&self) -> Option<BackendFeature<Self::Context, dyn ListFolders>>
         Some(Arc::new(ListImapFolders::some_new_boxed))
     }
 
+    fn list_folders_with_status(
+        &self,
+    ) -> Option<BackendFeature<Self::Context, dyn ListFoldersWithStatus>> {
+        Some(Arc::new(ListImapFoldersWithStatus::some_new_boxed))
+    }
+
+    fn get_folder_status(&self) -> Option<BackendFeature<Self::Context, dyn GetFolderStatus>> {
+        Some(Arc::new(GetImapFolderStatus::some_new_boxed))
+    }
+
     fn expunge_folder(&self) -> Option<BackendFeature<Self::Context, dyn ExpungeFolder>> {
         Some(Arc::new(ExpungeImapFolder::some_new_boxed))
     }
@@ -942,6 +1395,30 @@ fn purge_folder(&self) -> Option<BackendFeature<Self::Context, dyn PurgeFolder>>
         Some(Arc::new(PurgeImapFolder::some_new_boxed))
     }
 
+    fn rename_folder(&self) -> Option<BackendFeature<Self::Context, dyn RenameFolder>> {
+        Some(Arc::new(RenameImapFolder::some_new_boxed))
+    }
+
+    fn subscribe_folder(&self) -> Option<BackendFeature<Self::Context, dyn SubscribeFolder>> {
+        Some(Arc::new(SubscribeImapFolder::some_new_boxed))
+    }
+
+    fn unsubscribe_folder(&self) -> Option<BackendFeature<Self::Context, dyn UnsubscribeFolder>> {
+        Some(Arc::new(UnsubscribeImapFolder::some_new_boxed))
+    }
+
+    fn get_folder_annotations(
+        &self,
+    ) -> Option<BackendFeature<Self::Context, dyn GetFolderAnnotations>> {
+        Some(Arc::new(GetImapFolderAnnotations::some_new_boxed))
+    }
+
+    fn set_folder_annotation(
+        &self,
+    ) -> Option<BackendFeature<Self::Context, dyn SetFolderAnnotation>> {
+        Some(Arc::new(SetImapFolderAnnotation::some_new_boxed))
+    }
+
     fn delete_folder(&self) -> Option<BackendFeature<Self::Context, dyn DeleteFolder>> {
         Some(Arc::new(DeleteImapFolder::some_new_boxed))
     }
@@ -1004,6 +1481,10 @@ fn remove_messages(&self) -> Option<BackendFeature<Self::Context, dyn RemoveMess
         Some(Arc::new(RemoveImapMessages::some_new_boxed))
     }
 
+    fn get_quota(&self) -> Option<BackendFeature<Self::Context, dyn GetQuota>> {
+        Some(Arc::new(GetImapQuota::some_new_boxed))
+    }
+
     async fn build(self) -> AnyResult<Self::Context> {
         let client_builder =
             ImapClientBuilder::new(self.imap_config.clone(), self.prebuilt_credentials);
@@ -1069,6 +1550,11 @@ async fn check_up(&self) -> AnyResult<()> {
         debug!("executing check up backend feature");
         Ok(self.ctx.client().await.noop().await?)
     }
+
+    #[instrument(skip_all)]
+    async fn server_capabilities(&self) -> AnyResult<ServerCapabilities> {
+        Ok(self.ctx.client().await.capabilities())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -1334,6 +1820,12 @@ pub async fn build(&mut self) -> Result<Client> {
             debug!(?params, "server identity");
         }
 
+        // TODO: negotiate COMPRESS=DEFLATE (RFC 4978) after
+        // authentication once imap-client allows to wrap its stream,
+        // which is a `Stream<MaybeTlsStream>` without room for a
+        // compression layer. Sending the COMPRESS command without
+        // compressing the stream would break the connection.
+
         // TODO: make it customizable
         //
         // debug!("enabling UTF8 capability…");
@@ -0,0 +1,55 @@
+use imap_client::{
+    imap_next::imap_types::{
+        command::CommandBody,
+        mailbox::{ListMailbox, Mailbox},
+        response::{Data, StatusBody},
+    },
+    tasks::{tasks::TaskError, Task},
+};
+
+use super::status_to_result;
+use crate::folder::imap::{ImapMailbox, ImapMailboxes};
+
+/// The IMAP LSUB task (RFC 3501).
+///
+/// The task lists all subscribed mailboxes.
+#[derive(Clone, Debug, Default)]
+pub struct LsubTask {
+    mboxes: ImapMailboxes,
+}
+
+impl LsubTask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Task for LsubTask {
+    type Output = Result<ImapMailboxes, TaskError>;
+
+    fn command_body(&self) -> CommandBody<'static> {
+        CommandBody::Lsub {
+            reference: Mailbox::try_from("").unwrap(),
+            mailbox_wildcard: ListMailbox::try_from("*").unwrap(),
+        }
+    }
+
+    fn process_data(&mut self, data: Data<'static>) -> Option<Data<'static>> {
+        if let Data::Lsub {
+            items,
+            delimiter,
+            mailbox,
+        } = data
+        {
+            let mbox: ImapMailbox = (mailbox, delimiter, items);
+            self.mboxes.push(mbox);
+            None
+        } else {
+            Some(data)
+        }
+    }
+
+    fn process_tagged(self, status_body: StatusBody<'static>) -> Self::Output {
+        status_to_result(status_body).map(|()| self.mboxes)
+    }
+}
@@ -0,0 +1,104 @@
+use imap_client::{
+    imap_next::imap_types::{
+        command::CommandBody,
+        core::{NString, NString8, Vec1},
+        extensions::metadata::{Depth, Entry, EntryValue, GetMetadataOption, MetadataResponse},
+        mailbox::Mailbox,
+        response::{Data, StatusBody},
+    },
+    tasks::{tasks::TaskError, Task},
+};
+
+use super::status_to_result;
+
+/// The IMAP GETMETADATA task (RFC 5464).
+///
+/// The task returns the values of the given entries and of all their
+/// descendants. Entries without value are returned with `None`.
+#[derive(Clone, Debug)]
+pub struct GetMetadataTask {
+    mbox: Mailbox<'static>,
+    entries: Vec1<Entry<'static>>,
+    values: Vec<(String, Option<String>)>,
+}
+
+impl GetMetadataTask {
+    pub fn new(mbox: Mailbox<'static>, entries: Vec1<Entry<'static>>) -> Self {
+        Self {
+            mbox,
+            entries,
+            values: Vec::new(),
+        }
+    }
+}
+
+impl Task for GetMetadataTask {
+    type Output = Result<Vec<(String, Option<String>)>, TaskError>;
+
+    fn command_body(&self) -> CommandBody<'static> {
+        CommandBody::GetMetadata {
+            options: vec![GetMetadataOption::Depth(Depth::Infinity)],
+            mailbox: self.mbox.clone(),
+            entries: self.entries.clone(),
+        }
+    }
+
+    fn process_data(&mut self, data: Data<'static>) -> Option<Data<'static>> {
+        match data {
+            Data::Metadata {
+                items: MetadataResponse::WithValues(entry_values),
+                ..
+            } => {
+                self.values
+                    .extend(entry_values.into_iter().map(|EntryValue { entry, value }| {
+                        let entry = String::from_utf8_lossy(entry.as_ref()).to_string();
+                        let value = match value {
+                            NString8::NString(NString(value)) => value
+                                .map(|value| String::from_utf8_lossy(value.as_ref()).to_string()),
+                            NString8::Literal8(value) => {
+                                Some(String::from_utf8_lossy(&value.data).to_string())
+                            }
+                        };
+                        (entry, value)
+                    }));
+                None
+            }
+            data => Some(data),
+        }
+    }
+
+    fn process_tagged(self, status_body: StatusBody<'static>) -> Self::Output {
+        status_to_result(status_body)?;
+        Ok(self.values)
+    }
+}
+
+/// The IMAP SETMETADATA task (RFC 5464).
+///
+/// Entries having a `NIL` value are removed from the server.
+#[derive(Clone, Debug)]
+pub struct SetMetadataTask {
+    mbox: Mailbox<'static>,
+    entry_values: Vec1<EntryValue<'static>>,
+}
+
+impl SetMetadataTask {
+    pub fn new(mbox: Mailbox<'static>, entry_values: Vec1<EntryValue<'static>>) -> Self {
+        Self { mbox, entry_values }
+    }
+}
+
+impl Task for SetMetadataTask {
+    type Output = Result<(), TaskError>;
+
+    fn command_body(&self) -> CommandBody<'static> {
+        CommandBody::SetMetadata {
+            mailbox: self.mbox.clone(),
+            entry_values: self.entry_values.clone(),
+        }
+    }
+
+    fn process_tagged(self, status_body: StatusBody<'static>) -> Self::Output {
+        status_to_result(status_body)
+    }
+}
@@ -0,0 +1,26 @@
+//! # IMAP tasks
+//!
+//! Module dedicated to low-level IMAP tasks that are not exposed by
+//! the IMAP client. Tasks are resolved using
+//! [`ImapClient`](super::ImapClient).
+
+pub mod lsub;
+pub mod metadata;
+pub mod quota;
+pub mod rename;
+pub mod status;
+pub mod subscribe;
+
+use imap_client::{
+    imap_next::imap_types::response::{StatusBody, StatusKind},
+    tasks::tasks::TaskError,
+};
+
+/// Turn the given tagged status into a task result.
+fn status_to_result(status_body: StatusBody<'static>) -> Result<(), TaskError> {
+    match status_body.kind {
+        StatusKind::Ok => Ok(()),
+        StatusKind::No => Err(TaskError::UnexpectedNoResponse(status_body)),
+        StatusKind::Bad => Err(TaskError::UnexpectedBadResponse(status_body)),
+    }
+}
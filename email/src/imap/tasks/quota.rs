@@ -0,0 +1,71 @@
+use imap_client::{
+    imap_next::imap_types::{
+        command::CommandBody,
+        extensions::quota::QuotaGet,
+        mailbox::Mailbox,
+        response::{Data, StatusBody},
+    },
+    tasks::{tasks::TaskError, Task},
+};
+
+use super::status_to_result;
+
+/// The IMAP GETQUOTAROOT task (RFC 9208).
+///
+/// The task returns the quota roots of the given mailbox, alongside
+/// their resources.
+#[derive(Clone, Debug)]
+pub struct GetQuotaRootTask {
+    mbox: Mailbox<'static>,
+    roots: Vec<String>,
+    quotas: Vec<(String, Vec<QuotaGet<'static>>)>,
+}
+
+impl GetQuotaRootTask {
+    pub fn new(mbox: Mailbox<'static>) -> Self {
+        Self {
+            mbox,
+            roots: Vec::new(),
+            quotas: Vec::new(),
+        }
+    }
+}
+
+impl Task for GetQuotaRootTask {
+    type Output = Result<Vec<(String, Vec<QuotaGet<'static>>)>, TaskError>;
+
+    fn command_body(&self) -> CommandBody<'static> {
+        CommandBody::GetQuotaRoot {
+            mailbox: self.mbox.clone(),
+        }
+    }
+
+    fn process_data(&mut self, data: Data<'static>) -> Option<Data<'static>> {
+        match data {
+            Data::QuotaRoot { roots, .. } => {
+                self.roots.extend(
+                    roots
+                        .iter()
+                        .map(|root| String::from_utf8_lossy(root.as_ref()).to_string()),
+                );
+                None
+            }
+            Data::Quota { root, quotas } => {
+                let root = String::from_utf8_lossy(root.as_ref()).to_string();
+                self.quotas.push((root, quotas.as_ref().to_vec()));
+                None
+            }
+            data => Some(data),
+        }
+    }
+
+    fn process_tagged(self, status_body: StatusBody<'static>) -> Self::Output {
+        status_to_result(status_body)?;
+
+        // keep quotas in the order of the announced roots
+        let mut quotas = self.quotas;
+        quotas.sort_by_key(|(root, _)| self.roots.iter().position(|r| r == root));
+
+        Ok(quotas)
+    }
+}
@@ -0,0 +1,34 @@
+use imap_client::{
+    imap_next::imap_types::{command::CommandBody, mailbox::Mailbox, response::StatusBody},
+    tasks::{tasks::TaskError, Task},
+};
+
+use super::status_to_result;
+
+/// The IMAP RENAME task (RFC 3501).
+#[derive(Clone, Debug)]
+pub struct RenameTask {
+    from: Mailbox<'static>,
+    to: Mailbox<'static>,
+}
+
+impl RenameTask {
+    pub fn new(from: Mailbox<'static>, to: Mailbox<'static>) -> Self {
+        Self { from, to }
+    }
+}
+
+impl Task for RenameTask {
+    type Output = Result<(), TaskError>;
+
+    fn command_body(&self) -> CommandBody<'static> {
+        CommandBody::Rename {
+            from: self.from.clone(),
+            to: self.to.clone(),
+        }
+    }
+
+    fn process_tagged(self, status_body: StatusBody<'static>) -> Self::Output {
+        status_to_result(status_body)
+    }
+}
@@ -0,0 +1,104 @@
+use imap_client::{
+    imap_next::imap_types::{
+        command::CommandBody,
+        mailbox::Mailbox,
+        response::{Data, StatusBody},
+        status::{StatusDataItem, StatusDataItemName},
+    },
+    tasks::{tasks::TaskError, Task},
+};
+
+use super::status_to_result;
+
+/// The IMAP STATUS task (RFC 3501).
+///
+/// The task requests the number of messages, recent messages and
+/// unseen messages, as well as the UIDNEXT and UIDVALIDITY values of
+/// the given mailbox. STATUS responses of other mailboxes, which some
+/// servers send unsolicited, are left to other tasks.
+#[derive(Clone, Debug)]
+pub struct StatusTask {
+    mbox: Mailbox<'static>,
+    items: Vec<StatusDataItem>,
+}
+
+impl StatusTask {
+    pub fn new(mbox: Mailbox<'static>) -> Self {
+        Self {
+            mbox,
+            items: Vec::new(),
+        }
+    }
+}
+
+impl Task for StatusTask {
+    type Output = Result<Vec<StatusDataItem>, TaskError>;
+
+    fn command_body(&self) -> CommandBody<'static> {
+        CommandBody::Status {
+            mailbox: self.mbox.clone(),
+            item_names: vec![
+                StatusDataItemName::Messages,
+                StatusDataItemName::Recent,
+                StatusDataItemName::Unseen,
+                StatusDataItemName::UidNext,
+                StatusDataItemName::UidValidity,
+            ]
+            .into(),
+        }
+    }
+
+    fn process_data(&mut self, data: Data<'static>) -> Option<Data<'static>> {
+        match data {
+            Data::Status { mailbox, items } if mailbox == self.mbox => {
+                self.items.extend(items.into_owned());
+                None
+            }
+            data => Some(data),
+        }
+    }
+
+    fn process_tagged(self, status_body: StatusBody<'static>) -> Self::Output {
+        status_to_result(status_body).map(|()| self.items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, num::NonZeroU32};
+
+    use imap_client::{
+        imap_next::imap_types::{mailbox::Mailbox, response::Data, status::StatusDataItem},
+        tasks::Task,
+    };
+
+    use super::StatusTask;
+
+    #[test]
+    fn process_data_of_requested_mailbox_only() {
+        let mut task = StatusTask::new(Mailbox::try_from("Archives").unwrap());
+
+        let other = Data::Status {
+            mailbox: Mailbox::try_from("INBOX").unwrap(),
+            items: Cow::Owned(vec![StatusDataItem::Messages(3)]),
+        };
+        assert!(task.process_data(other).is_some());
+
+        let data = Data::Status {
+            mailbox: Mailbox::try_from("Archives").unwrap(),
+            items: Cow::Owned(vec![
+                StatusDataItem::Messages(1),
+                StatusDataItem::UidNext(NonZeroU32::new(2).unwrap()),
+            ]),
+        };
+        assert!(task.process_data(data).is_none());
+
+        assert_eq!(
+            task.items,
+            vec![
+                StatusDataItem::Messages(1),
+                StatusDataItem::UidNext(NonZeroU32::new(2).unwrap()),
+            ]
+        );
+    }
+}
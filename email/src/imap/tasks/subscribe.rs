@@ -0,0 +1,58 @@
+use imap_client::{
+    imap_next::imap_types::{command::CommandBody, mailbox::Mailbox, response::StatusBody},
+    tasks::{tasks::TaskError, Task},
+};
+
+use super::status_to_result;
+
+/// The IMAP SUBSCRIBE task (RFC 3501).
+#[derive(Clone, Debug)]
+pub struct SubscribeTask {
+    mbox: Mailbox<'static>,
+}
+
+impl SubscribeTask {
+    pub fn new(mbox: Mailbox<'static>) -> Self {
+        Self { mbox }
+    }
+}
+
+impl Task for SubscribeTask {
+    type Output = Result<(), TaskError>;
+
+    fn command_body(&self) -> CommandBody<'static> {
+        CommandBody::Subscribe {
+            mailbox: self.mbox.clone(),
+        }
+    }
+
+    fn process_tagged(self, status_body: StatusBody<'static>) -> Self::Output {
+        status_to_result(status_body)
+    }
+}
+
+/// The IMAP UNSUBSCRIBE task (RFC 3501).
+#[derive(Clone, Debug)]
+pub struct UnsubscribeTask {
+    mbox: Mailbox<'static>,
+}
+
+impl UnsubscribeTask {
+    pub fn new(mbox: Mailbox<'static>) -> Self {
+        Self { mbox }
+    }
+}
+
+impl Task for UnsubscribeTask {
+    type Output = Result<(), TaskError>;
+
+    fn command_body(&self) -> CommandBody<'static> {
+        CommandBody::Unsubscribe {
+            mailbox: self.mbox.clone(),
+        }
+    }
+
+    fn process_tagged(self, status_body: StatusBody<'static>) -> Self::Output {
+        status_to_result(status_body)
+    }
+}
@@ -22,8 +22,15 @@
 //!
 //! - [`AddFolder`](crate::folder::add::AddFolder)
 //! - [`ListFolders`](crate::folder::list::ListFolders)
+//! - [`ListFoldersWithStatus`](crate::folder::status::ListFoldersWithStatus)
+//! - [`GetFolderStatus`](crate::folder::status::GetFolderStatus)
 //! - [`ExpungeFolder`](crate::folder::expunge::ExpungeFolder)
 //! - [`PurgeFolder`](crate::folder::purge::PurgeFolder)
+//! - [`RenameFolder`](crate::folder::rename::RenameFolder)
+//! - [`SubscribeFolder`](crate::folder::subscribe::SubscribeFolder)
+//! - [`UnsubscribeFolder`](crate::folder::subscribe::UnsubscribeFolder)
+//! - [`GetFolderAnnotations`](crate::folder::annotation::GetFolderAnnotations)
+//! - [`SetFolderAnnotation`](crate::folder::annotation::SetFolderAnnotation)
 //! - [`DeleteFolder`](crate::folder::delete::DeleteFolder)
 //!
 //! ### Envelope
@@ -47,6 +54,10 @@
 //! - [`MoveMessages`](crate::message::move_::MoveMessages)
 //! - [`DeleteMessages`](crate::message::delete::DeleteMessages)
 //! - [`SendRawMessage`](crate::message::send_raw::SendRawMessage)
+//!
+//! ### Quota
+//!
+//! - [`GetQuota`](crate::quota::GetQuota)
 
 pub mod account;
 #[cfg(feature = "autoconfig")]
@@ -62,6 +73,7 @@
 pub mod maildir;
 #[cfg(feature = "notmuch")]
 pub mod notmuch;
+pub mod quota;
 pub mod retry;
 #[cfg(feature = "sendmail")]
 pub mod sendmail;
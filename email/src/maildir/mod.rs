@@ -33,9 +33,18 @@
     },
     folder::{
         add::{maildir::AddMaildirFolder, AddFolder},
+        annotation::{
+            maildir::{GetMaildirFolderAnnotations, SetMaildirFolderAnnotation},
+            GetFolderAnnotations, SetFolderAnnotation,
+        },
         delete::{maildir::DeleteMaildirFolder, DeleteFolder},
         expunge::{maildir::ExpungeMaildirFolder, ExpungeFolder},
         list::{maildir::ListMaildirFolders, ListFolders},
+        rename::{maildir::RenameMaildirFolder, RenameFolder},
+        status::{
+            maildir::{GetMaildirFolderStatus, ListMaildirFoldersWithStatus},
+            GetFolderStatus, ListFoldersWithStatus,
+        },
         FolderKind,
     },
     message::{
@@ -47,6 +56,7 @@
         r#move::{maildir::MoveMaildirMessages, MoveMessages},
         remove::{maildir::RemoveMaildirMessages, RemoveMessages},
     },
+    quota::{maildir::GetMaildirQuota, GetQuota},
     AnyResult,
 };
 
@@ -69,10 +79,15 @@ impl MaildirContext {
     /// Create a maildir instance from a folder name.
     pub fn get_maildir_from_folder_alias(&self, folder: &str) -> Result<Maildir> {
         let folder = self.account_config.get_folder_alias(folder);
+        self.get_maildir_from_folder_name(&folder)
+    }
 
+    /// Get the Maildir instance of the given folder name, without
+    /// resolving folder aliases.
+    pub fn get_maildir_from_folder_name(&self, folder: &str) -> Result<Maildir> {
         // If the folder matches to the inbox folder kind, create a
         // maildir instance from the root folder.
-        if self.maildir_config.maildirpp && FolderKind::matches_inbox(&folder) {
+        if self.maildir_config.maildirpp && FolderKind::matches_inbox(folder) {
             return Ok(Maildir::from(try_shellexpand_path(self.root.path())?));
         }
 
@@ -175,6 +190,16 @@ fn list_folders(&self) -> Option<BackendFeature<Self::Context, dyn ListFolders>>
         Some(Arc::new(ListMaildirFolders::some_new_boxed))
     }
 
+    fn list_folders_with_status(
+        &self,
+    ) -> Option<BackendFeature<Self::Context, dyn ListFoldersWithStatus>> {
+        Some(Arc::new(ListMaildirFoldersWithStatus::some_new_boxed))
+    }
+
+    fn get_folder_status(&self) -> Option<BackendFeature<Self::Context, dyn GetFolderStatus>> {
+        Some(Arc::new(GetMaildirFolderStatus::some_new_boxed))
+    }
+
     fn expunge_folder(&self) -> Option<BackendFeature<Self::Context, dyn ExpungeFolder>> {
         Some(Arc::new(ExpungeMaildirFolder::some_new_boxed))
     }
@@ -184,6 +209,22 @@ fn expunge_folder(&self) -> Option<BackendFeature<Self::Context, dyn ExpungeFold
     //     Some(Arc::new(PurgeMaildirFolder::some_new_boxed))
     // }
 
+    fn rename_folder(&self) -> Option<BackendFeature<Self::Context, dyn RenameFolder>> {
+        Some(Arc::new(RenameMaildirFolder::some_new_boxed))
+    }
+
+    fn get_folder_annotations(
+        &self,
+    ) -> Option<BackendFeature<Self::Context, dyn GetFolderAnnotations>> {
+        Some(Arc::new(GetMaildirFolderAnnotations::some_new_boxed))
+    }
+
+    fn set_folder_annotation(
+        &self,
+    ) -> Option<BackendFeature<Self::Context, dyn SetFolderAnnotation>> {
+        Some(Arc::new(SetMaildirFolderAnnotation::some_new_boxed))
+    }
+
     fn delete_folder(&self) -> Option<BackendFeature<Self::Context, dyn DeleteFolder>> {
         Some(Arc::new(DeleteMaildirFolder::some_new_boxed))
     }
@@ -246,6 +287,10 @@ fn remove_messages(&self) -> Option<BackendFeature<Self::Context, dyn RemoveMess
         Some(Arc::new(RemoveMaildirMessages::some_new_boxed))
     }
 
+    fn get_quota(&self) -> Option<BackendFeature<Self::Context, dyn GetQuota>> {
+        Some(Arc::new(GetMaildirQuota::some_new_boxed))
+    }
+
     async fn build(self) -> AnyResult<Self::Context> {
         info!("building new maildir context");
 
@@ -46,6 +46,9 @@ pub struct NotmuchConfig {
 
     #[cfg_attr(feature = "derive", serde(default))]
     pub maildirpp: bool,
+
+    /// The Notmuch watch configuration.
+    pub watch: Option<NotmuchWatchConfig>,
 }
 
 impl NotmuchConfig {
@@ -90,4 +93,33 @@ pub fn find_config_path(&self) -> Option<&Path> {
     pub fn find_profile(&self) -> Option<&str> {
         self.profile.as_deref()
     }
+
+    /// Find the Notmuch watch poll interval, in seconds.
+    pub fn find_watch_poll_interval(&self) -> Option<u64> {
+        self.watch.as_ref().and_then(|c| c.find_poll_interval())
+    }
+}
+
+/// The Notmuch watch options.
+///
+/// Changes are watched by polling the database revision.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct NotmuchWatchConfig {
+    /// The Notmuch watch poll interval, in seconds.
+    ///
+    /// Interval used to check if the database revision changed.
+    /// Defaults to 10 s.
+    poll_interval: Option<u64>,
+}
+
+impl NotmuchWatchConfig {
+    /// Find the Notmuch watch poll interval.
+    pub fn find_poll_interval(&self) -> Option<u64> {
+        self.poll_interval
+    }
 }
@@ -13,6 +13,8 @@
 use self::config::NotmuchConfig;
 #[doc(inline)]
 pub use self::error::{Error, Result};
+#[cfg(feature = "watch")]
+use crate::envelope::watch::{notmuch::WatchNotmuchEnvelopes, WatchEnvelopes};
 use crate::{
     account::config::AccountConfig,
     backend::{
@@ -31,6 +33,11 @@
     folder::{
         add::{notmuch::AddNotmuchFolder, AddFolder},
         list::{notmuch::ListNotmuchFolders, ListFolders},
+        rename::{notmuch::RenameNotmuchFolder, RenameFolder},
+        status::{
+            notmuch::{GetNotmuchFolderStatus, ListNotmuchFoldersWithStatus},
+            GetFolderStatus, ListFoldersWithStatus,
+        },
     },
     maildir::{config::MaildirConfig, MaildirContext},
     message::{
@@ -42,6 +49,7 @@
         r#move::{notmuch::MoveNotmuchMessages, MoveMessages},
         remove::{notmuch::RemoveNotmuchMessages, RemoveMessages},
     },
+    quota::{notmuch::GetNotmuchQuota, GetQuota},
     AnyResult,
 };
 
@@ -155,6 +163,16 @@ fn list_folders(&self) -> Option<BackendFeature<Self::Context, dyn ListFolders>>
         Some(Arc::new(ListNotmuchFolders::some_new_boxed))
     }
 
+    fn list_folders_with_status(
+        &self,
+    ) -> Option<BackendFeature<Self::Context, dyn ListFoldersWithStatus>> {
+        Some(Arc::new(ListNotmuchFoldersWithStatus::some_new_boxed))
+    }
+
+    fn get_folder_status(&self) -> Option<BackendFeature<Self::Context, dyn GetFolderStatus>> {
+        Some(Arc::new(GetNotmuchFolderStatus::some_new_boxed))
+    }
+
     // TODO
     // fn expunge_folder(&self) -> Option<BackendFeature<Self::Context, dyn ExpungeFolder>> {
     //     Some(Arc::new(ExpungeNotmuchFolder::some_new_boxed))
@@ -165,6 +183,10 @@ fn list_folders(&self) -> Option<BackendFeature<Self::Context, dyn ListFolders>>
     //     Some(Arc::new(PurgeNotmuchFolder::some_new_boxed))
     // }
 
+    fn rename_folder(&self) -> Option<BackendFeature<Self::Context, dyn RenameFolder>> {
+        Some(Arc::new(RenameNotmuchFolder::some_new_boxed))
+    }
+
     // TODO
     // fn delete_folder(&self) -> Option<BackendFeature<Self::Context, dyn DeleteFolder>> {
     //     Some(Arc::new(DeleteNotmuchFolder::some_new_boxed))
@@ -178,10 +200,10 @@ fn list_envelopes(&self) -> Option<BackendFeature<Self::Context, dyn ListEnvelop
         Some(Arc::new(ListNotmuchEnvelopes::some_new_boxed))
     }
 
-    // TODO
-    // fn watch_envelopes(&self) -> Option<BackendFeature<Self::Context, dyn WatchEnvelopes>> {
-    //     Some(Arc::new(WatchNotmuchEnvelopes::some_new_boxed))
-    // }
+    #[cfg(feature = "watch")]
+    fn watch_envelopes(&self) -> Option<BackendFeature<Self::Context, dyn WatchEnvelopes>> {
+        Some(Arc::new(WatchNotmuchEnvelopes::some_new_boxed))
+    }
 
     fn add_flags(&self) -> Option<BackendFeature<Self::Context, dyn AddFlags>> {
         Some(Arc::new(AddNotmuchFlags::some_new_boxed))
@@ -223,6 +245,10 @@ fn remove_messages(&self) -> Option<BackendFeature<Self::Context, dyn RemoveMess
         Some(Arc::new(RemoveNotmuchMessages::some_new_boxed))
     }
 
+    fn get_quota(&self) -> Option<BackendFeature<Self::Context, dyn GetQuota>> {
+        Some(Arc::new(GetNotmuchQuota::some_new_boxed))
+    }
+
     async fn build(self) -> AnyResult<Self::Context> {
         info!("building new notmuch context");
 
@@ -0,0 +1,105 @@
+#[cfg(feature = "watch")]
+use std::time::Duration;
+
+use super::{Quota, QuotaUsage};
+#[cfg(feature = "watch")]
+use crate::watch::config::WatchHook;
+
+/// The quota configuration.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct QuotaConfig {
+    /// The storage limit, in bytes.
+    ///
+    /// Only applies to local backends (Maildir and Notmuch), which
+    /// cannot know the limit of the underlying filesystem. Remote
+    /// backends report their own limits.
+    pub storage_limit: Option<u64>,
+
+    /// The message limit, in number of messages.
+    ///
+    /// Only applies to local backends (Maildir and Notmuch), which
+    /// cannot know the limit of the underlying filesystem. Remote
+    /// backends report their own limits.
+    pub message_limit: Option<u64>,
+
+    /// The configuration dedicated to quota changes.
+    #[cfg(feature = "watch")]
+    pub watch: Option<WatchQuotaConfig>,
+}
+
+impl QuotaConfig {
+    /// Return `true` if at least one local limit is configured.
+    pub fn has_local_limit(&self) -> bool {
+        self.storage_limit.is_some() || self.message_limit.is_some()
+    }
+
+    /// Apply the configured local limits to the given quota.
+    pub fn apply_local_limits(&self, quota: &mut Quota) {
+        let apply = |usage: &mut Option<QuotaUsage>, limit: Option<u64>| {
+            if let (Some(usage), Some(limit)) = (usage.as_mut(), limit) {
+                usage.limit = Some(limit);
+            }
+        };
+
+        apply(&mut quota.storage, self.storage_limit);
+        apply(&mut quota.messages, self.message_limit);
+    }
+}
+
+/// The configuration dedicated to quota changes.
+///
+/// Quotas are checked while watching envelopes, when a change occurs
+/// and at most once per interval.
+#[cfg(feature = "watch")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct WatchQuotaConfig {
+    /// The usage threshold, in percent, from which the hook is
+    /// executed.
+    ///
+    /// Defaults to 90.
+    pub threshold: Option<u8>,
+
+    /// The minimum interval between two quota checks, in seconds.
+    ///
+    /// Defaults to 300 (5 minutes).
+    pub interval: Option<u64>,
+
+    /// Watch hook configuration for when the usage of a resource
+    /// crosses the threshold.
+    ///
+    /// The command and the notification receive the resource:
+    ///  - as placeholders: "{resource}" (storage or message),
+    ///    "{usage}", "{limit}" and "{percentage}"
+    ///  - as `QUOTA_*` environment variables, for example
+    ///    `QUOTA_PERCENTAGE`
+    ///  - as JSON on the standard input
+    ///
+    /// The callback is not supported for quota hooks.
+    pub exceeded: Option<WatchHook>,
+}
+
+#[cfg(feature = "watch")]
+impl WatchQuotaConfig {
+    pub const DEFAULT_THRESHOLD: u8 = 90;
+    pub const DEFAULT_INTERVAL: u64 = 300;
+
+    /// Get the threshold, or the default one.
+    pub fn get_threshold(&self) -> u8 {
+        self.threshold.unwrap_or(Self::DEFAULT_THRESHOLD)
+    }
+
+    /// Get the interval, or the default one.
+    pub fn get_interval(&self) -> Duration {
+        Duration::from_secs(self.interval.unwrap_or(Self::DEFAULT_INTERVAL))
+    }
+}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use imap_client::imap_next::imap_types::extensions::quota::{QuotaGet, Resource};
+use tracing::{debug, info};
+use utf7_imap::encode_utf7_imap as encode_utf7;
+
+use super::{GetQuota, Quota, QuotaUsage};
+use crate::{imap::ImapContext, AnyResult};
+
+impl Quota {
+    /// Build a quota from the given IMAP quota root resources.
+    ///
+    /// Storage resources are expressed in units of 1024 octets, they
+    /// are converted into bytes (RFC 9208).
+    pub fn from_imap_quota(root: impl ToString, quotas: &[QuotaGet]) -> Self {
+        let mut quota = Quota {
+            root: Some(root.to_string()),
+            ..Default::default()
+        };
+
+        for q in quotas {
+            match q.resource {
+                Resource::Storage => {
+                    quota.storage = Some(QuotaUsage::new(q.usage * 1024, Some(q.limit * 1024)));
+                }
+                Resource::Message => {
+                    quota.messages = Some(QuotaUsage::new(q.usage, Some(q.limit)));
+                }
+                _ => {
+                    debug!("skipping unsupported imap quota resource {:?}", q.resource);
+                }
+            }
+        }
+
+        quota
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GetImapQuota {
+    ctx: ImapContext,
+}
+
+impl GetImapQuota {
+    pub fn new(ctx: &ImapContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &ImapContext) -> Box<dyn GetQuota> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &ImapContext) -> Option<Box<dyn GetQuota>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl GetQuota for GetImapQuota {
+    async fn get_quota(&self, folder: &str) -> AnyResult<Quota> {
+        info!("getting imap quota of folder {folder}");
+
+        let mut client = self.ctx.client().await;
+        let config = &client.account_config;
+
+        let folder = config.get_folder_alias(folder);
+        let folder_encoded = encode_utf7(folder.clone());
+        debug!("utf7 encoded folder: {folder_encoded}");
+
+        let quota = client.get_quota_root(folder_encoded).await?;
+
+        Ok(quota)
+    }
+}
@@ -0,0 +1,99 @@
+use std::{fs, path::Path};
+
+use async_trait::async_trait;
+use tracing::{debug, info};
+
+use super::{GetQuota, Quota, QuotaUsage};
+use crate::{maildir::MaildirContextSync, AnyResult};
+
+impl Quota {
+    /// Build a best-effort quota from the filesystem usage of the
+    /// given Maildir root.
+    ///
+    /// The storage usage is the size of all files found under the
+    /// root, and the message usage is the number of files found in
+    /// `cur` and `new` directories. Limits are unknown, see
+    /// [`QuotaConfig`](super::config::QuotaConfig) to configure
+    /// them. Unreadable entries are skipped.
+    pub fn from_maildir_root(root: impl AsRef<Path>) -> Self {
+        let mut storage = 0;
+        let mut messages = 0;
+        let mut dirs = vec![(root.as_ref().to_owned(), false)];
+
+        while let Some((dir, is_mdir_subdir)) = dirs.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) => {
+                    debug!(?err, "cannot read directory {}, skipping it", dir.display());
+                    continue;
+                }
+            };
+
+            for entry in entries.flatten() {
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+
+                if file_type.is_dir() {
+                    let name = entry.file_name();
+                    let is_mdir_subdir = name == "cur" || name == "new";
+                    dirs.push((entry.path(), is_mdir_subdir));
+                } else if file_type.is_file() {
+                    if let Ok(metadata) = entry.metadata() {
+                        storage += metadata.len();
+                    }
+
+                    if is_mdir_subdir {
+                        messages += 1;
+                    }
+                }
+            }
+        }
+
+        Quota {
+            root: Some(root.as_ref().display().to_string()),
+            storage: Some(QuotaUsage::new(storage, None)),
+            messages: Some(QuotaUsage::new(messages, None)),
+        }
+    }
+}
+
+pub struct GetMaildirQuota {
+    ctx: MaildirContextSync,
+}
+
+impl GetMaildirQuota {
+    pub fn new(ctx: &MaildirContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &MaildirContextSync) -> Box<dyn GetQuota> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &MaildirContextSync) -> Option<Box<dyn GetQuota>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl GetQuota for GetMaildirQuota {
+    /// Get the filesystem usage of the Maildir root.
+    ///
+    /// The whole Maildir root shares the same quota, so the given
+    /// folder is ignored. Limits are taken from the quota
+    /// configuration.
+    async fn get_quota(&self, folder: &str) -> AnyResult<Quota> {
+        info!("getting maildir quota of folder {folder}");
+
+        let ctx = self.ctx.lock().await;
+        let root = ctx.root.path().to_owned();
+        let config = ctx.account_config.quota.clone().unwrap_or_default();
+        drop(ctx);
+
+        let mut quota = tokio::task::spawn_blocking(move || Quota::from_maildir_root(root)).await?;
+        config.apply_local_limits(&mut quota);
+
+        Ok(quota)
+    }
+}
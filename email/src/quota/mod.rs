@@ -0,0 +1,240 @@
+//! # Quota
+//!
+//! Module dedicated to account quotas. The main structure of this
+//! module is [`Quota`], which gathers the storage and message usage
+//! of an account alongside their limits.
+//!
+//! The [`GetQuota`] backend feature returns the quota applied to a
+//! given folder. IMAP uses the `GETQUOTAROOT` command (RFC 9208),
+//! whereas Maildir and Notmuch report the filesystem usage of the
+//! Maildir root, as a best-effort value whose limits come from the
+//! configuration.
+//!
+//! The [`config`] module exposes the quota configuration, which
+//! allows to execute a watch hook when the usage crosses a given
+//! threshold.
+
+pub mod config;
+#[cfg(feature = "imap")]
+pub mod imap;
+#[cfg(feature = "maildir")]
+pub mod maildir;
+#[cfg(feature = "notmuch")]
+pub mod notmuch;
+
+use std::fmt;
+#[cfg(feature = "watch")]
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::AnyResult;
+
+/// The quota resource kind.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum QuotaResourceKind {
+    /// The storage resource, in bytes.
+    Storage,
+
+    /// The message resource, in number of messages.
+    Message,
+}
+
+impl fmt::Display for QuotaResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Storage => write!(f, "storage"),
+            Self::Message => write!(f, "message"),
+        }
+    }
+}
+
+/// The usage of a quota resource.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct QuotaUsage {
+    /// The current usage of the resource.
+    pub usage: u64,
+
+    /// The limit of the resource, if known.
+    pub limit: Option<u64>,
+}
+
+impl QuotaUsage {
+    pub fn new(usage: u64, limit: Option<u64>) -> Self {
+        Self { usage, limit }
+    }
+
+    /// Return the usage as a percentage of the limit, if the limit
+    /// is known and not null.
+    pub fn percentage(&self) -> Option<f64> {
+        match self.limit {
+            Some(limit) if limit > 0 => Some(self.usage as f64 * 100.0 / limit as f64),
+            _ => None,
+        }
+    }
+}
+
+/// The quota structure.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct Quota {
+    /// The quota root name, if any.
+    ///
+    /// For IMAP, the quota root is the name of the resources set the
+    /// folder belongs to.
+    pub root: Option<String>,
+
+    /// The storage usage, in bytes.
+    pub storage: Option<QuotaUsage>,
+
+    /// The message usage, in number of messages.
+    pub messages: Option<QuotaUsage>,
+}
+
+impl Quota {
+    /// Get the usage of the given resource kind.
+    pub fn get(&self, kind: QuotaResourceKind) -> Option<&QuotaUsage> {
+        match kind {
+            QuotaResourceKind::Storage => self.storage.as_ref(),
+            QuotaResourceKind::Message => self.messages.as_ref(),
+        }
+    }
+
+    /// Return the resources whose usage crossed the given threshold
+    /// (in percent) since the previous quota.
+    ///
+    /// A resource crosses the threshold when its usage is above or
+    /// equal to the threshold, and was below it previously (or when
+    /// there is no previous quota).
+    pub fn crossed_threshold(&self, prev: Option<&Quota>, threshold: u8) -> Vec<QuotaResourceKind> {
+        let threshold = threshold as f64;
+
+        [QuotaResourceKind::Storage, QuotaResourceKind::Message]
+            .into_iter()
+            .filter(|kind| {
+                let above = |quota: &Quota| {
+                    quota
+                        .get(*kind)
+                        .and_then(QuotaUsage::percentage)
+                        .map(|percentage| percentage >= threshold)
+                        .unwrap_or_default()
+                };
+
+                above(self) && !prev.map(above).unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+/// The quota state of a watcher.
+///
+/// Keeps the last known quota, used to detect threshold crossings,
+/// and the instant of the last check, used to throttle checks.
+#[cfg(feature = "watch")]
+#[derive(Clone, Debug, Default)]
+pub struct QuotaWatcher {
+    last: Option<Quota>,
+    checked_at: Option<Instant>,
+}
+
+#[cfg(feature = "watch")]
+impl QuotaWatcher {
+    /// Get the last known quota.
+    pub fn last(&self) -> Option<&Quota> {
+        self.last.as_ref()
+    }
+
+    /// Return `true` if the quota was never checked, or if the
+    /// given interval elapsed since the last check.
+    pub fn is_due(&self, interval: Duration) -> bool {
+        match self.checked_at {
+            Some(checked_at) => checked_at.elapsed() >= interval,
+            None => true,
+        }
+    }
+
+    /// Mark the quota as checked.
+    ///
+    /// The last known quota is replaced by the given one, if any,
+    /// so that a failed check keeps the previous value.
+    pub fn checked(&mut self, quota: Option<Quota>) {
+        self.checked_at = Some(Instant::now());
+
+        if let Some(quota) = quota {
+            self.last = Some(quota);
+        }
+    }
+}
+
+#[async_trait]
+pub trait GetQuota: Send + Sync {
+    /// Get the quota applied to the given folder.
+    async fn get_quota(&self, folder: &str) -> AnyResult<Quota>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Quota, QuotaResourceKind, QuotaUsage};
+
+    fn quota(storage: u64, messages: u64) -> Quota {
+        Quota {
+            root: None,
+            storage: Some(QuotaUsage::new(storage, Some(100))),
+            messages: Some(QuotaUsage::new(messages, Some(100))),
+        }
+    }
+
+    #[test]
+    fn percentage() {
+        assert_eq!(QuotaUsage::new(50, Some(200)).percentage(), Some(25.0));
+        assert_eq!(QuotaUsage::new(50, Some(0)).percentage(), None);
+        assert_eq!(QuotaUsage::new(50, None).percentage(), None);
+    }
+
+    #[test]
+    fn crossed_threshold() {
+        let kinds = quota(95, 10).crossed_threshold(None, 90);
+        assert_eq!(kinds, vec![QuotaResourceKind::Storage]);
+
+        let kinds = quota(95, 92).crossed_threshold(Some(&quota(89, 91)), 90);
+        assert_eq!(kinds, vec![QuotaResourceKind::Storage]);
+
+        let kinds = quota(95, 92).crossed_threshold(Some(&quota(95, 92)), 90);
+        assert!(kinds.is_empty());
+
+        let kinds = Quota::default().crossed_threshold(None, 0);
+        assert!(kinds.is_empty());
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn watcher() {
+        use std::time::Duration;
+
+        use super::QuotaWatcher;
+
+        let mut watcher = QuotaWatcher::default();
+        assert!(watcher.is_due(Duration::from_secs(300)));
+
+        watcher.checked(Some(quota(95, 10)));
+        assert!(!watcher.is_due(Duration::from_secs(300)));
+        assert!(watcher.is_due(Duration::ZERO));
+
+        watcher.checked(None);
+        assert_eq!(watcher.last(), Some(&quota(95, 10)));
+    }
+}
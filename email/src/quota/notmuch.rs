@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use tracing::info;
+
+use super::{GetQuota, Quota};
+use crate::{notmuch::NotmuchContextSync, AnyResult};
+
+pub struct GetNotmuchQuota {
+    ctx: NotmuchContextSync,
+}
+
+impl GetNotmuchQuota {
+    pub fn new(ctx: &NotmuchContextSync) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    pub fn new_boxed(ctx: &NotmuchContextSync) -> Box<dyn GetQuota> {
+        Box::new(Self::new(ctx))
+    }
+
+    pub fn some_new_boxed(ctx: &NotmuchContextSync) -> Option<Box<dyn GetQuota>> {
+        Some(Self::new_boxed(ctx))
+    }
+}
+
+#[async_trait]
+impl GetQuota for GetNotmuchQuota {
+    /// Get the filesystem usage of the Maildir root behind the
+    /// Notmuch database.
+    async fn get_quota(&self, folder: &str) -> AnyResult<Quota> {
+        info!("getting notmuch quota of folder {folder} via maildir");
+
+        let ctx = self.ctx.lock().await;
+        let root = ctx.mdir_ctx.root.path().to_owned();
+        let config = ctx.account_config.quota.clone().unwrap_or_default();
+        drop(ctx);
+
+        let mut quota = tokio::task::spawn_blocking(move || Quota::from_maildir_root(root)).await?;
+        config.apply_local_limits(&mut quota);
+
+        Ok(quota)
+    }
+}
@@ -8,10 +8,14 @@
 use mail_parser::{Addr, Address, HeaderName, HeaderValue, Message, MessageParser};
 use mail_send::{
     smtp::message::{Address as SmtpAddress, IntoMessage, Message as SmtpMessage},
-    SmtpClientBuilder,
+    SmtpClient, SmtpClientBuilder,
 };
+use smtp_proto::EhloResponse;
 #[cfg(feature = "tokio")]
-use tokio::net::TcpStream;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+};
 #[cfg(feature = "tokio-native-tls")]
 use tokio_native_tls::TlsStream;
 #[cfg(feature = "tokio-rustls")]
@@ -24,6 +28,7 @@
 use crate::{
     account::config::AccountConfig,
     backend::{
+        capability::{ServerCapabilities, ServerCapability},
         context::{BackendContext, BackendContextBuilder},
         feature::{BackendFeature, CheckUp},
     },
@@ -48,6 +53,10 @@ pub struct SmtpContext {
 
     /// The SMTP client.
     client: SmtpClientStream,
+
+    /// The capabilities advertised by the server in its `EHLO`
+    /// response, at connection time.
+    capabilities: ServerCapabilities,
 }
 
 impl SmtpContext {
@@ -113,12 +122,15 @@ pub async fn send(&mut self, msg: &[u8]) -> Result<()> {
 
                     debug!("re-connecting…");
 
-                    self.client = if self.smtp_config.is_encryption_enabled() {
+                    let (client, ehlo) = if self.smtp_config.is_encryption_enabled() {
                         build_tls_client(&self.client_builder).await
                     } else {
                         build_tcp_client(&self.client_builder).await
                     }?;
 
+                    self.client = client;
+                    self.capabilities = to_server_capabilities(&ehlo);
+
                     retry.reset();
                     continue;
                 }
@@ -129,8 +141,40 @@ pub async fn send(&mut self, msg: &[u8]) -> Result<()> {
     pub async fn noop(&mut self) -> Result<()> {
         self.client.noop().await
     }
+
+    /// Return the extensions advertised by the server when the
+    /// client connected.
+    pub fn capabilities(&self) -> &ServerCapabilities {
+        &self.capabilities
+    }
+}
+
+/// Collect the extensions of the given `EHLO` response as server
+/// capabilities.
+fn to_server_capabilities(ehlo: &EhloResponse<String>) -> ServerCapabilities {
+    SMTP_EXTENSIONS
+        .iter()
+        .filter(|(ext, _)| ehlo.has_capability(*ext))
+        .map(|(_, name)| ServerCapability::Smtp(name.to_string()))
+        .collect()
 }
 
+/// The SMTP extensions reported as server capabilities, with their
+/// `EHLO` keyword.
+const SMTP_EXTENSIONS: [(u32, &str); 11] = [
+    (smtp_proto::EXT_8BIT_MIME, "8BITMIME"),
+    (smtp_proto::EXT_BINARY_MIME, "BINARYMIME"),
+    (smtp_proto::EXT_CHUNKING, "CHUNKING"),
+    (smtp_proto::EXT_DSN, "DSN"),
+    (smtp_proto::EXT_ENHANCED_STATUS_CODES, "ENHANCEDSTATUSCODES"),
+    (smtp_proto::EXT_PIPELINING, "PIPELINING"),
+    (smtp_proto::EXT_REQUIRE_TLS, "REQUIRETLS"),
+    (smtp_proto::EXT_SIZE, "SIZE"),
+    (smtp_proto::EXT_SMTP_UTF8, "SMTPUTF8"),
+    (smtp_proto::EXT_START_TLS, "STARTTLS"),
+    (smtp_proto::EXT_AUTH, "AUTH"),
+];
+
 /// The sync version of the SMTP backend context.
 ///
 /// This is just an SMTP client wrapped into a mutex, so the same SMTP
@@ -178,22 +222,27 @@ fn send_message(&self) -> Option<BackendFeature<Self::Context, dyn SendMessage>>
     async fn build(self) -> AnyResult<Self::Context> {
         info!("building new smtp context");
 
+        // EHLO is sent after connection by the client builders in
+        // order to keep the capabilities advertised by the server
         let mut client_builder =
             SmtpClientBuilder::new(self.smtp_config.host.clone(), self.smtp_config.port)
                 .credentials(self.smtp_config.credentials().await?)
-                .implicit_tls(!self.smtp_config.is_start_tls_encryption_enabled());
+                .implicit_tls(!self.smtp_config.is_start_tls_encryption_enabled())
+                .say_ehlo(false);
 
         if self.smtp_config.is_encryption_disabled() {
             client_builder = client_builder.allow_invalid_certs();
         }
 
-        let (client_builder, client) = build_client(&self.smtp_config, client_builder).await?;
+        let (client_builder, client, ehlo) =
+            build_client(&self.smtp_config, client_builder).await?;
 
         let ctx = SmtpContext {
             account_config: self.account_config,
             smtp_config: self.smtp_config,
             client_builder,
             client,
+            capabilities: to_server_capabilities(&ehlo),
         };
 
         Ok(Arc::new(Mutex::new(ctx)))
@@ -246,26 +295,35 @@ async fn check_up(&self) -> AnyResult<()> {
         let mut ctx = self.ctx.lock().await;
         Ok(ctx.noop().await?)
     }
+
+    async fn server_capabilities(&self) -> AnyResult<ServerCapabilities> {
+        let ctx = self.ctx.lock().await;
+        Ok(ctx.capabilities().clone())
+    }
 }
 
 pub async fn build_client(
     smtp_config: &SmtpConfig,
     #[cfg_attr(not(feature = "oauth2"), allow(unused_mut))]
     mut client_builder: mail_send::SmtpClientBuilder<String>,
-) -> Result<(mail_send::SmtpClientBuilder<String>, SmtpClientStream)> {
+) -> Result<(
+    mail_send::SmtpClientBuilder<String>,
+    SmtpClientStream,
+    EhloResponse<String>,
+)> {
     match (&smtp_config.auth, smtp_config.is_encryption_enabled()) {
         (SmtpAuthConfig::Password(_), false) => {
-            let client = build_tcp_client(&client_builder).await?;
-            Ok((client_builder, client))
+            let (client, ehlo) = build_tcp_client(&client_builder).await?;
+            Ok((client_builder, client, ehlo))
         }
         (SmtpAuthConfig::Password(_), true) => {
-            let client = build_tls_client(&client_builder).await?;
-            Ok((client_builder, client))
+            let (client, ehlo) = build_tls_client(&client_builder).await?;
+            Ok((client_builder, client, ehlo))
         }
         #[cfg(feature = "oauth2")]
         (SmtpAuthConfig::OAuth2(oauth2_config), false) => {
             match Ok(build_tcp_client(&client_builder).await?) {
-                Ok(client) => Ok((client_builder, client)),
+                Ok((client, ehlo)) => Ok((client_builder, client, ehlo)),
                 Err(Error::ConnectTcpSmtpError(mail_send::Error::AuthenticationFailed(_))) => {
                     warn!("authentication failed, refreshing access token and retrying…");
                     oauth2_config
@@ -273,8 +331,8 @@ pub async fn build_client(
                         .await
                         .map_err(|_| Error::RefreshingAccessTokenFailed)?;
                     client_builder = client_builder.credentials(smtp_config.credentials().await?);
-                    let client = build_tcp_client(&client_builder).await?;
-                    Ok((client_builder, client))
+                    let (client, ehlo) = build_tcp_client(&client_builder).await?;
+                    Ok((client_builder, client, ehlo))
                 }
                 Err(err) => Err(err),
             }
@@ -282,7 +340,7 @@ pub async fn build_client(
         #[cfg(feature = "oauth2")]
         (SmtpAuthConfig::OAuth2(oauth2_config), true) => {
             match Ok(build_tls_client(&client_builder).await?) {
-                Ok(client) => Ok((client_builder, client)),
+                Ok((client, ehlo)) => Ok((client_builder, client, ehlo)),
                 Err(Error::ConnectTlsSmtpError(mail_send::Error::AuthenticationFailed(_))) => {
                     warn!("authentication failed, refreshing access token and retrying…");
                     oauth2_config
@@ -290,8 +348,8 @@ pub async fn build_client(
                         .await
                         .map_err(|_| Error::RefreshingAccessTokenFailed)?;
                     client_builder = client_builder.credentials(smtp_config.credentials().await?);
-                    let client = build_tls_client(&client_builder).await?;
-                    Ok((client_builder, client))
+                    let (client, ehlo) = build_tls_client(&client_builder).await?;
+                    Ok((client_builder, client, ehlo))
                 }
                 Err(err) => Err(err),
             }
@@ -301,20 +359,54 @@ pub async fn build_client(
 
 pub async fn build_tcp_client(
     client_builder: &mail_send::SmtpClientBuilder<String>,
-) -> Result<SmtpClientStream> {
-    match client_builder.connect_plain().await {
-        Ok(client) => Ok(SmtpClientStream::Tcp(client)),
-        Err(err) => Err(Error::ConnectTcpSmtpError(err)),
-    }
+) -> Result<(SmtpClientStream, EhloResponse<String>)> {
+    let connect = async {
+        let mut client = client_builder.connect_plain().await?;
+        let ehlo = hello(&mut client, client_builder).await?;
+        Ok((SmtpClientStream::Tcp(client), ehlo))
+    };
+
+    connect.await.map_err(Error::ConnectTcpSmtpError)
 }
 
 pub async fn build_tls_client(
     client_builder: &mail_send::SmtpClientBuilder<String>,
-) -> Result<SmtpClientStream> {
-    match client_builder.connect().await {
-        Ok(client) => Ok(SmtpClientStream::Tls(client)),
-        Err(err) => Err(Error::ConnectTlsSmtpError(err)),
-    }
+) -> Result<(SmtpClientStream, EhloResponse<String>)> {
+    let connect = async {
+        let mut client = client_builder.connect().await?;
+        let ehlo = hello(&mut client, client_builder).await?;
+        Ok((SmtpClientStream::Tls(client), ehlo))
+    };
+
+    connect.await.map_err(Error::ConnectTlsSmtpError)
+}
+
+/// Send `EHLO`, then authenticate using the mechanisms advertised by
+/// the server.
+///
+/// The client builder is expected not to say `EHLO` by itself, so
+/// the response can be kept for the whole session. Like the
+/// connection, the whole exchange is bounded by the timeout of the
+/// client builder.
+async fn hello<T: AsyncRead + AsyncWrite + Unpin>(
+    client: &mut SmtpClient<T>,
+    client_builder: &SmtpClientBuilder<String>,
+) -> mail_send::Result<EhloResponse<String>> {
+    let hello = async {
+        let ehlo = client
+            .capabilities(&client_builder.local_host, client_builder.is_lmtp)
+            .await?;
+
+        if let Some(credentials) = &client_builder.credentials {
+            client.authenticate(credentials, &ehlo).await?;
+        }
+
+        Ok(ehlo)
+    };
+
+    tokio::time::timeout(client_builder.timeout, hello)
+        .await
+        .map_err(|_| mail_send::Error::Timeout)?
 }
 
 /// Transform a [`mail_parser::Message`] into a
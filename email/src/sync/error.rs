@@ -35,4 +35,27 @@ pub enum Error {
     RightContextNotConfiguredError(#[source] AnyBoxedError),
     #[error("cannot build sync pool context")]
     BuildSyncPoolContextError(#[source] AnyBoxedError),
+
+    #[error("cannot read migration state file at {1}")]
+    ReadMigrateStateError(#[source] io::Error, PathBuf),
+    #[error("cannot write migration state file at {1}")]
+    WriteMigrateStateError(#[source] io::Error, PathBuf),
+    #[error("cannot configure migration target context")]
+    ConfigureMigrateTargetError(#[source] AnyBoxedError),
+    #[error("cannot build migration backends")]
+    BuildMigrateBackendsError(#[source] AnyBoxedError),
+    #[error("cannot list migration source folders")]
+    ListMigrateSourceFoldersError(#[source] AnyBoxedError),
+    #[error("cannot list migration target folders")]
+    ListMigrateTargetFoldersError(#[source] AnyBoxedError),
+    #[error("cannot create migration target folder {1}")]
+    CreateMigrateFolderError(#[source] AnyBoxedError, String),
+    #[error("cannot list migration source envelopes from folder {1}")]
+    ListMigrateSourceEnvelopesError(#[source] AnyBoxedError, String),
+    #[error("cannot list migration target envelopes from folder {1}")]
+    ListMigrateTargetEnvelopesError(#[source] AnyBoxedError, String),
+    #[error("cannot update flags of migrated email {2} ({1})")]
+    UpdateMigrateFlagsError(#[source] AnyBoxedError, String, String),
+    #[error("cannot copy email {2} to migration target folder {1}")]
+    CopyMigrateEmailError(#[source] AnyBoxedError, String, String),
 }
@@ -0,0 +1,115 @@
+//! # Migration folder mapping
+//!
+//! Module dedicated to folder name mapping. The main structure of
+//! this module is [`FolderMapping`], which translates source folder
+//! names into target folder names during a migration.
+
+/// The folder mapping rule.
+///
+/// A rule maps a source folder name to a target folder name. If the
+/// source ends with `*`, the rule matches all folders starting with
+/// the given prefix, and the rest of the folder name is appended to
+/// the target (which should also end with `*`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct FolderMappingRule {
+    /// The source folder name, or prefix if it ends with `*`.
+    pub from: String,
+
+    /// The target folder name, or prefix if it ends with `*`.
+    pub to: String,
+}
+
+impl FolderMappingRule {
+    pub fn new(from: impl ToString, to: impl ToString) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    /// Apply the rule to the given folder name.
+    ///
+    /// Returns `None` if the rule does not match the folder.
+    pub fn apply(&self, folder: &str) -> Option<String> {
+        match self.from.strip_suffix('*') {
+            None if self.from == folder => Some(self.to.clone()),
+            None => None,
+            Some(prefix) => {
+                let rest = folder.strip_prefix(prefix)?;
+                let to = self.to.strip_suffix('*').unwrap_or(&self.to);
+                Some(format!("{to}{rest}"))
+            }
+        }
+    }
+}
+
+/// The folder mapping.
+///
+/// Rules are applied in order, the first matching one wins. Folders
+/// that do not match any rule keep their name.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct FolderMapping {
+    pub rules: Vec<FolderMappingRule>,
+}
+
+impl FolderMapping {
+    pub fn add_rule(&mut self, from: impl ToString, to: impl ToString) {
+        self.rules.push(FolderMappingRule::new(from, to));
+    }
+
+    pub fn with_rule(mut self, from: impl ToString, to: impl ToString) -> Self {
+        self.add_rule(from, to);
+        self
+    }
+
+    /// Map the given source folder name to its target folder name.
+    pub fn map(&self, folder: &str) -> String {
+        self.rules
+            .iter()
+            .find_map(|rule| rule.apply(folder))
+            .unwrap_or_else(|| folder.to_owned())
+    }
+}
+
+impl<F: ToString, T: ToString> FromIterator<(F, T)> for FolderMapping {
+    fn from_iter<I: IntoIterator<Item = (F, T)>>(iter: I) -> Self {
+        Self {
+            rules: iter
+                .into_iter()
+                .map(|(from, to)| FolderMappingRule::new(from, to))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FolderMapping;
+
+    #[test]
+    fn map() {
+        let mapping = FolderMapping::from_iter([
+            ("[Gmail]/Sent Mail", "Sent"),
+            ("[Gmail]/All Mail", "Archives"),
+            ("[Gmail]/*", "*"),
+            ("Work/*", "Projects/*"),
+        ]);
+
+        assert_eq!(mapping.map("INBOX"), "INBOX");
+        assert_eq!(mapping.map("[Gmail]/Sent Mail"), "Sent");
+        assert_eq!(mapping.map("[Gmail]/All Mail"), "Archives");
+        assert_eq!(mapping.map("[Gmail]/Drafts"), "Drafts");
+        assert_eq!(mapping.map("Work/2024"), "Projects/2024");
+        assert_eq!(mapping.map("Workshop"), "Workshop");
+    }
+}
@@ -0,0 +1,561 @@
+//! # Migration
+//!
+//! Module dedicated to one-way migration of folders and emails from
+//! a source backend to a target backend, typically from an IMAP
+//! server to another one. The main structure of this module is
+//! [`MigrateBuilder`].
+//!
+//! Contrary to the [synchronization](super::SyncBuilder), the
+//! migration never touches the source backend, and never deletes
+//! anything from the target backend. Messages are copied with their
+//! flags and their internal date. Folder names can be translated
+//! using a [`FolderMapping`].
+//!
+//! When a state file is given, migrated messages are recorded into
+//! it so that the migration can be run again incrementally: messages
+//! already migrated are skipped, even if they have been removed from
+//! the target backend in the meantime.
+
+pub mod mapping;
+pub mod state;
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
+    fmt,
+    future::Future,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+};
+
+use tracing::{debug, trace};
+
+#[doc(inline)]
+pub use self::{
+    mapping::{FolderMapping, FolderMappingRule},
+    state::MigrateState,
+};
+use super::{Error, Result};
+use crate::{
+    backend::{
+        context::{BackendContext, BackendContextBuilder},
+        Backend, BackendBuilder,
+    },
+    envelope::{
+        list::{ListEnvelopes, ListEnvelopesOptions},
+        Envelope, Id,
+    },
+    flag::set::SetFlags,
+    folder::{
+        add::AddFolder,
+        list::ListFolders,
+        sync::{config::FolderSyncStrategy, hunk::FolderName},
+    },
+    message::{add::AddMessage, peek::PeekMessages},
+};
+
+/// The migration builder.
+#[derive(Clone)]
+pub struct MigrateBuilder<S: BackendContextBuilder, T: BackendContextBuilder> {
+    source_builder: BackendBuilder<S>,
+    target_builder: BackendBuilder<T>,
+    folder_mapping: FolderMapping,
+    folder_filters: FolderSyncStrategy,
+    state_file: Option<PathBuf>,
+    handler: Option<Arc<MigrateEventHandler>>,
+    dry_run: bool,
+}
+
+impl<S, T> MigrateBuilder<S, T>
+where
+    S: BackendContextBuilder + 'static,
+    T: BackendContextBuilder + 'static,
+{
+    /// Create a new migration builder using the two given backend
+    /// builders.
+    pub fn new(source_builder: BackendBuilder<S>, target_builder: BackendBuilder<T>) -> Self {
+        Self {
+            source_builder,
+            target_builder,
+            folder_mapping: Default::default(),
+            folder_filters: Default::default(),
+            state_file: None,
+            handler: None,
+            dry_run: false,
+        }
+    }
+
+    // folder mapping setters
+
+    pub fn set_folder_mapping(&mut self, mapping: impl Into<FolderMapping>) {
+        self.folder_mapping = mapping.into();
+    }
+
+    pub fn with_folder_mapping(mut self, mapping: impl Into<FolderMapping>) -> Self {
+        self.set_folder_mapping(mapping);
+        self
+    }
+
+    pub fn add_folder_mapping_rule(&mut self, from: impl ToString, to: impl ToString) {
+        self.folder_mapping.add_rule(from, to);
+    }
+
+    pub fn with_folder_mapping_rule(mut self, from: impl ToString, to: impl ToString) -> Self {
+        self.add_folder_mapping_rule(from, to);
+        self
+    }
+
+    // folder filters setters
+
+    pub fn set_some_folder_filters(&mut self, f: Option<impl Into<FolderSyncStrategy>>) {
+        self.folder_filters = f.map(Into::into).unwrap_or_default();
+    }
+
+    pub fn set_folder_filters(&mut self, f: impl Into<FolderSyncStrategy>) {
+        self.set_some_folder_filters(Some(f));
+    }
+
+    pub fn with_some_folder_filters(mut self, f: Option<impl Into<FolderSyncStrategy>>) -> Self {
+        self.set_some_folder_filters(f);
+        self
+    }
+
+    pub fn with_folder_filters(mut self, f: impl Into<FolderSyncStrategy>) -> Self {
+        self.set_folder_filters(f);
+        self
+    }
+
+    // state file setters
+
+    pub fn set_some_state_file(&mut self, path: Option<impl Into<PathBuf>>) {
+        self.state_file = path.map(Into::into);
+    }
+
+    pub fn set_state_file(&mut self, path: impl Into<PathBuf>) {
+        self.set_some_state_file(Some(path));
+    }
+
+    pub fn with_some_state_file(mut self, path: Option<impl Into<PathBuf>>) -> Self {
+        self.set_some_state_file(path);
+        self
+    }
+
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.set_state_file(path);
+        self
+    }
+
+    // handler setters
+
+    pub fn set_some_handler<F: Future<Output = Result<()>> + Send + 'static>(
+        &mut self,
+        handler: Option<impl Fn(MigrateEvent) -> F + Send + Sync + 'static>,
+    ) {
+        self.handler = match handler {
+            Some(handler) => Some(Arc::new(move |evt| Box::pin(handler(evt)))),
+            None => None,
+        };
+    }
+
+    pub fn set_handler<F: Future<Output = Result<()>> + Send + 'static>(
+        &mut self,
+        handler: impl Fn(MigrateEvent) -> F + Send + Sync + 'static,
+    ) {
+        self.set_some_handler(Some(handler));
+    }
+
+    pub fn with_some_handler<F: Future<Output = Result<()>> + Send + 'static>(
+        mut self,
+        handler: Option<impl Fn(MigrateEvent) -> F + Send + Sync + 'static>,
+    ) -> Self {
+        self.set_some_handler(handler);
+        self
+    }
+
+    pub fn with_handler<F: Future<Output = Result<()>> + Send + 'static>(
+        mut self,
+        handler: impl Fn(MigrateEvent) -> F + Send + Sync + 'static,
+    ) -> Self {
+        self.set_handler(handler);
+        self
+    }
+
+    // dry run setters
+
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.set_dry_run(dry_run);
+        self
+    }
+
+    /// Migrate folders and emails from the source backend to the
+    /// target backend.
+    pub async fn migrate(self) -> Result<MigrateReport> {
+        let mut report = MigrateReport::default();
+
+        let mut state = match &self.state_file {
+            Some(path) if !self.dry_run => MigrateState::read(path)?,
+            _ => MigrateState::default(),
+        };
+
+        let mut target_builder = self.target_builder;
+        if target_builder.ctx_builder.check_configuration().is_err() && !self.dry_run {
+            target_builder
+                .ctx_builder
+                .configure()
+                .await
+                .map_err(Error::ConfigureMigrateTargetError)?;
+        }
+
+        let (source, target) =
+            tokio::try_join!(self.source_builder.build(), target_builder.build())
+                .map_err(Error::BuildMigrateBackendsError)?;
+
+        let source_folders = if self.folder_filters.is_subscribed() {
+            source.list_folders_with_subscriptions().await
+        } else {
+            source.list_folders().await
+        };
+
+        let source_folders: Vec<FolderName> = source_folders
+            .map_err(Error::ListMigrateSourceFoldersError)?
+            .iter()
+            .filter(|folder| folder.is_selectable())
+            .filter(|folder| {
+                if self.folder_filters.is_subscribed() {
+                    folder.is_subscribed()
+                } else {
+                    self.folder_filters.matches(&folder.name)
+                }
+            })
+            .map(|folder| folder.name.clone())
+            .collect();
+
+        MigrateEvent::ListedSourceFolders(source_folders.len())
+            .emit(&self.handler)
+            .await;
+
+        let mut target_folders: BTreeSet<FolderName> = target
+            .list_folders()
+            .await
+            .map_err(Error::ListMigrateTargetFoldersError)?
+            .iter()
+            .map(|folder| folder.name.clone())
+            .collect();
+
+        for source_folder in source_folders {
+            let target_folder = self.folder_mapping.map(&source_folder);
+
+            if !target_folders.contains(&target_folder) {
+                if !self.dry_run {
+                    if let Err(err) = target.add_folder(&target_folder).await {
+                        let err = Error::CreateMigrateFolderError(err, target_folder);
+                        debug!("{err}");
+                        report.errors.push(err);
+                        continue;
+                    }
+                }
+
+                MigrateEvent::CreatedFolder(target_folder.clone())
+                    .emit(&self.handler)
+                    .await;
+
+                target_folders.insert(target_folder.clone());
+            }
+
+            let folder_report = migrate_folder(
+                &source,
+                &source_folder,
+                &target,
+                &target_folder,
+                &mut state,
+                &self.handler,
+                self.dry_run,
+            )
+            .await;
+
+            match folder_report {
+                Ok(folder_report) => {
+                    report.copied += folder_report.copied;
+                    report.updated += folder_report.updated;
+                    report.skipped += folder_report.skipped;
+                    report.errors.extend(folder_report.errors);
+                }
+                Err(err) => {
+                    debug!("{err}");
+                    report.errors.push(err);
+                }
+            }
+
+            MigrateEvent::MigratedFolder(source_folder, target_folder.clone())
+                .emit(&self.handler)
+                .await;
+
+            report.folders.insert(target_folder);
+        }
+
+        Ok(report)
+    }
+}
+
+async fn migrate_folder<S, T>(
+    source: &Backend<S>,
+    source_folder: &str,
+    target: &Backend<T>,
+    target_folder: &str,
+    state: &mut MigrateState,
+    handler: &Option<Arc<MigrateEventHandler>>,
+    dry_run: bool,
+) -> Result<MigrateReport>
+where
+    S: BackendContext,
+    T: BackendContext,
+{
+    let mut report = MigrateReport::default();
+
+    let opts = ListEnvelopesOptions {
+        page: 0,
+        page_size: 0,
+        query: None,
+    };
+
+    let source_envelopes = source
+        .list_envelopes(source_folder, opts.clone())
+        .await
+        .map_err(|err| Error::ListMigrateSourceEnvelopesError(err, source_folder.to_owned()))?;
+
+    // NOTE: the target folder may not exist yet in dry run mode
+    let target_envelopes: HashMap<String, Envelope> =
+        match target.list_envelopes(target_folder, opts).await {
+            Ok(envelopes) => envelopes
+                .into_iter()
+                .map(|envelope| (migrate_key(&envelope), envelope))
+                .collect(),
+            Err(_) if dry_run => Default::default(),
+            Err(err) => {
+                let err = Error::ListMigrateTargetEnvelopesError(err, target_folder.to_owned());
+                return Err(err);
+            }
+        };
+
+    MigrateEvent::ListedEnvelopes(source_folder.to_owned(), source_envelopes.len())
+        .emit(handler)
+        .await;
+
+    for envelope in source_envelopes.iter() {
+        let message_id = &migrate_key(envelope);
+
+        // one-way flags update for messages already migrated
+        if let Some(target_envelope) = target_envelopes.get(message_id) {
+            if target_envelope.flags != envelope.flags {
+                if !dry_run {
+                    let id = Id::single(&target_envelope.id);
+                    if let Err(err) = target.set_flags(target_folder, &id, &envelope.flags).await {
+                        let err = Error::UpdateMigrateFlagsError(
+                            err,
+                            target_folder.to_owned(),
+                            message_id.clone(),
+                        );
+                        debug!("{err}");
+                        report.errors.push(err);
+                        continue;
+                    }
+                }
+
+                MigrateEvent::UpdatedFlags(target_folder.to_owned(), message_id.clone())
+                    .emit(handler)
+                    .await;
+
+                report.updated += 1;
+            } else {
+                report.skipped += 1;
+            }
+
+            if !dry_run {
+                state.mark(target_folder, message_id)?;
+            }
+
+            continue;
+        }
+
+        if state.contains(target_folder, message_id) {
+            trace!("message {message_id} already migrated to {target_folder}, skipping it");
+            report.skipped += 1;
+            continue;
+        }
+
+        if !dry_run {
+            let id = Id::single(&envelope.id);
+            let date = envelope.internal_date.unwrap_or(envelope.date);
+
+            let copy = async {
+                let msgs = source.peek_messages(source_folder, &id).await?;
+                let msg = msgs
+                    .first()
+                    .ok_or_else(|| crate::Error::FindMessageError(envelope.id.clone()))?;
+                target
+                    .add_message_with_flags_and_date(
+                        target_folder,
+                        msg.raw()?,
+                        &envelope.flags,
+                        &date,
+                    )
+                    .await
+            };
+
+            if let Err(err) = copy.await {
+                let err =
+                    Error::CopyMigrateEmailError(err, target_folder.to_owned(), message_id.clone());
+                debug!("{err}");
+                report.errors.push(err);
+                continue;
+            }
+
+            state.mark(target_folder, message_id)?;
+        }
+
+        MigrateEvent::CopiedEmail(target_folder.to_owned(), message_id.clone(), envelope.size)
+            .emit(handler)
+            .await;
+
+        report.copied += 1;
+    }
+
+    Ok(report)
+}
+
+/// Return the key identifying the given envelope across backends.
+///
+/// The key is the Message-ID of the envelope. Messages without
+/// Message-ID header get a Message-ID generated from their date
+/// only, so messages sharing the same date would collide: their key
+/// hashes the sender, the recipient and the subject as well.
+fn migrate_key(envelope: &Envelope) -> String {
+    if !envelope.message_id.ends_with("@generated>") {
+        return envelope.message_id.clone();
+    }
+
+    let mut hasher = DefaultHasher::new();
+    envelope.date.to_string().hash(&mut hasher);
+    envelope.from.addr.hash(&mut hasher);
+    envelope.to.addr.hash(&mut hasher);
+    envelope.subject.hash(&mut hasher);
+    format!("<{:x}@migrate.generated>", hasher.finish())
+}
+
+/// The migration report.
+#[derive(Debug, Default)]
+pub struct MigrateReport {
+    /// The target folders that have been migrated.
+    pub folders: BTreeSet<FolderName>,
+
+    /// The number of emails copied to the target backend.
+    pub copied: usize,
+
+    /// The number of emails which flags have been updated on the
+    /// target backend.
+    pub updated: usize,
+
+    /// The number of emails already migrated.
+    pub skipped: usize,
+
+    /// The errors that occurred during the migration.
+    ///
+    /// An error does not stop the migration, the failing folder or
+    /// email is just skipped.
+    pub errors: Vec<Error>,
+}
+
+/// The migration event handler.
+pub type MigrateEventHandler =
+    dyn Fn(MigrateEvent) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync;
+
+/// The migration event.
+///
+/// Represents all the events that can be triggered during the
+/// migration process.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MigrateEvent {
+    ListedSourceFolders(usize),
+    CreatedFolder(FolderName),
+    ListedEnvelopes(FolderName, usize),
+    CopiedEmail(FolderName, String, usize),
+    UpdatedFlags(FolderName, String),
+    MigratedFolder(FolderName, FolderName),
+}
+
+impl MigrateEvent {
+    pub async fn emit(&self, handler: &Option<Arc<MigrateEventHandler>>) {
+        if let Some(handler) = handler.as_ref() {
+            if let Err(err) = handler(self.clone()).await {
+                debug!(?err, "error while emitting migrate event");
+            } else {
+                debug!("emitted migrate event {self:?}");
+            }
+        }
+    }
+}
+
+impl fmt::Display for MigrateEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrateEvent::ListedSourceFolders(n) => {
+                write!(f, "Listed {n} source folders")
+            }
+            MigrateEvent::CreatedFolder(folder) => {
+                write!(f, "Created target folder {folder}")
+            }
+            MigrateEvent::ListedEnvelopes(folder, n) => {
+                write!(f, "Listed {n} source envelopes from {folder}")
+            }
+            MigrateEvent::CopiedEmail(folder, id, size) => {
+                write!(f, "Copied email {id} ({size} bytes) to {folder}")
+            }
+            MigrateEvent::UpdatedFlags(folder, id) => {
+                write!(f, "Updated flags of email {id} ({folder})")
+            }
+            MigrateEvent::MigratedFolder(source, target) => {
+                write!(f, "Migrated folder {source} to {target}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::migrate_key;
+    use crate::{envelope::Envelope, message::Message};
+
+    fn envelope(headers: &str) -> Envelope {
+        let msg = format!("{headers}\r\n\r\nHello!\r\n");
+        Envelope::from_msg("1", Default::default(), Message::from(msg.into_bytes()))
+    }
+
+    #[test]
+    fn migrate_key_of_generated_message_ids() {
+        let date = "Date: Mon, 1 Jan 2024 00:00:00 +0000";
+        let a = envelope(&format!("{date}\r\nFrom: a@localhost\r\nSubject: A"));
+        let b = envelope(&format!("{date}\r\nFrom: a@localhost\r\nSubject: B"));
+        let c = envelope(&format!("{date}\r\nFrom: c@localhost\r\nSubject: A"));
+
+        // generated Message-IDs only depend on the date
+        assert!(a.message_id.ends_with("@generated>"));
+        assert_eq!(a.message_id, b.message_id);
+
+        assert_ne!(migrate_key(&a), migrate_key(&b));
+        assert_ne!(migrate_key(&a), migrate_key(&c));
+
+        let a2 = envelope(&format!("{date}\r\nFrom: a@localhost\r\nSubject: A"));
+        assert_eq!(migrate_key(&a), migrate_key(&a2));
+    }
+
+    #[test]
+    fn migrate_key_of_message_ids() {
+        let a = envelope("Message-ID: <a@localhost>\r\nSubject: A");
+        assert_eq!(migrate_key(&a), "<a@localhost>");
+    }
+}
@@ -0,0 +1,111 @@
+//! # Migration state
+//!
+//! Module dedicated to the migration state. The state keeps track of
+//! messages already migrated, so that a migration can be run again
+//! incrementally: only new messages are copied.
+//!
+//! The state file is a plain text file containing one migrated
+//! message per line, composed of the target folder name and the
+//! Message-ID header separated by a tab.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use super::super::{Error, Result};
+
+/// The migration state.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MigrateState {
+    path: Option<PathBuf>,
+    migrated: HashMap<String, HashSet<String>>,
+}
+
+impl MigrateState {
+    /// Read the migration state from the given file.
+    ///
+    /// A missing file is considered as an empty state.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(Error::ReadMigrateStateError(err, path.to_owned())),
+        };
+
+        let mut state = Self::parse(&content);
+        state.path = Some(path.to_owned());
+        Ok(state)
+    }
+
+    /// Parse the migration state from the given string.
+    ///
+    /// Malformed lines are ignored.
+    pub fn parse(content: &str) -> Self {
+        let mut state = Self::default();
+
+        for line in content.lines() {
+            if let Some((folder, message_id)) = line.split_once('\t') {
+                state.insert(folder, message_id);
+            }
+        }
+
+        state
+    }
+
+    /// Return `true` if the given message has already been migrated
+    /// to the given folder.
+    pub fn contains(&self, folder: &str, message_id: &str) -> bool {
+        self.migrated
+            .get(folder)
+            .map(|ids| ids.contains(message_id))
+            .unwrap_or_default()
+    }
+
+    fn insert(&mut self, folder: &str, message_id: &str) -> bool {
+        self.migrated
+            .entry(folder.to_owned())
+            .or_default()
+            .insert(message_id.to_owned())
+    }
+
+    /// Mark the given message as migrated to the given folder.
+    ///
+    /// The state file, if any, is updated right away so that an
+    /// interrupted migration can be resumed.
+    pub fn mark(&mut self, folder: &str, message_id: &str) -> Result<()> {
+        if !self.insert(folder, message_id) {
+            return Ok(());
+        }
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| writeln!(file, "{folder}\t{message_id}"))
+            .map_err(|err| Error::WriteMigrateStateError(err, path.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MigrateState;
+
+    #[test]
+    fn parse() {
+        let state = MigrateState::parse("INBOX\t<a@localhost>\nmalformed\nSent\t<b@localhost>\n");
+
+        assert!(state.contains("INBOX", "<a@localhost>"));
+        assert!(state.contains("Sent", "<b@localhost>"));
+        assert!(!state.contains("INBOX", "<b@localhost>"));
+        assert!(!state.contains("malformed", ""));
+    }
+}
@@ -6,7 +6,9 @@
 
 mod error;
 pub mod hash;
+pub mod migrate;
 pub mod pool;
+pub mod progress;
 pub mod report;
 
 use std::{
@@ -42,7 +44,7 @@
         },
     },
     maildir::{config::MaildirConfig, MaildirContextBuilder},
-    message::sync::config::MessageSyncPermissions,
+    message::sync::config::{MessageSyncPermissions, MessageSyncStrategy},
     sync::pool::{SyncPoolConfig, SyncPoolContextBuilder},
 };
 
@@ -362,6 +364,52 @@ pub fn with_right_message_permissions(mut self, p: impl Into<MessageSyncPermissi
         self
     }
 
+    // left message strategy setters
+
+    pub fn set_some_left_message_strategy(&mut self, s: Option<impl Into<MessageSyncStrategy>>) {
+        self.config.left_message_strategy = s.map(Into::into);
+    }
+
+    pub fn set_left_message_strategy(&mut self, s: impl Into<MessageSyncStrategy>) {
+        self.set_some_left_message_strategy(Some(s));
+    }
+
+    pub fn with_some_left_message_strategy(
+        mut self,
+        s: Option<impl Into<MessageSyncStrategy>>,
+    ) -> Self {
+        self.set_some_left_message_strategy(s);
+        self
+    }
+
+    pub fn with_left_message_strategy(mut self, s: impl Into<MessageSyncStrategy>) -> Self {
+        self.set_left_message_strategy(s);
+        self
+    }
+
+    // right message strategy setters
+
+    pub fn set_some_right_message_strategy(&mut self, s: Option<impl Into<MessageSyncStrategy>>) {
+        self.config.right_message_strategy = s.map(Into::into);
+    }
+
+    pub fn set_right_message_strategy(&mut self, s: impl Into<MessageSyncStrategy>) {
+        self.set_some_right_message_strategy(Some(s));
+    }
+
+    pub fn with_some_right_message_strategy(
+        mut self,
+        s: Option<impl Into<MessageSyncStrategy>>,
+    ) -> Self {
+        self.set_some_right_message_strategy(s);
+        self
+    }
+
+    pub fn with_right_message_strategy(mut self, s: impl Into<MessageSyncStrategy>) -> Self {
+        self.set_right_message_strategy(s);
+        self
+    }
+
     // getters
 
     pub fn find_default_cache_dir(&self) -> Option<PathBuf> {
@@ -554,6 +602,7 @@ pub enum SyncEvent {
     ListedRightCachedEnvelopes(FolderName, usize),
     ListedRightEnvelopes(FolderName, usize),
     GeneratedEmailPatch(BTreeMap<FolderName, BTreeSet<EmailSyncHunk>>),
+    TransferredEmail(FolderName, usize),
     ProcessedEmailHunk(EmailSyncHunk),
     ProcessedAllEmailHunks,
     ExpungedAllFolders,
@@ -617,6 +666,9 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 let np = patch.values().flatten().count();
                 write!(f, "Generated {np} patch for {nf} folders")
             }
+            SyncEvent::TransferredEmail(folder, n) => {
+                write!(f, "Transferred {n} bytes to {folder}")
+            }
             SyncEvent::ProcessedEmailHunk(hunk) => {
                 write!(f, "{hunk}")
             }
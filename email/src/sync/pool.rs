@@ -11,13 +11,14 @@
     email::sync::hunk::EmailSyncHunk,
     envelope::sync::config::EnvelopeSyncFilters,
     flag::sync::config::FlagSyncPermissions,
+    folder::list::ListFolders,
     folder::sync::{
         config::{FolderSyncPermissions, FolderSyncStrategy},
         hunk::FolderSyncHunk,
         patch::FolderSyncPatches,
     },
     maildir::{MaildirContextBuilder, MaildirContextSync},
-    message::sync::config::MessageSyncPermissions,
+    message::sync::config::{MessageSyncPermissions, MessageSyncStrategy},
     AnyResult,
 };
 
@@ -26,9 +27,11 @@ pub struct SyncPoolConfig {
     pub left_folder_permissions: Option<FolderSyncPermissions>,
     pub left_flag_permissions: Option<FlagSyncPermissions>,
     pub left_message_permissions: Option<MessageSyncPermissions>,
+    pub left_message_strategy: Option<MessageSyncStrategy>,
     pub right_folder_permissions: Option<FolderSyncPermissions>,
     pub right_flag_permissions: Option<FlagSyncPermissions>,
     pub right_message_permissions: Option<MessageSyncPermissions>,
+    pub right_message_strategy: Option<MessageSyncStrategy>,
     pub pool_size: Option<usize>,
     pub folder_filters: Option<FolderSyncStrategy>,
     pub envelope_filters: Option<EnvelopeSyncFilters>,
@@ -113,6 +116,20 @@ pub async fn build(self) -> AnyResult<SyncPoolContext<L::Context, R::Context>> {
             })
             .unwrap_or_default();
 
+        let left_message_strategy = self
+            .config
+            .left_message_strategy
+            .clone()
+            .or_else(|| {
+                self.left_builder
+                    .account_config
+                    .message
+                    .as_ref()
+                    .and_then(|c| c.sync.as_ref())
+                    .map(|c| c.strategy.clone())
+            })
+            .unwrap_or_default();
+
         let right_folder_permissions = self
             .config
             .right_folder_permissions
@@ -155,6 +172,20 @@ pub async fn build(self) -> AnyResult<SyncPoolContext<L::Context, R::Context>> {
             })
             .unwrap_or_default();
 
+        let right_message_strategy = self
+            .config
+            .right_message_strategy
+            .clone()
+            .or_else(|| {
+                self.right_builder
+                    .account_config
+                    .message
+                    .as_ref()
+                    .and_then(|c| c.sync.as_ref())
+                    .map(|c| c.strategy.clone())
+            })
+            .unwrap_or_default();
+
         let folder_filters = self
             .config
             .folder_filters
@@ -190,17 +221,33 @@ pub async fn build(self) -> AnyResult<SyncPoolContext<L::Context, R::Context>> {
             self.right_builder.build(),
         )?;
 
+        // subscribed folders can only be known once backends are
+        // built, so the strategy is resolved here
+        let folder_filters = if folder_filters.is_subscribed() {
+            let (left_folders, right_folders) = tokio::try_join!(
+                left.list_folders_with_subscriptions(),
+                right.list_folders_with_subscriptions()
+            )?;
+            FolderSyncStrategy::from_subscribed_folders(
+                left_folders.iter().chain(right_folders.iter()),
+            )
+        } else {
+            folder_filters
+        };
+
         Ok(SyncPoolContext {
             left_cache,
             left,
             left_folder_permissions,
             left_flag_permissions,
             left_message_permissions,
+            left_message_strategy,
             right_cache,
             right,
             right_folder_permissions,
             right_flag_permissions,
             right_message_permissions,
+            right_message_strategy,
             folder_filters,
             envelope_filters,
             handler: self.config.handler,
@@ -217,9 +264,11 @@ pub struct SyncPoolContext<L: BackendContext, R: BackendContext> {
     pub left_folder_permissions: FolderSyncPermissions,
     pub left_flag_permissions: FlagSyncPermissions,
     pub left_message_permissions: MessageSyncPermissions,
+    pub left_message_strategy: MessageSyncStrategy,
     pub right_folder_permissions: FolderSyncPermissions,
     pub right_flag_permissions: FlagSyncPermissions,
     pub right_message_permissions: MessageSyncPermissions,
+    pub right_message_strategy: MessageSyncStrategy,
     pub folder_filters: FolderSyncStrategy,
     pub envelope_filters: EnvelopeSyncFilters,
     pub handler: Option<Arc<SyncEventHandler>>,
@@ -237,6 +286,12 @@ pub fn apply_folder_permissions(&self, patch: &mut FolderSyncPatches) {
                 Create(_, Right) | Cache(_, Right) => self.right_folder_permissions.create,
                 Delete(_, Left) | Uncache(_, Left) => self.left_folder_permissions.delete,
                 Delete(_, Right) | Uncache(_, Right) => self.right_folder_permissions.delete,
+                Rename(_, _, Left) => {
+                    self.left_folder_permissions.create && self.left_folder_permissions.delete
+                }
+                Rename(_, _, Right) => {
+                    self.right_folder_permissions.create && self.right_folder_permissions.delete
+                }
             });
         }
     }
@@ -0,0 +1,337 @@
+//! # Synchronization progress
+//!
+//! Module dedicated to synchronization progress. The main structure
+//! of this module is [`SyncProgress`], which aggregates
+//! [`SyncEvent`]s in order to compute the progression, the
+//! throughput and the estimated remaining time of the email
+//! synchronization, per folder and overall.
+//!
+//! ```rust,ignore
+//! let progress = SyncProgress::new();
+//!
+//! let sync_builder = SyncBuilder::new(left_builder, right_builder).with_handler({
+//!     let progress = progress.clone();
+//!     move |evt| {
+//!         progress.handle(&evt);
+//!         let stats = progress.overall();
+//!         println!("{}/{} bytes, eta: {:?}", stats.processed_bytes, stats.total_bytes, stats.eta());
+//!         async { Ok(()) }
+//!     }
+//! });
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::SyncEvent;
+use crate::folder::sync::hunk::FolderName;
+
+/// The synchronization progress statistics.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SyncProgressStats {
+    /// The total number of email hunks to process.
+    pub total_hunks: usize,
+
+    /// The number of email hunks already processed.
+    pub processed_hunks: usize,
+
+    /// The total number of bytes to transfer, based on envelopes
+    /// size.
+    pub total_bytes: usize,
+
+    /// The number of bytes already processed, based on envelopes
+    /// size.
+    pub processed_bytes: usize,
+
+    /// The number of bytes actually transferred.
+    ///
+    /// This number can be lower than the processed bytes when
+    /// messages are synchronized as placeholders.
+    pub transferred_bytes: usize,
+
+    /// The time elapsed since the beginning of the email
+    /// synchronization, or until its end if it is finished.
+    pub elapsed: Duration,
+}
+
+impl SyncProgressStats {
+    /// Return `true` if all hunks have been processed.
+    pub fn is_finished(&self) -> bool {
+        self.processed_hunks >= self.total_hunks
+    }
+
+    /// Return the progression, between 0 and 1.
+    ///
+    /// The progression is based on bytes when sizes are known, on
+    /// hunks otherwise.
+    pub fn ratio(&self) -> f64 {
+        match self.progression() {
+            (_, 0) => 1.0,
+            (done, total) => done as f64 / total as f64,
+        }
+    }
+
+    /// Return the throughput, in bytes per second.
+    pub fn throughput(&self) -> Option<f64> {
+        let secs = self.elapsed.as_secs_f64();
+
+        if secs > 0.0 {
+            Some(self.processed_bytes as f64 / secs)
+        } else {
+            None
+        }
+    }
+
+    /// Return the estimated remaining time.
+    ///
+    /// Returns `None` if nothing has been processed yet.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.is_finished() {
+            return Some(Duration::ZERO);
+        }
+
+        let (done, total) = self.progression();
+
+        if done == 0 {
+            return None;
+        }
+
+        let remaining = total.saturating_sub(done) as u128;
+        let nanos = self.elapsed.as_nanos() * remaining / done as u128;
+        Some(Duration::from_nanos(nanos as u64))
+    }
+
+    /// Return the processed and the total amount of work, in bytes
+    /// when sizes are known, in hunks otherwise.
+    fn progression(&self) -> (usize, usize) {
+        if self.total_bytes > 0 {
+            (self.processed_bytes, self.total_bytes)
+        } else {
+            (self.processed_hunks, self.total_hunks)
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.total_hunks += other.total_hunks;
+        self.processed_hunks += other.processed_hunks;
+        self.total_bytes += other.total_bytes;
+        self.processed_bytes += other.processed_bytes;
+        self.transferred_bytes += other.transferred_bytes;
+        self.elapsed = self.elapsed.max(other.elapsed);
+    }
+}
+
+#[derive(Debug, Default)]
+struct FolderSyncProgress {
+    stats: SyncProgressStats,
+    finished_at: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct SyncProgressState {
+    started_at: Option<Instant>,
+    folders: BTreeMap<FolderName, FolderSyncProgress>,
+}
+
+/// The synchronization progress aggregator.
+///
+/// The aggregator is cheap to clone, so that one clone can be moved
+/// into the [`SyncBuilder`](super::SyncBuilder) handler while
+/// another one is used to read the progress.
+#[derive(Clone, Debug, Default)]
+pub struct SyncProgress {
+    state: Arc<Mutex<SyncProgressState>>,
+}
+
+impl SyncProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the progress using the given synchronization event.
+    pub fn handle(&self, evt: &SyncEvent) {
+        self.handle_at(evt, Instant::now())
+    }
+
+    /// Update the progress using the given synchronization event,
+    /// received at the given instant.
+    pub fn handle_at(&self, evt: &SyncEvent, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+
+        match evt {
+            SyncEvent::GeneratedEmailPatch(patch) => {
+                state.started_at = Some(now);
+                state.folders = patch
+                    .iter()
+                    .map(|(folder, hunks)| {
+                        let mut progress = FolderSyncProgress::default();
+                        progress.stats.total_hunks = hunks.len();
+                        progress.stats.total_bytes = hunks.iter().map(|h| h.size()).sum();
+                        if hunks.is_empty() {
+                            progress.finished_at = Some(now);
+                        }
+                        (folder.clone(), progress)
+                    })
+                    .collect();
+            }
+            SyncEvent::TransferredEmail(folder, bytes) => {
+                if let Some(progress) = state.folders.get_mut(folder) {
+                    progress.stats.transferred_bytes += bytes;
+                }
+            }
+            SyncEvent::ProcessedEmailHunk(hunk) => {
+                if let Some(progress) = state.folders.get_mut(hunk.folder()) {
+                    progress.stats.processed_hunks += 1;
+                    progress.stats.processed_bytes += hunk.size();
+                    if progress.stats.is_finished() {
+                        progress.finished_at = Some(now);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Return the progress statistics of the given folder.
+    pub fn folder(&self, folder: impl AsRef<str>) -> Option<SyncProgressStats> {
+        self.folder_at(folder, Instant::now())
+    }
+
+    /// Return the progress statistics of the given folder, at the
+    /// given instant.
+    pub fn folder_at(&self, folder: impl AsRef<str>, now: Instant) -> Option<SyncProgressStats> {
+        let state = self.state.lock().unwrap();
+        let progress = state.folders.get(folder.as_ref())?;
+        Some(Self::stats(&state, progress, now))
+    }
+
+    /// Return the progress statistics of all folders.
+    pub fn folders(&self) -> BTreeMap<FolderName, SyncProgressStats> {
+        self.folders_at(Instant::now())
+    }
+
+    /// Return the progress statistics of all folders, at the given
+    /// instant.
+    pub fn folders_at(&self, now: Instant) -> BTreeMap<FolderName, SyncProgressStats> {
+        let state = self.state.lock().unwrap();
+        state
+            .folders
+            .iter()
+            .map(|(folder, progress)| (folder.clone(), Self::stats(&state, progress, now)))
+            .collect()
+    }
+
+    /// Return the overall progress statistics.
+    pub fn overall(&self) -> SyncProgressStats {
+        self.overall_at(Instant::now())
+    }
+
+    /// Return the overall progress statistics, at the given instant.
+    pub fn overall_at(&self, now: Instant) -> SyncProgressStats {
+        let mut overall = SyncProgressStats::default();
+
+        for stats in self.folders_at(now).values() {
+            overall.add(stats);
+        }
+
+        overall
+    }
+
+    fn stats(
+        state: &SyncProgressState,
+        progress: &FolderSyncProgress,
+        now: Instant,
+    ) -> SyncProgressStats {
+        let mut stats = progress.stats.clone();
+
+        if let Some(started_at) = state.started_at {
+            let ended_at = progress.finished_at.unwrap_or(now);
+            stats.elapsed = ended_at.saturating_duration_since(started_at);
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        time::{Duration, Instant},
+    };
+
+    use super::SyncProgress;
+    use crate::{
+        email::sync::hunk::EmailSyncHunk,
+        envelope::Envelope,
+        sync::{SyncDestination, SyncEvent},
+    };
+
+    fn copy_hunk(folder: &str, id: &str, size: usize) -> EmailSyncHunk {
+        EmailSyncHunk::CopyThenCache(
+            folder.into(),
+            Envelope {
+                id: id.into(),
+                message_id: format!("<{id}@localhost>"),
+                size,
+                ..Default::default()
+            },
+            SyncDestination::Right,
+            SyncDestination::Left,
+            true,
+        )
+    }
+
+    #[test]
+    fn progress() {
+        let a = copy_hunk("INBOX", "a", 100);
+        let b = copy_hunk("INBOX", "b", 300);
+        let c = copy_hunk("Sent", "c", 600);
+
+        let start = Instant::now();
+        let progress = SyncProgress::new();
+
+        progress.handle_at(
+            &SyncEvent::GeneratedEmailPatch(BTreeMap::from_iter([
+                ("INBOX".into(), BTreeSet::from_iter([a.clone(), b.clone()])),
+                ("Sent".into(), BTreeSet::from_iter([c.clone()])),
+            ])),
+            start,
+        );
+
+        let overall = progress.overall_at(start);
+        assert_eq!(overall.total_hunks, 3);
+        assert_eq!(overall.total_bytes, 1000);
+        assert_eq!(overall.eta(), None);
+
+        let now = start + Duration::from_secs(1);
+        progress.handle_at(&SyncEvent::TransferredEmail("INBOX".into(), 100), now);
+        progress.handle_at(&SyncEvent::ProcessedEmailHunk(a), now);
+
+        let inbox = progress.folder_at("INBOX", now).unwrap();
+        assert_eq!(inbox.processed_bytes, 100);
+        assert_eq!(inbox.transferred_bytes, 100);
+        assert_eq!(inbox.throughput(), Some(100.0));
+        assert_eq!(inbox.eta(), Some(Duration::from_secs(3)));
+
+        let overall = progress.overall_at(now);
+        assert_eq!(overall.eta(), Some(Duration::from_secs(9)));
+
+        let now = start + Duration::from_secs(4);
+        progress.handle_at(&SyncEvent::ProcessedEmailHunk(b), now);
+
+        let inbox = progress.folder_at("INBOX", now).unwrap();
+        assert!(inbox.is_finished());
+        assert_eq!(inbox.eta(), Some(Duration::ZERO));
+
+        let later = start + Duration::from_secs(10);
+        let inbox = progress.folder_at("INBOX", later).unwrap();
+        assert_eq!(inbox.elapsed, Duration::from_secs(4));
+
+        progress.handle_at(&SyncEvent::ProcessedEmailHunk(c), later);
+        assert!(progress.overall_at(later).is_finished());
+    }
+}
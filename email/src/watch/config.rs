@@ -1,8 +1,17 @@
-use std::{fmt, future::Future, ops::Deref, pin::Pin, sync::Arc};
+use std::{
+    collections::BTreeSet, fmt, future::Future, ops::Deref, pin::Pin, sync::Arc, time::Duration,
+};
 
 use process::Command;
 
-use crate::envelope::Envelope;
+use crate::{
+    account::config::AccountConfig,
+    envelope::{Envelope, Id},
+    flag::{add::AddFlags, Flag},
+    folder::ARCHIVE,
+    message::r#move::MoveMessages,
+    AnyResult,
+};
 
 /// Watch hook configuration.
 ///
@@ -17,8 +26,16 @@
 pub struct WatchHook {
     /// Execute the shell command.
     ///
-    /// For now, command is executed without any parameter nor
-    /// input. This may change in the future.
+    /// The command receives the envelope that triggered the hook:
+    ///  - as placeholders, see [`WatchNotifyConfig`] for the list of
+    ///    accepted placeholders
+    ///  - as `EMAIL_*` environment variables, for example `EMAIL_ID`
+    ///    or `EMAIL_SENDER_ADDRESS`
+    ///  - as JSON on the standard input
+    ///
+    /// Placeholders are replaced by shell-quoted values, so they
+    /// should not be surrounded by quotes: `notify-send {subject}`
+    /// rather than `notify-send "{subject}"`.
     pub cmd: Option<Command>,
 
     /// Send a system notification using the given
@@ -95,7 +112,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 ///
 /// The structure tries to match the [`notify_rust::Notification`] API
 /// and may evolve in the future.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(
     feature = "derive",
     derive(serde::Serialize, serde::Deserialize),
@@ -106,7 +123,11 @@ pub struct WatchNotifyConfig {
     ///
     /// Accepted placeholders:
     ///  - "{id}": the id of the envelope
+    ///  - "{folder}": the folder of the envelope, if known
+    ///  - "{message-id}": the Message-ID of the envelope
     ///  - "{subject}": the subject of the envelope
+    ///  - "{date}": the date of the envelope, in RFC 3339 format
+    ///  - "{flags}": the comma-separated flags of the envelope
     ///  - "{sender}" either the sender name or the address
     ///  - "{sender.name}" the sender name or "unknown"
     ///  - "{sender.address}" the sender address
@@ -119,7 +140,11 @@ pub struct WatchNotifyConfig {
     ///
     /// Accepted placeholders:
     ///  - "{id}": the id of the envelope
+    ///  - "{folder}": the folder of the envelope, if known
+    ///  - "{message-id}": the Message-ID of the envelope
     ///  - "{subject}": the subject of the envelope
+    ///  - "{date}": the date of the envelope, in RFC 3339 format
+    ///  - "{flags}": the comma-separated flags of the envelope
     ///  - "{sender}" either the sender name or the address
     ///  - "{sender.name}" the sender name or "unknown"
     ///  - "{sender.address}" the sender address
@@ -127,4 +152,314 @@ pub struct WatchNotifyConfig {
     ///  - "{recipient.name}" the recipient name or "unknown"
     ///  - "{recipient.address}" the recipient address
     pub body: String,
+
+    /// The folders notifications are enabled for.
+    ///
+    /// Defaults to all folders. Envelopes that do not belong to a
+    /// known folder are always notified.
+    #[cfg_attr(feature = "derive", serde(default))]
+    pub folders: WatchNotifyFolders,
+
+    /// The batch configuration.
+    ///
+    /// When defined, envelopes notified within the batch window are
+    /// grouped into a single digest notification, instead of one
+    /// notification per envelope.
+    pub batch: Option<WatchNotifyBatchConfig>,
+
+    /// The actions proposed by the notification.
+    ///
+    /// Actions are only proposed by single envelope notifications,
+    /// and are only supported on Linux. They are executed by the
+    /// [`action_handler`](Self::action_handler).
+    #[cfg_attr(feature = "derive", serde(default))]
+    pub actions: Vec<WatchNotifyAction>,
+
+    /// The handler executing the notification actions.
+    ///
+    /// The handler cannot be de/serialized. See
+    /// [`WatchNotifyActionFn::from_backend`] to create a handler
+    /// calling back into a backend.
+    #[cfg_attr(feature = "derive", serde(skip))]
+    pub action_handler: Option<WatchNotifyActionFn>,
+}
+
+impl Eq for WatchNotifyConfig {
+    //
+}
+
+impl PartialEq for WatchNotifyConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.summary == other.summary
+            && self.body == other.body
+            && self.folders == other.folders
+            && self.batch == other.batch
+            && self.actions == other.actions
+    }
+}
+
+impl WatchNotifyConfig {
+    /// Return `true` if notifications are enabled for the given
+    /// folder.
+    ///
+    /// Folders are compared after alias resolution, see
+    /// [`WatchNotifyFolders::matches`].
+    pub fn is_enabled_for(&self, config: &AccountConfig, folder: Option<&str>) -> bool {
+        match folder {
+            Some(folder) => self.folders.matches(config, folder),
+            None => true,
+        }
+    }
+
+    /// Find the batch window, if batching is enabled.
+    pub fn find_batch_window(&self) -> Option<Duration> {
+        self.batch
+            .as_ref()
+            .map(|c| c.window.unwrap_or(DEFAULT_NOTIFY_BATCH_WINDOW))
+            .filter(|window| *window > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+/// The default notification batch window, in seconds.
+pub const DEFAULT_NOTIFY_BATCH_WINDOW: u64 = 5;
+
+/// The folders notifications are enabled for.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum WatchNotifyFolders {
+    /// Notifications are enabled for all folders.
+    #[default]
+    All,
+
+    /// Notifications are enabled only for the given folders.
+    Include(BTreeSet<String>),
+
+    /// Notifications are enabled for all folders except the given
+    /// ones.
+    Exclude(BTreeSet<String>),
+}
+
+impl WatchNotifyFolders {
+    /// Return `true` if the given folder matches.
+    ///
+    /// Both the given folder and the configured ones are resolved
+    /// using the folder aliases of the given account configuration,
+    /// so that `sent` matches the folder it is an alias of.
+    pub fn matches(&self, config: &AccountConfig, folder: &str) -> bool {
+        let folder = config.get_folder_alias(folder);
+        let contains = |folders: &BTreeSet<String>| {
+            folders.iter().any(|f| config.get_folder_alias(f) == folder)
+        };
+
+        match self {
+            Self::All => true,
+            Self::Include(folders) => contains(folders),
+            Self::Exclude(folders) => !contains(folders),
+        }
+    }
+}
+
+/// The notification batch configuration.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct WatchNotifyBatchConfig {
+    /// The batch window, in seconds.
+    ///
+    /// Envelopes notified within this window after a first envelope
+    /// are grouped together. Defaults to 5 s.
+    pub window: Option<u64>,
+
+    /// The summary of the digest notification.
+    ///
+    /// Accepted placeholders:
+    ///  - "{count}": the number of envelopes
+    ///  - "{folders}": the comma-separated folders of the envelopes
+    ///  - "{senders}": the comma-separated senders of the envelopes
+    ///
+    /// Defaults to "{count} new emails".
+    pub summary: Option<String>,
+
+    /// The body of the digest notification.
+    ///
+    /// Accepts the same placeholders as the summary, plus "{entries}"
+    /// which is replaced by one [entry](Self::entry) per line.
+    ///
+    /// Defaults to "{entries}".
+    pub body: Option<String>,
+
+    /// The digest entry of one envelope.
+    ///
+    /// Accepts the same placeholders as [`WatchNotifyConfig::body`].
+    ///
+    /// Defaults to "{sender}: {subject}".
+    pub entry: Option<String>,
+}
+
+impl WatchNotifyBatchConfig {
+    pub fn find_summary(&self) -> &str {
+        self.summary.as_deref().unwrap_or("{count} new emails")
+    }
+
+    pub fn find_body(&self) -> &str {
+        self.body.as_deref().unwrap_or("{entries}")
+    }
+
+    pub fn find_entry(&self) -> &str {
+        self.entry.as_deref().unwrap_or("{sender}: {subject}")
+    }
+}
+
+/// The notification action.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "derive",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum WatchNotifyAction {
+    /// Add the seen flag to the envelope.
+    MarkAsRead,
+
+    /// Move the envelope to the archive folder.
+    Archive,
+}
+
+impl WatchNotifyAction {
+    /// Return the action identifier, as given to the notification
+    /// server.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::MarkAsRead => "mark-as-read",
+            Self::Archive => "archive",
+        }
+    }
+
+    /// Return the action label, as displayed by the notification.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::MarkAsRead => "Mark as read",
+            Self::Archive => "Archive",
+        }
+    }
+
+    /// Find the action matching the given identifier.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "mark-as-read" => Some(Self::MarkAsRead),
+            "archive" => Some(Self::Archive),
+            _ => None,
+        }
+    }
+}
+
+/// Watch notification action function.
+///
+/// This is just a wrapper around a function that takes the action
+/// to execute, the folder and the envelope the notification was
+/// shown for.
+#[derive(Clone)]
+pub struct WatchNotifyActionFn(
+    #[allow(clippy::type_complexity)]
+    Arc<
+        dyn Fn(
+                WatchNotifyAction,
+                String,
+                Envelope,
+            ) -> Pin<Box<dyn Future<Output = AnyResult<()>> + Send>>
+            + Send
+            + Sync,
+    >,
+);
+
+impl WatchNotifyActionFn {
+    /// Create a new watch notification action function.
+    pub fn new<F: Future<Output = AnyResult<()>> + Send + 'static>(
+        f: impl Fn(WatchNotifyAction, String, Envelope) -> F + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(move |action, folder, envelope| {
+            Box::pin(f(action, folder, envelope))
+        }))
+    }
+
+    /// Create a new watch notification action function calling back
+    /// into the given backend.
+    ///
+    /// Envelopes are archived into the folder matching the `archive`
+    /// alias of the given account configuration.
+    pub fn from_backend<B>(account_config: Arc<AccountConfig>, backend: Arc<B>) -> Self
+    where
+        B: AddFlags + MoveMessages + 'static,
+    {
+        Self::new(move |action, folder, envelope| {
+            let account_config = account_config.clone();
+            let backend = backend.clone();
+
+            async move {
+                let id = Id::single(envelope.id);
+
+                match action {
+                    WatchNotifyAction::MarkAsRead => {
+                        backend.add_flag(&folder, &id, Flag::Seen).await
+                    }
+                    WatchNotifyAction::Archive => {
+                        let archive = account_config.get_folder_alias(ARCHIVE);
+                        backend.move_messages(&folder, &archive, &id).await
+                    }
+                }
+            }
+        })
+    }
+
+    /// Execute the given action.
+    pub async fn exec(
+        &self,
+        action: WatchNotifyAction,
+        folder: String,
+        envelope: Envelope,
+    ) -> AnyResult<()> {
+        (self.0)(action, folder, envelope).await
+    }
+}
+
+impl fmt::Debug for WatchNotifyActionFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WatchNotifyActionFn()")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashMap};
+
+    use super::WatchNotifyFolders;
+    use crate::{account::config::AccountConfig, folder::config::FolderConfig};
+
+    #[test]
+    fn match_folders_by_alias() {
+        let config = AccountConfig {
+            folder: Some(FolderConfig {
+                aliases: Some(HashMap::from_iter([("sent".into(), "Sent Mail".into())])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let include = WatchNotifyFolders::Include(BTreeSet::from_iter(["sent".into()]));
+        assert!(include.matches(&config, "Sent Mail"));
+        assert!(include.matches(&config, "SENT"));
+        assert!(!include.matches(&config, "INBOX"));
+
+        let exclude = WatchNotifyFolders::Exclude(BTreeSet::from_iter(["Sent Mail".into()]));
+        assert!(!exclude.matches(&config, "sent"));
+        assert!(exclude.matches(&config, "INBOX"));
+    }
 }
@@ -0,0 +1,370 @@
+//! # Watch hook
+//!
+//! Module dedicated to the data given to watch hooks. The envelope
+//! (or the quota resource) that triggered a hook is exposed to shell
+//! commands as placeholders, as `EMAIL_*` (or `QUOTA_*`) environment
+//! variables and as JSON on the standard input, see
+//! [`WatchHookVars`].
+
+#[cfg(feature = "watch")]
+use crate::envelope::Address;
+use crate::{
+    envelope::Envelope,
+    quota::{QuotaResourceKind, QuotaUsage},
+};
+
+/// The envelope given to watch hooks.
+///
+/// The folder is optional, since hooks can be executed for
+/// envelopes that do not belong to a watched folder.
+#[derive(Clone, Debug)]
+pub struct WatchHookEnvelope<'a> {
+    folder: Option<&'a str>,
+    envelope: &'a Envelope,
+}
+
+impl<'a> WatchHookEnvelope<'a> {
+    pub fn new(envelope: &'a Envelope) -> Self {
+        Self {
+            folder: None,
+            envelope,
+        }
+    }
+
+    pub fn set_folder(&mut self, folder: &'a str) {
+        self.folder = Some(folder);
+    }
+
+    pub fn with_folder(mut self, folder: &'a str) -> Self {
+        self.set_folder(folder);
+        self
+    }
+
+    pub fn folder(&self) -> Option<&'a str> {
+        self.folder
+    }
+
+    pub fn envelope(&self) -> &Envelope {
+        self.envelope
+    }
+}
+
+/// Replace the placeholders of the given string by the given
+/// variables.
+///
+/// Placeholders are variable keys surrounded by braces. The string
+/// is scanned once, so placeholders contained in replaced values are
+/// kept as is. Unknown placeholders are kept as is as well.
+pub(crate) fn replace_vars(fmt: &str, vars: &[(&str, String)]) -> String {
+    let mut output = String::with_capacity(fmt.len());
+    let mut rest = fmt;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let var = rest.find('}').and_then(|end| {
+            let key = &rest[1..end];
+            let (_, val) = vars.iter().find(|(k, _)| *k == key)?;
+            Some((end, val))
+        });
+
+        match var {
+            Some((end, val)) => {
+                output.push_str(val);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Quote the given value for POSIX shells.
+///
+/// The value is surrounded by single quotes, inner single quotes are
+/// closed, escaped then reopened.
+fn shell_quote(val: &str) -> String {
+    format!("'{}'", val.replace('\'', r"'\''"))
+}
+
+/// The variables given to watch hooks.
+///
+/// Variables are exposed to shell commands as placeholders, as
+/// environment variables and as JSON on the standard input, and to
+/// system notifications as placeholders.
+pub trait WatchHookVars {
+    /// The prefix of the environment variables, without trailing
+    /// underscore.
+    const ENV_PREFIX: &'static str;
+
+    /// Return the variables, as key-value pairs.
+    ///
+    /// Keys are the placeholder names, without braces.
+    fn vars(&self) -> Vec<(&'static str, String)>;
+
+    /// Replace the placeholders of the given string.
+    ///
+    /// Accepted placeholders are the [variable](Self::vars) keys
+    /// surrounded by braces, for example `{subject}`. The string is
+    /// scanned once, so placeholders contained in replaced values
+    /// are kept as is.
+    fn replace(&self, fmt: &str) -> String {
+        replace_vars(fmt, &self.vars())
+    }
+
+    /// Replace the placeholders of the given shell command.
+    ///
+    /// Same as [`replace`](Self::replace), except that values are
+    /// quoted for POSIX shells, so that they are always given as
+    /// single arguments and never interpreted by the shell.
+    /// Placeholders should therefore not be surrounded by quotes.
+    fn replace_quoted(&self, cmd: &str) -> String {
+        let vars: Vec<_> = self
+            .vars()
+            .into_iter()
+            .map(|(key, val)| (key, shell_quote(&val)))
+            .collect();
+
+        replace_vars(cmd, &vars)
+    }
+
+    /// Return the variables as environment variables.
+    ///
+    /// Keys are uppercased, dots and dashes are replaced by
+    /// underscores and the [prefix](Self::ENV_PREFIX) is added, for
+    /// example `EMAIL_SENDER_ADDRESS`.
+    fn env_vars(&self) -> Vec<(String, String)> {
+        self.vars()
+            .into_iter()
+            .map(|(key, val)| {
+                let key = key.to_uppercase().replace(['.', '-'], "_");
+                (format!("{}_{key}", Self::ENV_PREFIX), val)
+            })
+            .collect()
+    }
+
+    /// Serialize the variables to JSON.
+    #[cfg(feature = "watch")]
+    fn to_json(&self) -> String;
+}
+
+impl WatchHookVars for WatchHookEnvelope<'_> {
+    const ENV_PREFIX: &'static str = "EMAIL";
+
+    fn vars(&self) -> Vec<(&'static str, String)> {
+        let envelope = self.envelope;
+        let from = &envelope.from;
+        let to = &envelope.to;
+
+        vec![
+            ("id", envelope.id.clone()),
+            ("folder", self.folder.unwrap_or_default().to_owned()),
+            ("message-id", envelope.message_id.clone()),
+            ("subject", envelope.subject.clone()),
+            ("date", envelope.date.to_rfc3339()),
+            ("flags", envelope.flags.to_string()),
+            ("sender", from.name.clone().unwrap_or(from.addr.clone())),
+            ("sender.name", from.name.clone().unwrap_or("unknown".into())),
+            ("sender.address", from.addr.clone()),
+            ("recipient", to.name.clone().unwrap_or(to.addr.clone())),
+            (
+                "recipient.name",
+                to.name.clone().unwrap_or("unknown".into()),
+            ),
+            ("recipient.address", to.addr.clone()),
+        ]
+    }
+
+    #[cfg(feature = "watch")]
+    fn to_json(&self) -> String {
+        let envelope = self.envelope;
+
+        let address = |addr: &Address| {
+            serde_json::json!({
+                "name": addr.name,
+                "address": addr.addr,
+            })
+        };
+
+        let json = serde_json::json!({
+            "id": envelope.id,
+            "folder": self.folder,
+            "message-id": envelope.message_id,
+            "subject": envelope.subject,
+            "date": envelope.date.to_rfc3339(),
+            "flags": envelope.flags.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            "sender": address(&envelope.from),
+            "recipient": address(&envelope.to),
+        });
+
+        json.to_string()
+    }
+}
+
+/// The quota resource given to watch hooks.
+///
+/// Exposed to shell commands as `QUOTA_*` environment variables, for
+/// example `QUOTA_PERCENTAGE`.
+#[derive(Clone, Debug)]
+pub struct WatchHookQuota<'a> {
+    kind: QuotaResourceKind,
+    usage: &'a QuotaUsage,
+}
+
+impl<'a> WatchHookQuota<'a> {
+    pub fn new(kind: QuotaResourceKind, usage: &'a QuotaUsage) -> Self {
+        Self { kind, usage }
+    }
+}
+
+impl WatchHookVars for WatchHookQuota<'_> {
+    const ENV_PREFIX: &'static str = "QUOTA";
+
+    fn vars(&self) -> Vec<(&'static str, String)> {
+        let limit = self.usage.limit.map(|limit| limit.to_string());
+        let percentage = self.usage.percentage();
+
+        vec![
+            ("resource", self.kind.to_string()),
+            ("usage", self.usage.usage.to_string()),
+            ("limit", limit.unwrap_or_default()),
+            (
+                "percentage",
+                percentage.map(|p| format!("{p:.0}")).unwrap_or_default(),
+            ),
+        ]
+    }
+
+    #[cfg(feature = "watch")]
+    fn to_json(&self) -> String {
+        let json = serde_json::json!({
+            "resource": self.kind.to_string(),
+            "usage": self.usage.usage,
+            "limit": self.usage.limit,
+            "percentage": self.usage.percentage(),
+        });
+
+        json.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WatchHookEnvelope, WatchHookQuota, WatchHookVars};
+    use crate::{
+        envelope::{Address, Envelope},
+        flag::{Flag, Flags},
+        quota::{QuotaResourceKind, QuotaUsage},
+    };
+
+    fn envelope() -> Envelope {
+        Envelope {
+            id: "42".into(),
+            message_id: "<id@localhost>".into(),
+            flags: Flags::from_iter([Flag::Seen, Flag::Flagged]),
+            from: Address::new(Some("Alice"), "alice@localhost"),
+            to: Address::new_nameless("bob@localhost"),
+            subject: "Hello".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn replace() {
+        let envelope = envelope();
+        let envelope = WatchHookEnvelope::new(&envelope).with_folder("INBOX");
+
+        assert_eq!(
+            envelope.replace("{id} {folder} {sender} <{sender.address}> {recipient.name}"),
+            "42 INBOX Alice <alice@localhost> unknown"
+        );
+
+        assert_eq!(
+            envelope.replace("[{flags}] {message-id}: {subject}"),
+            "[seen, flagged] <id@localhost>: Hello"
+        );
+    }
+
+    #[test]
+    fn replace_quoted() {
+        let mut envelope = envelope();
+        envelope.subject = "$(rm -rf ~); `id` 'quoted' \"{sender}\"".into();
+        let envelope = WatchHookEnvelope::new(&envelope);
+
+        assert_eq!(
+            envelope.replace_quoted("notify-send {sender} {subject}"),
+            r#"notify-send 'Alice' '$(rm -rf ~); `id` '\''quoted'\'' "{sender}"'"#
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn replace_quoted_cmd() {
+        let mut envelope = envelope();
+        envelope.subject = "$(echo injected); echo injected 'quoted'".into();
+        let envelope = WatchHookEnvelope::new(&envelope);
+
+        let cmd = envelope.replace_quoted("printf %s {subject}");
+        let output = process::Command::new(cmd).run().await.unwrap();
+
+        assert_eq!(
+            output.to_string_lossy(),
+            "$(echo injected); echo injected 'quoted'"
+        );
+    }
+
+    #[test]
+    fn replace_once() {
+        let mut envelope = envelope();
+        envelope.subject = "{sender} {unknown} {".into();
+        let envelope = WatchHookEnvelope::new(&envelope);
+
+        assert_eq!(
+            envelope.replace("{{subject}} {sender}"),
+            "{{sender} {unknown} {} Alice"
+        );
+    }
+
+    #[test]
+    fn env_vars() {
+        let envelope = envelope();
+        let vars = WatchHookEnvelope::new(&envelope).env_vars();
+
+        assert!(vars.contains(&("EMAIL_ID".into(), "42".into())));
+        assert!(vars.contains(&("EMAIL_FOLDER".into(), "".into())));
+        assert!(vars.contains(&("EMAIL_MESSAGE_ID".into(), "<id@localhost>".into())));
+        assert!(vars.contains(&("EMAIL_SENDER_ADDRESS".into(), "alice@localhost".into())));
+    }
+
+    #[test]
+    fn quota_vars() {
+        let usage = QuotaUsage::new(95, Some(100));
+        let quota = WatchHookQuota::new(QuotaResourceKind::Storage, &usage);
+
+        assert_eq!(quota.replace("{resource}: {percentage}%"), "storage: 95%");
+        assert!(quota
+            .env_vars()
+            .contains(&("QUOTA_LIMIT".into(), "100".into())));
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn to_json() {
+        let envelope = envelope();
+        let json = WatchHookEnvelope::new(&envelope)
+            .with_folder("INBOX")
+            .to_json();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(json["folder"], "INBOX");
+        assert_eq!(json["flags"], serde_json::json!(["seen", "flagged"]));
+        assert_eq!(json["sender"]["name"], "Alice");
+        assert_eq!(json["recipient"]["name"], serde_json::Value::Null);
+    }
+}
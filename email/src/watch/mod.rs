@@ -1 +1,4 @@
 pub mod config;
+pub mod hook;
+#[cfg(feature = "notify")]
+pub mod notify;
@@ -0,0 +1,368 @@
+//! # Watch notify
+//!
+//! Module dedicated to system notifications sent by watch hooks.
+//! Notifications are either sent straight, or grouped into a digest
+//! notification when a batch window is configured. Batches are held
+//! by a [`WatchNotifier`], owned by the watcher.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use notify_rust::Notification;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+#[cfg(target_os = "linux")]
+use super::config::WatchNotifyAction;
+use super::{
+    config::{WatchNotifyBatchConfig, WatchNotifyConfig},
+    hook::{replace_vars, WatchHookEnvelope, WatchHookVars},
+};
+use crate::{account::config::AccountConfig, envelope::Envelope};
+
+/// The envelopes of a notification batch, alongside their folder.
+type Batch = Vec<(Option<String>, Envelope)>;
+
+/// Send the notification of the given envelope straight.
+///
+/// Batching needs to keep state between envelopes, so the batch
+/// configuration is ignored. See [`WatchNotifier`].
+pub async fn notify(
+    account_config: &AccountConfig,
+    config: &WatchNotifyConfig,
+    envelope: &WatchHookEnvelope<'_>,
+) {
+    if !config.is_enabled_for(account_config, envelope.folder()) {
+        debug!("notifications disabled for folder, skipping");
+        return;
+    }
+
+    notify_envelope(config, envelope).await
+}
+
+/// The notifier of a watcher.
+///
+/// Holds the pending notification batches, indexed by batch key,
+/// and the tasks flushing them. Pending batches are discarded when
+/// the notifier is dropped.
+#[derive(Debug, Default)]
+pub struct WatchNotifier {
+    batches: Arc<Mutex<HashMap<String, Batch>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl WatchNotifier {
+    /// Send the notification of the given envelope.
+    ///
+    /// When a batch window is configured, the envelope is added to
+    /// the batch matching the given notify configuration. The first
+    /// envelope of a batch schedules the notification at the end of
+    /// the window.
+    pub async fn notify(
+        &self,
+        account_config: &AccountConfig,
+        config: &WatchNotifyConfig,
+        envelope: &WatchHookEnvelope<'_>,
+    ) {
+        let Some(window) = config.find_batch_window() else {
+            return notify(account_config, config, envelope).await;
+        };
+
+        if !config.is_enabled_for(account_config, envelope.folder()) {
+            debug!("notifications disabled for folder, skipping");
+            return;
+        }
+
+        let key = format!("{}\0{}", config.summary, config.body);
+        let entry = (
+            envelope.folder().map(ToOwned::to_owned),
+            envelope.envelope().clone(),
+        );
+
+        let first = {
+            let mut batches = self.batches.lock().unwrap();
+            let batch = batches.entry(key.clone()).or_default();
+            batch.push(entry);
+            batch.len() == 1
+        };
+
+        if !first {
+            return;
+        }
+
+        debug!("notification batch started, flushing in {window:?}");
+
+        let config = config.clone();
+        let batches = self.batches.clone();
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(window).await;
+            let batch = batches.lock().unwrap().remove(&key).unwrap_or_default();
+            notify_batch(&config, &batch).await
+        });
+
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|task| !task.is_finished());
+        tasks.push(task);
+    }
+}
+
+impl Drop for WatchNotifier {
+    fn drop(&mut self) {
+        if let Ok(tasks) = self.tasks.get_mut() {
+            for task in tasks.drain(..) {
+                task.abort();
+            }
+        }
+    }
+}
+
+/// Send the notification of the given batch.
+///
+/// A batch of a single envelope is notified like any envelope,
+/// otherwise a digest notification is sent.
+async fn notify_batch(config: &WatchNotifyConfig, batch: &[(Option<String>, Envelope)]) {
+    match batch {
+        [] => (),
+        [(folder, envelope)] => {
+            let mut envelope = WatchHookEnvelope::new(envelope);
+            if let Some(folder) = folder {
+                envelope.set_folder(folder);
+            }
+            notify_envelope(config, &envelope).await
+        }
+        entries => {
+            let default_batch_config = WatchNotifyBatchConfig::default();
+            let batch_config = config.batch.as_ref().unwrap_or(&default_batch_config);
+            let (summary, body) = render_digest(batch_config, entries);
+            show(summary, body).await
+        }
+    }
+}
+
+/// Send the notification of the given hook variables.
+///
+/// Unlike [`notify`], the notification is sent straight, without
+/// folder filter, batch nor action.
+pub async fn notify_vars(config: &WatchNotifyConfig, vars: &impl WatchHookVars) {
+    show(vars.replace(&config.summary), vars.replace(&config.body)).await
+}
+
+/// Send the notification of a single envelope, proposing the
+/// configured actions.
+async fn notify_envelope(config: &WatchNotifyConfig, envelope: &WatchHookEnvelope<'_>) {
+    let summary = envelope.replace(&config.summary);
+    let body = envelope.replace(&config.body);
+
+    #[cfg(target_os = "linux")]
+    if let Some(handler) = config.action_handler.as_ref() {
+        if !config.actions.is_empty() {
+            let mut notification = Notification::new();
+            notification.summary(&summary).body(&body);
+
+            for action in &config.actions {
+                notification.action(action.id(), action.label());
+            }
+
+            let handle = match notification.show_async().await {
+                Ok(handle) => handle,
+                Err(err) => {
+                    debug!("error while sending system notification");
+                    debug!("{err:?}");
+                    return;
+                }
+            };
+
+            let handler = handler.clone();
+            let folder = envelope.folder().unwrap_or_default().to_owned();
+            let envelope = envelope.envelope().clone();
+
+            // waiting for an action is blocking, so the action is
+            // awaited then executed in the background
+            tokio::spawn(async move {
+                let action = tokio::task::spawn_blocking(move || {
+                    let mut action = None;
+                    handle.wait_for_action(|id| action = WatchNotifyAction::from_id(id));
+                    action
+                })
+                .await;
+
+                if let Ok(Some(action)) = action {
+                    debug!("executing notification action {}", action.id());
+                    if let Err(err) = handler.exec(action, folder, envelope).await {
+                        debug!("error while executing notification action");
+                        debug!("{err:?}");
+                    }
+                }
+            });
+
+            return;
+        }
+    }
+
+    show(summary, body).await
+}
+
+/// Show a notification with the given summary and body.
+async fn show(summary: String, body: String) {
+    #[cfg(target_os = "linux")]
+    {
+        let res = Notification::new()
+            .summary(&summary)
+            .body(&body)
+            .show_async()
+            .await;
+
+        if let Err(err) = res {
+            debug!("error while sending system notification");
+            debug!("{err:?}");
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let res = tokio::task::spawn_blocking(move || {
+            Notification::new().summary(&summary).body(&body).show()
+        })
+        .await;
+
+        if let Err(err) = res {
+            debug!("cannot send system notification");
+            debug!("{err:?}");
+        } else {
+            let res = res.unwrap();
+            if let Err(err) = res {
+                debug!("error while sending system notification");
+                debug!("{err:?}");
+            }
+        }
+    }
+}
+
+/// Render the summary and the body of the digest notification of
+/// the given entries.
+fn render_digest(
+    config: &WatchNotifyBatchConfig,
+    entries: &[(Option<String>, Envelope)],
+) -> (String, String) {
+    let hook_envelopes = entries.iter().map(|(folder, envelope)| {
+        let mut envelope = WatchHookEnvelope::new(envelope);
+        if let Some(folder) = folder {
+            envelope.set_folder(folder);
+        }
+        envelope
+    });
+
+    let mut folders: Vec<&str> = Vec::new();
+    let mut senders: Vec<String> = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+
+    for envelope in hook_envelopes {
+        if let Some(folder) = envelope.folder() {
+            if !folders.contains(&folder) {
+                folders.push(folder);
+            }
+        }
+
+        let sender = envelope.replace("{sender}");
+        if !senders.contains(&sender) {
+            senders.push(sender);
+        }
+
+        lines.push(envelope.replace(config.find_entry()));
+    }
+
+    let vars = [
+        ("count", entries.len().to_string()),
+        ("folders", folders.join(", ")),
+        ("senders", senders.join(", ")),
+        ("entries", lines.join("\n")),
+    ];
+
+    // the entries are only accepted by the body
+    let summary = replace_vars(config.find_summary(), &vars[..3]);
+    let body = replace_vars(config.find_body(), &vars);
+
+    (summary, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_digest;
+    use crate::{
+        envelope::{Address, Envelope},
+        watch::config::WatchNotifyBatchConfig,
+    };
+
+    fn envelope(sender: &str, subject: &str) -> Envelope {
+        Envelope {
+            from: Address::new_nameless(sender),
+            subject: subject.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn render_default_digest() {
+        let entries = [
+            (Some("INBOX".into()), envelope("alice@localhost", "Hello")),
+            (Some("INBOX".into()), envelope("bob@localhost", "Hi")),
+            (
+                Some("Lists".into()),
+                envelope("alice@localhost", "Re: Hello"),
+            ),
+        ];
+
+        let (summary, body) = render_digest(&WatchNotifyBatchConfig::default(), &entries);
+
+        assert_eq!(summary, "3 new emails");
+        assert_eq!(
+            body,
+            "alice@localhost: Hello\nbob@localhost: Hi\nalice@localhost: Re: Hello"
+        );
+    }
+
+    #[test]
+    fn render_custom_digest() {
+        let config = WatchNotifyBatchConfig {
+            summary: Some("{count} emails in {folders}".into()),
+            body: Some("From {senders}:\n{entries}".into()),
+            entry: Some("- {subject}".into()),
+            ..Default::default()
+        };
+
+        let entries = [
+            (Some("INBOX".into()), envelope("alice@localhost", "Hello")),
+            (
+                Some("Lists".into()),
+                envelope("alice@localhost", "Re: Hello"),
+            ),
+        ];
+
+        let (summary, body) = render_digest(&config, &entries);
+
+        assert_eq!(summary, "2 emails in INBOX, Lists");
+        assert_eq!(body, "From alice@localhost:\n- Hello\n- Re: Hello");
+    }
+
+    #[test]
+    fn render_digest_once() {
+        let config = WatchNotifyBatchConfig {
+            summary: Some("{count} emails from {senders}".into()),
+            body: Some("{entries}".into()),
+            entry: Some("{subject}".into()),
+            ..Default::default()
+        };
+
+        let entries = [(
+            Some("INBOX".into()),
+            envelope("alice@localhost", "{folders} {count} {entries} {"),
+        )];
+
+        let (summary, body) = render_digest(&config, &entries);
+
+        assert_eq!(summary, "1 emails from alice@localhost");
+        assert_eq!(body, "{folders} {count} {entries} {");
+    }
+}
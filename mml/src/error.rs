@@ -12,8 +12,8 @@ pub enum Error {
     PgpMissingConfigurationError,
 
     #[cfg(feature = "compiler")]
-    #[error("cannot parse MML body")]
-    ParseMmlError(Vec<chumsky::error::Rich<'static, char>>, String),
+    #[error("cannot parse MML body: {}", display_diagnostics(.0))]
+    ParseMmlError(Vec<crate::message::Diagnostic>, String),
     #[cfg(feature = "compiler")]
     #[error("cannot compile template")]
     WriteCompiledPartToVecError(#[source] io::Error),
@@ -145,3 +145,12 @@ pub enum Error {
     #[error("cannot verify data using gpg")]
     VerifyGpgError(#[source] gpgme::Error),
 }
+
+#[cfg(feature = "compiler")]
+fn display_diagnostics(diagnostics: &[crate::message::Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
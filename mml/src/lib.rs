@@ -13,7 +13,7 @@
 pub use crate::message::{MimeInterpreter, MimeInterpreterBuilder};
 #[cfg(feature = "compiler")]
 #[doc(inline)]
-pub use crate::message::{MmlCompileResult, MmlCompiler, MmlCompilerBuilder};
+pub use crate::message::{MmlCompileResult, MmlCompiler, MmlCompilerBuilder, MmlLinter};
 
 #[cfg(any(feature = "pgp-commands", feature = "pgp-native"))]
 #[cfg(any(
@@ -0,0 +1,560 @@
+//! # MML body diagnostics module
+//!
+//! Module dedicated to MML body diagnostics. Diagnostics locate
+//! problems of a MML body (unclosed tags, unknown properties, missing
+//! attachments etc) using spans, and come with suggestions when
+//! possible. They are produced by the compiler when the body cannot
+//! be parsed, and by the [linter](crate::message::MmlLinter).
+
+use std::{fmt, ops::Range};
+
+use chumsky::{error::Rich, span::Span, Parser};
+
+use super::parsers::{self, MULTIPART_PROPS, PART_PROPS};
+use crate::message::body::{GREATER_THAN, MULTIPART_BEGIN, NEW_LINE, PART_BEGIN};
+
+/// The severity of a diagnostic.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+    /// The MML message can be compiled, but the result may not be
+    /// the expected one.
+    Warning,
+
+    /// The MML message cannot be compiled.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem located in a MML message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// The severity of the problem.
+    pub severity: Severity,
+
+    /// The description of the problem.
+    pub message: String,
+
+    /// The byte range of the problem in the source.
+    pub span: Range<usize>,
+
+    /// The line of the start of the span, starting from 1.
+    pub line: usize,
+
+    /// The column of the start of the span, in characters and
+    /// starting from 1.
+    pub column: usize,
+
+    /// The suggestion to fix the problem, if any.
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    /// Create a new diagnostic located at the given span of the
+    /// given source.
+    pub fn new(severity: Severity, message: impl ToString, src: &str, span: Range<usize>) -> Self {
+        let (line, column) = line_column(src, span.start);
+
+        Self {
+            severity,
+            message: message.to_string(),
+            span,
+            line,
+            column,
+            suggestion: None,
+        }
+    }
+
+    pub fn error(message: impl ToString, src: &str, span: Range<usize>) -> Self {
+        Self::new(Severity::Error, message, src, span)
+    }
+
+    pub fn warning(message: impl ToString, src: &str, span: Range<usize>) -> Self {
+        Self::new(Severity::Warning, message, src, span)
+    }
+
+    pub fn set_suggestion(&mut self, suggestion: impl ToString) {
+        self.suggestion = Some(suggestion.to_string());
+    }
+
+    pub fn with_suggestion(mut self, suggestion: impl ToString) -> Self {
+        self.set_suggestion(suggestion);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Move the diagnostic by the given offset, then recompute its
+    /// line and column from the given source.
+    ///
+    /// This is useful when the diagnostic has been produced from a
+    /// subpart of the given source, like the body of a message.
+    pub(crate) fn shift(mut self, src: &str, offset: usize) -> Self {
+        self.span = self.span.start + offset..self.span.end + offset;
+        (self.line, self.column) = line_column(src, self.span.start);
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            severity,
+            message,
+            line,
+            column,
+            ..
+        } = self;
+
+        write!(f, "{line}:{column}: {severity}: {message}")?;
+
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({suggestion})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A MML tag property, located in the source.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct TagProp<'a> {
+    pub key: &'a str,
+    pub key_span: Range<usize>,
+    pub val: Option<&'a str>,
+    pub val_span: Range<usize>,
+}
+
+/// A MML opening tag, located in the source.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Tag<'a> {
+    /// The tag name, either `<#part` or `<#multipart`.
+    pub name: &'static str,
+    pub span: Range<usize>,
+    pub props: Vec<TagProp<'a>>,
+    pub closed: bool,
+}
+
+impl Tag<'_> {
+    /// Return the properties accepted by the tag.
+    fn known_props(&self) -> &'static [&'static str] {
+        if self.name == MULTIPART_BEGIN {
+            MULTIPART_PROPS
+        } else {
+            PART_PROPS
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&TagProp<'_>> {
+        self.props.iter().find(|prop| prop.key == key)
+    }
+}
+
+/// Scan opening tags of the given MML body.
+///
+/// The scanner is less strict than the parser: it accepts unknown
+/// properties and stops a tag at the end of the line. It is used to
+/// locate attachments and to give hints about parser errors.
+pub(crate) fn scan_tags(src: &str) -> Vec<Tag<'_>> {
+    let mut tags = Vec::new();
+    let mut cursor = 0;
+
+    while let Some((begin, name)) = next_tag(src, cursor) {
+        let mut tag = Tag {
+            name,
+            span: begin..begin + name.len(),
+            props: Vec::new(),
+            closed: false,
+        };
+
+        let mut pos = begin + name.len();
+
+        loop {
+            pos += count_bytes(&src[pos..], |c| c == ' ' || c == '\t');
+
+            match src[pos..].chars().next() {
+                None | Some(NEW_LINE) => break,
+                Some(GREATER_THAN) => {
+                    tag.closed = true;
+                    pos += 1;
+                    break;
+                }
+                Some(_) => (),
+            }
+
+            let key_len = count_bytes(&src[pos..], |c| !"= \t>\n".contains(c));
+            let key_span = pos..pos + key_len;
+            pos += key_len;
+
+            let eq_offset = count_bytes(&src[pos..], |c| c == ' ' || c == '\t');
+            let (val, val_span) = if src[pos + eq_offset..].starts_with('=') {
+                pos += eq_offset + 1;
+                pos += count_bytes(&src[pos..], |c| c == ' ' || c == '\t');
+                let val_span = scan_val(src, pos);
+                pos = val_span.end;
+                (Some(unquote(&src[val_span.clone()])), val_span)
+            } else {
+                (None, pos..pos)
+            };
+
+            tag.props.push(TagProp {
+                key: &src[key_span.clone()],
+                key_span,
+                val,
+                val_span,
+            });
+        }
+
+        tag.span.end = pos;
+        cursor = pos;
+        tags.push(tag);
+    }
+
+    tags
+}
+
+/// Find the next opening tag of the given source, starting at the
+/// given cursor.
+fn next_tag(src: &str, cursor: usize) -> Option<(usize, &'static str)> {
+    let mut cursor = cursor;
+
+    while let Some(offset) = src[cursor..].find("<#") {
+        let begin = cursor + offset;
+
+        for name in [MULTIPART_BEGIN, PART_BEGIN] {
+            let is_tag = src[begin..].starts_with(name)
+                && src[begin + name.len()..]
+                    .chars()
+                    .next()
+                    .is_none_or(|c| c.is_whitespace() || c == GREATER_THAN);
+
+            if is_tag {
+                return Some((begin, name));
+            }
+        }
+
+        cursor = begin + 2;
+    }
+
+    None
+}
+
+/// Return the span of the property value starting at the given
+/// position.
+fn scan_val(src: &str, begin: usize) -> Range<usize> {
+    let quoted = src[begin..].starts_with('"');
+    let mut escaped = false;
+    let mut end = begin;
+
+    for (i, c) in src[begin..].char_indices() {
+        end = begin + i;
+
+        if c == NEW_LINE {
+            return begin..end;
+        }
+
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if quoted && c == '"' && i > 0 {
+            return begin..end + 1;
+        } else if !quoted && (c == ' ' || c == GREATER_THAN) {
+            return begin..end;
+        }
+
+        end += c.len_utf8();
+    }
+
+    begin..end
+}
+
+/// Count the bytes of the longest prefix of the given string
+/// matching the given predicate.
+fn count_bytes(s: &str, pred: impl Fn(char) -> bool) -> usize {
+    s.find(|c| !pred(c)).unwrap_or(s.len())
+}
+
+/// Remove the surrounding double quotes of the given value.
+fn unquote(val: &str) -> &str {
+    val.strip_prefix('"')
+        .and_then(|val| val.strip_suffix('"'))
+        .unwrap_or(val)
+}
+
+/// Lint the opening tags of the given MML body.
+///
+/// Report unclosed tags, properties without value and unknown
+/// properties. Unknown properties come with the closest known
+/// property as suggestion. These diagnostics are used as hints for
+/// the errors of the parser.
+fn lint_tags(src: &str, tags: &[Tag<'_>]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for tag in tags {
+        let known_props = tag.known_props();
+
+        if !tag.closed {
+            let tag_name = tag.name.trim_start_matches('<');
+            diagnostics.push(
+                Diagnostic::error(format!("unclosed tag <{tag_name}"), src, tag.span.clone())
+                    .with_suggestion("close the tag with `>` on the same line"),
+            );
+        }
+
+        for prop in &tag.props {
+            let key = prop.key;
+
+            if prop.val.is_none() {
+                diagnostics.push(
+                    Diagnostic::error(
+                        format!("missing value for property {key}"),
+                        src,
+                        prop.key_span.clone(),
+                    )
+                    .with_suggestion(format!("use `{key}=value`")),
+                );
+                continue;
+            }
+
+            if known_props.contains(&key) {
+                continue;
+            }
+
+            let mut diagnostic = Diagnostic::error(
+                format!("unknown property {key}"),
+                src,
+                prop.key_span.clone(),
+            );
+
+            match closest(key, known_props) {
+                Some(closest) => diagnostic.set_suggestion(format!("did you mean `{closest}`?")),
+                None => diagnostic
+                    .set_suggestion(format!("known properties are: {}", known_props.join(", "))),
+            }
+
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    diagnostics
+}
+
+/// Build diagnostics from the errors of the MML body parser.
+///
+/// Every parser error gives a diagnostic. Since parser errors often
+/// only report the expected tokens, the suggestion of the tag
+/// diagnostic located the closest to the error is attached to it,
+/// when any.
+pub(crate) fn from_parse_errors<'a, 'b: 'a>(
+    src: &str,
+    errs: impl IntoIterator<Item = &'a Rich<'b, char>>,
+) -> Vec<Diagnostic> {
+    let hints = lint_tags(src, &scan_tags(src));
+
+    errs.into_iter()
+        .map(|err| {
+            let span = err.span().start()..err.span().end();
+            let mut diagnostic = Diagnostic::error(err, src, span.clone());
+
+            let hint = hints
+                .iter()
+                .filter(|hint| hint.span.start <= span.start && span.start <= hint.span.end)
+                .min_by_key(|hint| (hint.span != span, hint.span.len()));
+
+            if let Some(suggestion) = hint.and_then(|hint| hint.suggestion.as_ref()) {
+                diagnostic.set_suggestion(suggestion);
+            }
+
+            diagnostic
+        })
+        .collect()
+}
+
+/// Lint the given MML body without compiling it.
+///
+/// The body is parsed in order to report the same errors as the
+/// compiler would.
+pub(crate) fn lint_body(src: &str) -> Vec<Diagnostic> {
+    let res = parsers::parts().parse(src);
+    from_parse_errors(src, res.errors())
+}
+
+/// Compute the line and the column of the given byte position.
+fn line_column(src: &str, pos: usize) -> (usize, usize) {
+    let before = src.get(..pos).unwrap_or(src);
+    let line = before.matches(NEW_LINE).count() + 1;
+    let line_begin = before.rfind(NEW_LINE).map(|i| i + 1).unwrap_or_default();
+    let column = before[line_begin..].chars().count() + 1;
+    (line, column)
+}
+
+/// Find the candidate closest to the given word.
+///
+/// Candidates farther than 2 edits are ignored.
+fn closest<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (levenshtein(word, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Compute the Levenshtein distance between the given words.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            let next = (row[j + 1] + 1).min(row[j] + 1).min(prev + cost);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use concat_with::concat_line;
+
+    use super::{lint_body, lint_tags, scan_tags, Severity};
+
+    #[test]
+    fn scan() {
+        let src = concat_line!(
+            "Hello",
+            "<#part type=text/html filename=\"/tmp/my file.html\">",
+            "<#!part type=escaped>",
+            "<#multipart type=mixed",
+        );
+
+        let tags = scan_tags(src);
+
+        assert_eq!(tags.len(), 2);
+
+        assert!(tags[0].closed);
+        assert_eq!(tags[0].get("type").unwrap().val, Some("text/html"));
+        assert_eq!(
+            tags[0].get("filename").unwrap().val,
+            Some("/tmp/my file.html")
+        );
+        assert_eq!(
+            &src[tags[0].get("type").unwrap().val_span.clone()],
+            "text/html"
+        );
+
+        assert!(!tags[1].closed);
+        assert_eq!(tags[1].get("type").unwrap().val, Some("mixed"));
+    }
+
+    #[test]
+    fn unknown_props() {
+        let src = concat_line!("Hello", "<#part typ=text/html foo=bar>", "world");
+        let diagnostics = lint_tags(src, &scan_tags(src));
+
+        assert_eq!(diagnostics.len(), 2);
+
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].message, "unknown property typ");
+        assert_eq!(diagnostics[0].span, 13..16);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 8));
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("did you mean `type`?")
+        );
+
+        assert_eq!(diagnostics[1].message, "unknown property foo");
+        assert!(diagnostics[1]
+            .suggestion
+            .as_deref()
+            .unwrap()
+            .starts_with("known properties are: type, filename"));
+
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "2:8: error: unknown property typ (did you mean `type`?)"
+        );
+    }
+
+    #[test]
+    fn unclosed_tags() {
+        let src = concat_line!("<#part type=text/html", "Hello", "<#/part>");
+        let diagnostics = lint_tags(src, &scan_tags(src));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].message, "unclosed tag <#part");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (1, 1));
+    }
+
+    #[test]
+    fn missing_values() {
+        let src = "<#part type>";
+        let diagnostics = lint_tags(src, &scan_tags(src));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "missing value for property type");
+        assert_eq!(diagnostics[0].span, 7..11);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let src = "<#part type=text/html foo=bar>x";
+        let diagnostics = lint_body(src);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "unknown property foo");
+        assert_eq!(diagnostics[0].span, 22..25);
+        assert!(diagnostics[0]
+            .suggestion
+            .as_deref()
+            .unwrap()
+            .starts_with("known properties are: "));
+
+        let src = "<#multipart type=mixd>\n<#/multipart>";
+        let diagnostics = lint_body(src);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "invalid value mixd for property type"
+        );
+        assert_eq!(diagnostics[0].span, 17..21);
+        assert_eq!(diagnostics[0].suggestion, None);
+
+        let src = "<#part type>";
+        let diagnostics = lint_body(src);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span.start, 11);
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("use `type=value`")
+        );
+    }
+
+    #[test]
+    fn parse_errors_only() {
+        // the scanner considers the tag as unclosed, but the parser
+        // accepts tags spanning multiple lines
+        let src = concat_line!("<#part type=text/plain", "  filename=/tmp/file.txt>");
+        assert_eq!(lint_body(src), vec![]);
+    }
+}
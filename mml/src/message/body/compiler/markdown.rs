@@ -0,0 +1,197 @@
+//! # Markdown compilation module
+//!
+//! Module dedicated to Markdown parts compilation. A Markdown part is
+//! compiled into a `multipart/alternative` MIME part, composed of the
+//! Markdown source as plain text and of its HTML rendering. Local
+//! images referenced by the Markdown are embedded into a
+//! `multipart/related` MIME part, and referenced by their Content-ID
+//! from the HTML.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use mail_builder::mime::MimePart;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use shellexpand_utils::shellexpand_path;
+
+use crate::{Error, Result};
+
+/// A local image referenced by a Markdown part.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct MarkdownImage {
+    /// The Content-ID of the image, without angle brackets.
+    pub cid: String,
+
+    /// The shell expanded path of the image.
+    pub path: PathBuf,
+}
+
+/// Render the given Markdown into HTML.
+///
+/// The source of local images is replaced by a `cid:` URL, and the
+/// images are returned alongside the HTML. Remote images, data URLs
+/// and `cid:` URLs are left untouched.
+pub(crate) fn render(markdown: &str) -> (String, Vec<MarkdownImage>) {
+    let mut images: Vec<MarkdownImage> = Vec::new();
+
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+
+    let parser = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if is_local(&dest_url) => {
+            let path = shellexpand_path(dest_url.as_ref());
+
+            let cid = match images.iter().find(|image| image.path == path) {
+                Some(image) => image.cid.clone(),
+                None => {
+                    let cid = content_id(images.len(), &path);
+                    images.push(MarkdownImage {
+                        cid: cid.clone(),
+                        path,
+                    });
+                    cid
+                }
+            };
+
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url: CowStr::from(format!("cid:{cid}")),
+                title,
+                id,
+            })
+        }
+        event => event,
+    });
+
+    let mut output = String::new();
+    html::push_html(&mut output, parser);
+
+    (output, images)
+}
+
+/// Compile the given Markdown into a `multipart/alternative` MIME
+/// part.
+pub(crate) fn compile<'a>(markdown: &str) -> Result<MimePart<'a>> {
+    let (html, images) = render(markdown);
+
+    let text_part = MimePart::new("text/plain", markdown.to_owned());
+    let mut html_part = MimePart::new("text/html", html);
+
+    if !images.is_empty() {
+        let mut related_parts = vec![html_part];
+
+        for image in images {
+            let contents = fs::read(&image.path)
+                .map_err(|err| Error::ReadAttachmentError(err, image.path.clone()))?;
+            let ctype = tree_magic_mini::from_u8(&contents).to_owned();
+            let part = MimePart::new(ctype, contents).inline().cid(image.cid);
+            related_parts.push(part);
+        }
+
+        html_part = MimePart::new("multipart/related", related_parts);
+    }
+
+    Ok(MimePart::new(
+        "multipart/alternative",
+        vec![text_part, html_part],
+    ))
+}
+
+/// Return `true` if the given image URL points to a local file.
+fn is_local(url: &str) -> bool {
+    let url = url.trim();
+
+    if url.is_empty() || url.contains("://") {
+        return false;
+    }
+
+    !["cid:", "data:", "mailto:"].iter().any(|scheme| {
+        url.get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+    })
+}
+
+/// Build the Content-ID of the image at the given index.
+///
+/// The Content-ID is built from the image position and its file
+/// name, which makes it unique within the message.
+fn content_id(index: usize, path: &Path) -> String {
+    let name: String = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    format!("{}.{name}@mml", index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{is_local, render, MarkdownImage};
+
+    #[test]
+    fn local_urls() {
+        assert!(is_local("image.png"));
+        assert!(is_local("/tmp/image.png"));
+        assert!(is_local("~/image.png"));
+        assert!(!is_local("https://localhost/image.png"));
+        assert!(!is_local("cid:image@localhost"));
+        assert!(!is_local("DATA:image/png;base64,AAAA"));
+        assert!(!is_local(""));
+    }
+
+    #[test]
+    fn render_without_images() {
+        let (html, images) = render("# Hello\n\nThis is *Markdown*.\n");
+
+        assert_eq!(html, "<h1>Hello</h1>\n<p>This is <em>Markdown</em>.</p>\n");
+        assert!(images.is_empty());
+    }
+
+    #[test]
+    fn render_with_images() {
+        let (html, images) = render(concat!(
+            "![logo](/tmp/logo.png)\n",
+            "![remote](https://localhost/remote.png)\n",
+            "![logo again](/tmp/logo.png)\n",
+            "![my photo](</tmp/my photo.jpg>)\n",
+        ));
+
+        assert_eq!(
+            images,
+            vec![
+                MarkdownImage {
+                    cid: "1.logo.png@mml".into(),
+                    path: PathBuf::from("/tmp/logo.png"),
+                },
+                MarkdownImage {
+                    cid: "2.my-photo.jpg@mml".into(),
+                    path: PathBuf::from("/tmp/my photo.jpg"),
+                },
+            ]
+        );
+
+        assert!(html.contains("<img src=\"cid:1.logo.png@mml\" alt=\"logo\" />"));
+        assert!(html.contains("<img src=\"https://localhost/remote.png\" alt=\"remote\" />"));
+        assert!(html.contains("<img src=\"cid:1.logo.png@mml\" alt=\"logo again\" />"));
+    }
+}
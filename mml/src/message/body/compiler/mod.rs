@@ -2,6 +2,9 @@
 //!
 //! Module dedicated to MML → MIME message body compilation.
 
+pub(crate) mod diagnostics;
+#[cfg(feature = "markdown")]
+mod markdown;
 mod parsers;
 mod tokens;
 
@@ -9,6 +12,7 @@
 
 use async_recursion::async_recursion;
 use mail_builder::{
+    headers::content_type::ContentType,
     mime::{BodyPart, MimePart},
     MessageBuilder,
 };
@@ -20,15 +24,19 @@
 use crate::pgp::Pgp;
 use crate::{Error, Result};
 
+#[cfg(feature = "markdown")]
+use super::TEXT_MARKDOWN;
 use super::{
-    ALTERNATIVE, ATTACHMENT, DISPOSITION, ENCODING, ENCODING_7BIT, ENCODING_8BIT, ENCODING_BASE64,
-    ENCODING_QUOTED_PRINTABLE, FILENAME, INLINE, MIXED, MULTIPART_BEGIN, MULTIPART_BEGIN_ESCAPED,
-    MULTIPART_END, MULTIPART_END_ESCAPED, NAME, PART_BEGIN, PART_BEGIN_ESCAPED, PART_END,
-    PART_END_ESCAPED, RECIPIENT_FILENAME, RELATED, TYPE,
+    flowed, ALTERNATIVE, ATTACHMENT, DISPOSITION, ENCODING, ENCODING_7BIT, ENCODING_8BIT,
+    ENCODING_BASE64, ENCODING_QUOTED_PRINTABLE, FILENAME, INLINE, MIXED, MULTIPART_BEGIN,
+    MULTIPART_BEGIN_ESCAPED, MULTIPART_END, MULTIPART_END_ESCAPED, NAME, PART_BEGIN,
+    PART_BEGIN_ESCAPED, PART_END, PART_END_ESCAPED, RECIPIENT_FILENAME, RELATED, TYPE,
 };
 #[cfg(feature = "pgp")]
 use super::{ENCRYPT, PGP_MIME, SIGN};
 
+#[doc(inline)]
+pub use self::diagnostics::{Diagnostic, Severity};
 use self::{parsers::prelude::*, tokens::Part};
 
 /// MML → MIME message body compiler.
@@ -37,6 +45,13 @@
 /// is named `compile`.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct MmlBodyCompiler {
+    /// Should `text/plain` parts be compiled as `format=flowed`.
+    ///
+    /// When enabled, long lines are wrapped using soft line breaks
+    /// as defined in RFC 3676, which allows receiving agents to
+    /// reflow paragraphs to the width of their screen.
+    format_flowed: bool,
+
     #[cfg(feature = "pgp")]
     pgp: Option<Pgp>,
     #[cfg(feature = "pgp")]
@@ -51,6 +66,15 @@ pub fn new() -> Self {
         Self::default()
     }
 
+    pub fn set_format_flowed(&mut self, flowed: bool) {
+        self.format_flowed = flowed;
+    }
+
+    pub fn with_format_flowed(mut self, flowed: bool) -> Self {
+        self.set_format_flowed(flowed);
+        self
+    }
+
     #[cfg(feature = "pgp")]
     pub fn set_pgp(&mut self, pgp: impl Into<Pgp>) {
         self.pgp = Some(pgp.into());
@@ -217,6 +241,19 @@ fn unescape_mml_markup(text: impl AsRef<str>) -> String {
             .replace(MULTIPART_END_ESCAPED, MULTIPART_END)
     }
 
+    /// Build a `text/plain` MIME part from the given text.
+    ///
+    /// If the format flowed option is enabled, the text is encoded
+    /// as `format=flowed`.
+    fn text_plain_part(&self, ctype: ContentType<'a>, text: String) -> MimePart<'a> {
+        if self.format_flowed {
+            let text = flowed::encode(&text, flowed::DEFAULT_FLOWED_WIDTH);
+            MimePart::new(ctype.attribute("format", "flowed"), text)
+        } else {
+            MimePart::new(ctype, text)
+        }
+    }
+
     /// Compile given parts parsed from a MML body to a
     /// [MessageBuilder].
     async fn compile_parts(&'a self, parts: Vec<Part<'a>>) -> Result<MessageBuilder> {
@@ -289,13 +326,19 @@ async fn compile_part(&'a self, part: Part<'a>) -> Result<MimePart> {
                         }
                         MimePart::new(ctype, contents)
                     }
+                    #[cfg(feature = "markdown")]
+                    None if props.get(TYPE) == Some(&TEXT_MARKDOWN) => markdown::compile(body)?,
                     None => {
-                        let mut ctype =
+                        let mut ctype: ContentType =
                             Part::get_or_guess_content_type(props, body.as_bytes()).into();
                         if let Some(name) = props.get(NAME) {
                             ctype = ctype.attribute("name", *name);
                         }
-                        MimePart::new(ctype, body)
+                        if ctype.c_type == "text/plain" {
+                            self.text_plain_part(ctype, body.to_owned())
+                        } else {
+                            MimePart::new(ctype, body)
+                        }
                     }
                 };
 
@@ -355,7 +398,7 @@ async fn compile_part(&'a self, part: Part<'a>) -> Result<MimePart> {
             }
             Part::PlainText(body) => {
                 let body = Self::unescape_mml_markup(body);
-                let part = MimePart::new("text/plain", body);
+                let part = self.text_plain_part(ContentType::new("text/plain"), body);
                 Ok(part)
             }
         }
@@ -363,12 +406,12 @@ async fn compile_part(&'a self, part: Part<'a>) -> Result<MimePart> {
 
     /// Compile the given raw MML body to MIME body.
     pub async fn compile(&'a self, mml_body: &'a str) -> Result<MessageBuilder> {
-        let res = parsers::parts().parse(mml_body);
-        if let Some(parts) = res.output() {
-            Ok(self.compile_parts(parts.to_owned()).await?)
-        } else {
-            let errs = res.errors().map(|err| err.clone().into_owned()).collect();
-            Err(Error::ParseMmlError(errs, mml_body.to_owned()))
+        match parsers::parts().parse(mml_body).into_output_errors() {
+            (Some(parts), errs) if errs.is_empty() => Ok(self.compile_parts(parts).await?),
+            (_, errs) => {
+                let diagnostics = diagnostics::from_parse_errors(mml_body, &errs);
+                Err(Error::ParseMmlError(diagnostics, mml_body.to_owned()))
+            }
         }
     }
 }
@@ -476,4 +519,26 @@ async fn attachment() {
 
         assert_eq!(msg, expected_msg);
     }
+
+    #[tokio::test]
+    async fn plain_format_flowed() {
+        let mml_body = concat_line!(
+            "This paragraph is long enough to be wrapped using soft line breaks, as defined in RFC 3676.",
+            "",
+        );
+
+        let msg = MmlBodyCompiler::new()
+            .with_format_flowed(true)
+            .compile(mml_body)
+            .await
+            .unwrap()
+            .write_to_string()
+            .unwrap();
+
+        assert!(msg.contains("format=\"flowed\""));
+        assert!(
+            msg.contains("This paragraph is long enough to be wrapped using soft line breaks, as")
+        );
+        assert!(msg.contains("defined in RFC 3676.\r\n"));
+    }
 }
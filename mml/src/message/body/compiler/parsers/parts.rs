@@ -6,8 +6,9 @@
 };
 
 use super::{
-    creation_date, data_encoding, description, disposition, encoding, filename, modification_date,
-    multipart_type, name, part_type, prelude::*, read_date, recipient_filename,
+    creation_date, data_encoding, description, disposition, encoding, filename, invalid_prop,
+    modification_date, multipart_type, name, part_type, prelude::*, read_date, recipient_filename,
+    MULTIPART_PROPS, PART_PROPS,
 };
 #[cfg(feature = "pgp")]
 use super::{encrypt, sign};
@@ -40,6 +41,7 @@ pub(crate) fn multipart<'a>() -> impl Parser<'a, &'a str, Part<'a>, ParserError<
                     encrypt(),
                     #[cfg(feature = "pgp")]
                     sign(),
+                    invalid_prop(MULTIPART_PROPS),
                 ))
                 .repeated()
                 .collect::<Props>(),
@@ -81,6 +83,7 @@ pub(crate) fn part<'a>() -> impl Parser<'a, &'a str, Part<'a>, ParserError<'a>>
                 encrypt(),
                 #[cfg(feature = "pgp")]
                 sign(),
+                invalid_prop(PART_PROPS),
             ))
             .repeated()
             .collect::<HashMap<_, _>>()
@@ -15,6 +15,35 @@
 
 use super::{maybe_quoted_const_val, prelude::*, quoted_val, val};
 
+/// The properties accepted by the `<#part>` tag.
+pub(crate) const PART_PROPS: &[&str] = &[
+    TYPE,
+    FILENAME,
+    RECIPIENT_FILENAME,
+    NAME,
+    ENCODING,
+    DATA_ENCODING,
+    CREATION_DATE,
+    MODIFICATION_DATE,
+    READ_DATE,
+    DESCRIPTION,
+    DISPOSITION,
+    #[cfg(feature = "pgp")]
+    ENCRYPT,
+    #[cfg(feature = "pgp")]
+    SIGN,
+];
+
+/// The properties accepted by the `<#multipart>` tag.
+pub(crate) const MULTIPART_PROPS: &[&str] = &[
+    TYPE,
+    DESCRIPTION,
+    #[cfg(feature = "pgp")]
+    ENCRYPT,
+    #[cfg(feature = "pgp")]
+    SIGN,
+];
+
 /// The multipart type property.
 ///
 /// > The MIME type of the part (Content-Type).
@@ -248,3 +277,34 @@ pub(crate) fn encrypt<'a>() -> impl Parser<'a, &'a str, Prop<'a>, ParserError<'a
         .then(pgp_mime())
         .padded()
 }
+
+/// The invalid property parser.
+///
+/// It parses any property that could not be parsed by the property
+/// parsers of the tag, then reports it as an unknown property or as
+/// a property with an invalid value. This gives more precise errors
+/// than the ones of the property parsers, which only report the
+/// expected characters.
+pub(crate) fn invalid_prop<'a>(
+    known_props: &'static [&'static str],
+) -> impl Parser<'a, &'a str, Prop<'a>, ParserError<'a>> + Clone {
+    none_of("= \t\r\n>")
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .map_with(|key, e| (key, e.span()))
+        .then_ignore(just('=').padded())
+        .then(choice((quoted_val(), val().to_slice())).map_with(|val: &str, e| (val, e.span())))
+        .padded()
+        .validate(move |((key, key_span), (val, val_span)), _, emitter| {
+            if known_props.contains(&key) {
+                let err = format!("invalid value {val} for property {key}");
+                emitter.emit(Rich::custom(val_span, err));
+            } else {
+                let err = format!("unknown property {key}");
+                emitter.emit(Rich::custom(key_span, err));
+            }
+
+            (key, val)
+        })
+}
@@ -0,0 +1,262 @@
+//! # Format flowed module
+//!
+//! Module dedicated to the `text/plain; format=flowed` encoding, as
+//! defined in [RFC 3676]. Flowed lines end with a space (soft line
+//! break), which allows receiving agents to reflow paragraphs
+//! instead of displaying them as hard wrapped lines.
+//!
+//! [RFC 3676]: https://www.rfc-editor.org/rfc/rfc3676
+
+/// The default maximum width of flowed lines.
+///
+/// The RFC recommends lines of at most 78 characters, and suggests
+/// shorter lines so that quoting does not require rewrapping.
+pub const DEFAULT_FLOWED_WIDTH: usize = 72;
+
+/// The signature separator line, which is never flowed.
+const SIGNATURE_SEPARATOR: &str = "-- ";
+
+/// Encode the given plain text as `format=flowed`.
+///
+/// Lines longer than the given width are wrapped at spaces using
+/// soft line breaks, trailing spaces of hard line breaks are removed
+/// and lines are space-stuffed when needed. The quote depth of lines
+/// starting with `>` is preserved on every wrapped line. Words longer
+/// than the width are never split.
+pub fn encode(text: &str, width: usize) -> String {
+    let mut flowed = String::new();
+
+    for line in text.lines() {
+        if line == SIGNATURE_SEPARATOR {
+            flowed.push_str(line);
+            flowed.push('\n');
+            continue;
+        }
+
+        let (depth, content) = split_quote(line);
+        let content = content.trim_end_matches(' ');
+        let prefix = ">".repeat(depth);
+        let width = width.saturating_sub(depth + 1).max(1);
+
+        for content in wrap(content, width) {
+            flowed.push_str(&prefix);
+            if depth > 0 {
+                // a space after the quote marks acts as
+                // space-stuffing, and makes quotes more readable
+                if !content.is_empty() {
+                    flowed.push(' ');
+                }
+            } else if needs_stuffing(&content) {
+                flowed.push(' ');
+            }
+            flowed.push_str(&content);
+            flowed.push('\n');
+        }
+    }
+
+    if !text.ends_with('\n') {
+        flowed.pop();
+    }
+
+    flowed
+}
+
+/// Decode the given `format=flowed` text into plain text.
+///
+/// Soft broken lines of the same quote depth are joined into a
+/// single line, and space-stuffing is removed. When `delsp` is
+/// `true`, the trailing space of soft broken lines is deleted, as
+/// requested by the `DelSp=yes` parameter. Quote depth is preserved
+/// using `>` prefixes, so that decoded text can be quoted again.
+pub fn decode(text: &str, delsp: bool) -> String {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    let mut flowing = false;
+
+    for line in text.lines() {
+        let (depth, content) = split_quote_marks(line);
+        let content = content.strip_prefix(' ').unwrap_or(content);
+
+        let soft = content.ends_with(' ') && content != SIGNATURE_SEPARATOR;
+        let content = match soft && delsp {
+            true => content.strip_suffix(' ').unwrap_or(content),
+            false => content,
+        };
+
+        match lines.last_mut() {
+            Some((prev_depth, prev_content)) if flowing && *prev_depth == depth => {
+                prev_content.push_str(content);
+            }
+            // a flowed line followed by a line of another quote depth
+            // is considered as a hard line break
+            last => {
+                if let Some((_, prev_content)) = last.filter(|_| flowing) {
+                    trim_end_spaces(prev_content);
+                }
+                lines.push((depth, content.to_owned()));
+            }
+        }
+
+        flowing = soft;
+    }
+
+    if let Some((_, content)) = lines.last_mut().filter(|_| flowing) {
+        trim_end_spaces(content);
+    }
+
+    let mut text = lines
+        .into_iter()
+        .map(|(depth, content)| {
+            let mut line = ">".repeat(depth);
+            if depth > 0 && !content.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&content);
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if text.is_empty() {
+        return text;
+    }
+
+    text.push('\n');
+    text
+}
+
+/// Remove the trailing spaces of the given content.
+fn trim_end_spaces(content: &mut String) {
+    content.truncate(content.trim_end_matches(' ').len());
+}
+
+/// Split the quote marks of the given flowed line.
+///
+/// Only consecutive `>` are considered as quote marks.
+fn split_quote_marks(line: &str) -> (usize, &str) {
+    let content = line.trim_start_matches('>');
+    (line.len() - content.len(), content)
+}
+
+/// Split the quote prefix of the given plain text line.
+///
+/// Quote marks can be separated by a single space, so that
+/// `> > text` is considered as a quote of depth 2.
+fn split_quote(line: &str) -> (usize, &str) {
+    let mut depth = 0;
+    let mut content = line;
+
+    while let Some(rest) = content.strip_prefix('>') {
+        depth += 1;
+        content = rest.strip_prefix(' ').unwrap_or(rest);
+    }
+
+    (depth, content)
+}
+
+/// Return `true` if the given unquoted content needs to be
+/// space-stuffed.
+fn needs_stuffing(content: &str) -> bool {
+    content.starts_with(' ') || content.starts_with('>') || content.starts_with("From ")
+}
+
+/// Wrap the given content at spaces.
+///
+/// Every line but the last one ends with a space, which represents
+/// the soft line break.
+fn wrap(content: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    for (i, word) in content.split(' ').enumerate() {
+        let word_width = word.chars().count();
+
+        if i > 0 {
+            if line_width > 0 && line_width + 1 + word_width > width {
+                line.push(' ');
+                lines.push(line);
+                line = String::new();
+                line_width = 0;
+            } else {
+                line.push(' ');
+                line_width += 1;
+            }
+        }
+
+        line.push_str(word);
+        line_width += word_width;
+    }
+
+    lines.push(line);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+
+    #[test]
+    fn encode_short_lines() {
+        assert_eq!(encode("Hello, world!\n", 72), "Hello, world!\n");
+        assert_eq!(encode("Hello   \n\nworld", 72), "Hello\n\nworld");
+    }
+
+    #[test]
+    fn encode_long_lines() {
+        assert_eq!(
+            encode("The quick brown fox jumps over the lazy dog.\n", 20),
+            "The quick brown fox \njumps over the lazy \ndog.\n",
+        );
+    }
+
+    #[test]
+    fn encode_quoted_lines() {
+        assert_eq!(
+            encode("> > The quick brown fox jumps\n>\n", 20),
+            ">> The quick brown \n>> fox jumps\n>\n",
+        );
+    }
+
+    #[test]
+    fn encode_stuffed_lines() {
+        assert_eq!(
+            encode(" indented\nFrom here\n-- \nsignature", 72),
+            "  indented\n From here\n-- \nsignature",
+        );
+    }
+
+    #[test]
+    fn decode_flowed_lines() {
+        assert_eq!(
+            decode("The quick brown fox \njumps over the lazy \ndog.\n", false),
+            "The quick brown fox jumps over the lazy dog.\n",
+        );
+    }
+
+    #[test]
+    fn decode_delsp_lines() {
+        assert_eq!(decode("Hel \nlo, wor \nld!\n", true), "Hello, world!\n");
+    }
+
+    #[test]
+    fn decode_quoted_lines() {
+        assert_eq!(
+            decode(">> The quick brown \n>> fox jumps \n> over\n>\n", false),
+            ">> The quick brown fox jumps\n> over\n>\n",
+        );
+    }
+
+    #[test]
+    fn decode_stuffed_lines() {
+        assert_eq!(
+            decode("  indented\n From here\n-- \nsignature", false),
+            " indented\nFrom here\n-- \nsignature\n",
+        );
+    }
+
+    #[test]
+    fn encode_decode() {
+        let text = "> Some quoted text that is long enough to be wrapped.\n\nAnd the answer, which is long enough to be wrapped as well.\n";
+        assert_eq!(decode(&encode(text, 20), false), text);
+    }
+}
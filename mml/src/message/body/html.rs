@@ -0,0 +1,266 @@
+//! # HTML rendering module
+//!
+//! Module dedicated to HTML → plain text rendering, used by the
+//! [`MimeBodyInterpreter`](super::MimeBodyInterpreter) to display
+//! `text/html` parts. It also contains the resolution of `cid:`
+//! references, used to display inline images of HTML parts.
+
+use nanohtml2text::html2text;
+use process::Command;
+#[allow(unused_imports)]
+use tracing::{debug, trace};
+
+/// The default width used by the structured renderer.
+pub const DEFAULT_HTML_RENDERER_WIDTH: usize = 80;
+
+/// The HTML → plain text renderer.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum HtmlRenderer {
+    /// Render HTML by stripping tags.
+    ///
+    /// This renderer is fast but loses the structure of the document
+    /// (links, tables, lists, quotes etc).
+    #[default]
+    Simple,
+
+    /// Render HTML while preserving its structure.
+    ///
+    /// Links are turned into numbered footnotes, tables are laid out,
+    /// blockquotes are prefixed by `>` and lines are wrapped at the
+    /// given width.
+    #[cfg(feature = "structured-html")]
+    Structured {
+        /// The maximum width of rendered lines.
+        width: usize,
+    },
+
+    /// Render HTML using the given shell command.
+    ///
+    /// The HTML is given to the command via its standard input, and
+    /// the rendered text is read from its standard output, for
+    /// example `w3m -dump -T text/html` or `lynx -stdin -dump`.
+    Command(Command),
+}
+
+impl HtmlRenderer {
+    /// Create a structured renderer with the default width.
+    #[cfg(feature = "structured-html")]
+    pub fn structured() -> Self {
+        Self::Structured {
+            width: DEFAULT_HTML_RENDERER_WIDTH,
+        }
+    }
+
+    /// Render the given HTML as plain text.
+    ///
+    /// If the renderer fails, a debug message is logged and the
+    /// [simple](Self::Simple) renderer is used instead.
+    pub async fn render(&self, html: &str) -> String {
+        match self {
+            Self::Simple => html2text(html),
+            #[cfg(feature = "structured-html")]
+            Self::Structured { width } => {
+                let res = ::html2text::config::plain().string_from_read(html.as_bytes(), *width);
+
+                match res {
+                    Ok(text) => text,
+                    Err(err) => {
+                        debug!("cannot render html part, falling back to simple renderer: {err}");
+                        trace!("{err:?}");
+                        html2text(html)
+                    }
+                }
+            }
+            Self::Command(cmd) => match cmd.run_with(html).await {
+                Ok(output) => output.to_string_lossy(),
+                Err(err) => {
+                    debug!("cannot render html part using command, falling back to simple renderer: {err}");
+                    trace!("{err:?}");
+                    html2text(html)
+                }
+            },
+        }
+    }
+}
+
+impl From<Command> for HtmlRenderer {
+    fn from(cmd: Command) -> Self {
+        Self::Command(cmd)
+    }
+}
+
+/// The resolution strategy of `cid:` references.
+///
+/// HTML parts refer to their inline images using `cid:` URLs, which
+/// point to the Content-ID of a sibling MIME part. These URLs cannot
+/// be opened outside of the message, so they can be resolved when
+/// interpreting HTML parts.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum CidResolution {
+    /// Keep `cid:` references as they are.
+    #[default]
+    Keep,
+
+    /// Replace `cid:` references by the path of the referenced part,
+    /// saved in the attachments directory.
+    Path,
+
+    /// Replace `cid:` references by data URIs containing the
+    /// referenced part.
+    DataUri,
+}
+
+/// Replace `cid:` references of the given HTML.
+///
+/// The given resolver receives the Content-ID of every reference,
+/// percent-decoded and without angle brackets. References that
+/// cannot be resolved are kept as they are.
+pub(crate) fn replace_cids(html: &str, mut resolve: impl FnMut(&str) -> Option<String>) -> String {
+    // ASCII lowercase keeps byte offsets unchanged
+    let lowercase_html = html.to_ascii_lowercase();
+    let mut output = String::with_capacity(html.len());
+    let mut cursor = 0;
+
+    while let Some(offset) = lowercase_html[cursor..].find("cid:") {
+        let begin = cursor + offset;
+        let cid_begin = begin + 4;
+        let cid_end = html[cid_begin..]
+            .find(|c: char| c.is_whitespace() || "\"'()<>".contains(c))
+            .map(|offset| cid_begin + offset)
+            .unwrap_or(html.len());
+
+        output.push_str(&html[cursor..begin]);
+
+        let preceded_by_delimiter = html[..begin]
+            .chars()
+            .next_back()
+            .is_none_or(|c| c.is_whitespace() || "\"'(=".contains(c));
+
+        let cid = percent_decode(&html[cid_begin..cid_end]);
+        let cid = cid.trim_start_matches('<').trim_end_matches('>');
+
+        // only resolve references that would be replaced, since
+        // resolving may save the referenced part
+        let url = if preceded_by_delimiter && !cid.is_empty() {
+            resolve(cid)
+        } else {
+            None
+        };
+
+        match url {
+            Some(url) => output.push_str(&url),
+            None => output.push_str(&html[begin..cid_end]),
+        }
+
+        cursor = cid_end;
+    }
+
+    output.push_str(&html[cursor..]);
+    output
+}
+
+/// Decode percent-encoded characters of the given URL part.
+fn percent_decode(input: &str) -> String {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut iter = input.bytes();
+
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = iter.clone().take(2).collect::<Vec<_>>();
+            let decoded = std::str::from_utf8(&hex)
+                .ok()
+                .filter(|hex| hex.len() == 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+            if let Some(decoded) = decoded {
+                bytes.push(decoded);
+                iter.nth(1);
+                continue;
+            }
+        }
+
+        bytes.push(b);
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replace_cids, HtmlRenderer};
+
+    #[tokio::test]
+    async fn simple() {
+        let text = HtmlRenderer::Simple
+            .render("<h1>Hello, <em>world</em>!</h1>")
+            .await;
+
+        assert_eq!(text.trim(), "Hello, world!");
+    }
+
+    #[cfg(feature = "structured-html")]
+    #[tokio::test]
+    async fn structured() {
+        let html = concat!(
+            "<p>Hello, <a href=\"https://localhost\">world</a>!</p>",
+            "<blockquote>Quoted text.</blockquote>",
+        );
+
+        let text = HtmlRenderer::structured().render(html).await;
+
+        assert!(text.contains("[world][1]"));
+        assert!(text.contains("> Quoted text."));
+        assert!(text.contains("[1]: https://localhost"));
+    }
+
+    #[tokio::test]
+    async fn command() {
+        let text = HtmlRenderer::from(process::Command::new("tr a-z A-Z"))
+            .render("<p>hello</p>")
+            .await;
+
+        assert_eq!(text, "<P>HELLO</P>");
+    }
+
+    #[test]
+    fn replace_cid_references() {
+        let html = concat!(
+            "<img src=\"cid:logo@localhost\">",
+            "<img src='CID:photo%40localhost'>",
+            "<div style=\"background: url(cid:bg@localhost)\"></div>",
+            "<img src=\"cid:unknown@localhost\">",
+            "<p>acid:not-a-reference</p>",
+        );
+
+        let html = replace_cids(html, |cid| match cid {
+            "logo@localhost" => Some("/tmp/logo.png".into()),
+            "photo@localhost" => Some("/tmp/photo.jpg".into()),
+            "bg@localhost" => Some("data:image/png;base64,AAAA".into()),
+            _ => None,
+        });
+
+        assert_eq!(
+            html,
+            concat!(
+                "<img src=\"/tmp/logo.png\">",
+                "<img src='/tmp/photo.jpg'>",
+                "<div style=\"background: url(data:image/png;base64,AAAA)\"></div>",
+                "<img src=\"cid:unknown@localhost\">",
+                "<p>acid:not-a-reference</p>",
+            )
+        );
+    }
+
+    #[test]
+    fn replace_cids_resolve_replaced_only() {
+        let html = "<p>acid:not-a-reference</p><img src=\"cid:\"><img src=\"cid:logo\">";
+        let mut resolved = Vec::new();
+
+        replace_cids(html, |cid| {
+            resolved.push(cid.to_owned());
+            None
+        });
+
+        assert_eq!(resolved, vec!["logo"]);
+    }
+}
@@ -2,12 +2,14 @@
 //!
 //! Module dedicated to MIME → MML message body interpretation.
 
-use std::{env, fs, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Component, Path, PathBuf},
+};
 
 use async_recursion::async_recursion;
-use mail_builder::MessageBuilder;
+use mail_builder::{encoders::base64::base64_encode, MessageBuilder};
 use mail_parser::{Message, MessageParser, MessagePart, MimeHeaders, PartType};
-use nanohtml2text::html2text;
 #[allow(unused_imports)]
 use tracing::{debug, trace, warn};
 
@@ -16,6 +18,8 @@
 use crate::{Error, Result};
 
 use super::{
+    flowed,
+    html::{replace_cids, CidResolution, HtmlRenderer},
     MULTIPART_BEGIN, MULTIPART_BEGIN_ESCAPED, MULTIPART_END, MULTIPART_END_ESCAPED, PART_BEGIN,
     PART_BEGIN_ESCAPED, PART_END, PART_END_ESCAPED,
 };
@@ -149,6 +153,19 @@ pub struct MimeBodyInterpreter {
     /// [`std::env::temp_dir()`].
     save_attachments_dir: PathBuf,
 
+    /// Defines the renderer of `text/html` parts.
+    ///
+    /// The renderer is not used when only `text/html` parts are
+    /// shown, since the raw HTML is shown instead. See
+    /// [`HtmlRenderer`] for available renderers.
+    html_renderer: HtmlRenderer,
+
+    /// Defines the resolution strategy of `cid:` references.
+    ///
+    /// This option only applies when `text/html` parts are shown as
+    /// raw HTML. See [`CidResolution`] for available strategies.
+    cid_resolution: CidResolution,
+
     #[cfg(feature = "pgp")]
     pgp: Option<Pgp>,
     #[cfg(feature = "pgp")]
@@ -168,6 +185,8 @@ fn default() -> Self {
             show_plain_texts_signature: true,
             save_attachments: Default::default(),
             save_attachments_dir: Self::default_save_attachments_dir(),
+            html_renderer: Default::default(),
+            cid_resolution: Default::default(),
             #[cfg(feature = "pgp")]
             pgp: Default::default(),
             #[cfg(feature = "pgp")]
@@ -227,6 +246,24 @@ pub fn with_save_attachments_dir(mut self, dir: impl Into<PathBuf>) -> Self {
         self
     }
 
+    pub fn set_html_renderer(&mut self, renderer: impl Into<HtmlRenderer>) {
+        self.html_renderer = renderer.into();
+    }
+
+    pub fn with_html_renderer(mut self, renderer: impl Into<HtmlRenderer>) -> Self {
+        self.set_html_renderer(renderer);
+        self
+    }
+
+    pub fn set_cid_resolution(&mut self, resolution: CidResolution) {
+        self.cid_resolution = resolution;
+    }
+
+    pub fn with_cid_resolution(mut self, resolution: CidResolution) -> Self {
+        self.set_cid_resolution(resolution);
+        self
+    }
+
     #[cfg(feature = "pgp")]
     pub fn set_pgp(&mut self, pgp: impl Into<Pgp>) {
         self.pgp = Some(pgp.into());
@@ -409,16 +446,73 @@ fn interpret_text_plain(&self, plain: &str) -> String {
         tpl
     }
 
-    fn interpret_text_html(&self, html: &str) -> String {
+    /// Resolve `cid:` references of the given HTML using the given
+    /// strategy.
+    ///
+    /// Referenced parts are looked up in the given message. When
+    /// resolving to paths, referenced parts are saved in the
+    /// attachments directory, using the same file name as inline
+    /// attachments.
+    fn resolve_cids(
+        &self,
+        msg: &Message<'_>,
+        html: &str,
+        resolution: &CidResolution,
+    ) -> Result<String> {
+        if let CidResolution::Keep = resolution {
+            return Ok(html.to_owned());
+        }
+
+        let mut res = Ok(());
+
+        let html = replace_cids(html, |cid| {
+            let Some(part) = find_part_by_cid(msg, cid) else {
+                debug!("cannot find part matching content id {cid}, skipping it");
+                return None;
+            };
+
+            match resolution {
+                CidResolution::Keep => None,
+                CidResolution::Path => {
+                    let name = part.attachment_name().unwrap_or(cid);
+
+                    let Some(name) = get_safe_file_name(name) else {
+                        warn!("invalid file name {name:?} for content id {cid}, skipping it");
+                        return None;
+                    };
+
+                    let fname = self.save_attachments_dir.join(name);
+
+                    if let Err(err) = fs::write(&fname, part.contents()) {
+                        res = Err(Error::WriteAttachmentError(err, fname));
+                        return None;
+                    }
+
+                    Some(fname.to_string_lossy().into_owned())
+                }
+                CidResolution::DataUri => {
+                    let ctype = get_ctype(part);
+                    let data = base64_encode(part.contents()).ok()?;
+                    let data = String::from_utf8_lossy(&data);
+                    Some(format!("data:{ctype};base64,{data}"))
+                }
+            }
+        });
+
+        res.map(|()| html)
+    }
+
+    async fn interpret_text_html(&self, msg: &Message<'_>, html: &str) -> Result<String> {
         let mut tpl = String::new();
 
         if self.filter_parts.contains("text/html") {
             if self.filter_parts.only("text/html") {
                 let html = html.replace('\r', "");
+                let html = self.resolve_cids(msg, &html, &self.cid_resolution)?;
                 let html = Self::escape_mml_markup(html);
                 tpl.push_str(&html);
             } else {
-                let html = html2text(&html);
+                let html = self.html_renderer.render(html).await;
                 let html = Self::escape_mml_markup(html);
 
                 if self.show_parts {
@@ -433,7 +527,7 @@ fn interpret_text_html(&self, html: &str) -> String {
             }
         }
 
-        tpl
+        Ok(tpl)
     }
 
     #[async_recursion]
@@ -442,14 +536,20 @@ async fn interpret_part(&self, msg: &Message<'_>, part: &MessagePart<'_>) -> Res
         let ctype = get_ctype(part);
 
         match &part.body {
-            PartType::Text(plain) if ctype == "text/plain" => {
-                tpl.push_str(&self.interpret_text_plain(plain));
-            }
+            PartType::Text(plain) if ctype == "text/plain" => match get_flowed_delsp(part) {
+                Some(delsp) => {
+                    let plain = flowed::decode(plain, delsp);
+                    tpl.push_str(&self.interpret_text_plain(&plain));
+                }
+                None => {
+                    tpl.push_str(&self.interpret_text_plain(plain));
+                }
+            },
             PartType::Text(text) => {
                 tpl.push_str(&self.interpret_text(&ctype, text));
             }
             PartType::Html(html) => {
-                tpl.push_str(&self.interpret_text_html(html));
+                tpl.push_str(&self.interpret_text_html(msg, html).await?);
             }
             PartType::Binary(data) => {
                 tpl.push_str(&self.interpret_attachment(&ctype, part, data)?);
@@ -467,40 +567,27 @@ async fn interpret_part(&self, msg: &Message<'_>, part: &MessagePart<'_>) -> Res
                     FilterParts::All => {
                         let part = parts
                             .clone()
-                            .find_map(|part| match &part.body {
-                                PartType::Text(plain)
-                                    if is_plain(part) && !plain.trim().is_empty() =>
-                                {
-                                    Some(Ok(self.interpret_text_plain(plain)))
-                                }
-                                _ => None,
+                            .find(|part| match &part.body {
+                                PartType::Text(plain) => is_plain(part) && !plain.trim().is_empty(),
+                                _ => false,
                             })
                             .or_else(|| {
-                                parts.clone().find_map(|part| match &part.body {
-                                    PartType::Html(html) if !html.trim().is_empty() => {
-                                        Some(Ok(self.interpret_text_html(html)))
-                                    }
-                                    _ => None,
+                                parts.clone().find(|part| match &part.body {
+                                    PartType::Html(html) => !html.trim().is_empty(),
+                                    _ => false,
                                 })
                             })
                             .or_else(|| {
-                                parts.clone().find_map(|part| {
-                                    let ctype = get_ctype(part);
-                                    match &part.body {
-                                        PartType::Text(text) if !text.trim().is_empty() => {
-                                            Some(Ok(self.interpret_text(&ctype, text)))
-                                        }
-                                        _ => None,
-                                    }
+                                parts.clone().find(|part| match &part.body {
+                                    PartType::Text(text) => !text.trim().is_empty(),
+                                    _ => false,
                                 })
-                            });
+                            })
+                            .or_else(|| parts.next());
 
                         match part {
-                            Some(part) => Some(part),
-                            None => match parts.next() {
-                                Some(part) => Some(self.interpret_part(msg, part).await),
-                                None => None,
-                            },
+                            Some(part) => Some(self.interpret_part(msg, part).await),
+                            None => None,
                         }
                     }
                     FilterParts::Only(ctype) => {
@@ -611,6 +698,30 @@ pub async fn interpret_msg_builder<'a>(&self, builder: MessageBuilder<'a>) -> Re
         let bytes = builder.write_to_vec().map_err(Error::WriteMessageError)?;
         self.interpret_bytes(&bytes).await
     }
+
+    /// Interpret the given MIME [Message] as a self-contained HTML
+    /// string.
+    ///
+    /// The first HTML body of the message is used, or the first
+    /// plain text body converted to HTML. The `cid:` references are
+    /// replaced by data URIs, so that the HTML can be saved to a
+    /// file and viewed in a browser.
+    pub async fn interpret_msg_as_html<'a>(&self, msg: &Message<'a>) -> Result<String> {
+        let html = msg.body_html(0).unwrap_or_default();
+        self.resolve_cids(msg, &html, &CidResolution::DataUri)
+    }
+
+    /// Interpret the given MIME message bytes as a self-contained
+    /// HTML string.
+    pub async fn interpret_bytes_as_html<'a>(
+        &self,
+        bytes: impl AsRef<[u8]> + 'a,
+    ) -> Result<String> {
+        let msg = MessageParser::new()
+            .parse(bytes.as_ref())
+            .ok_or(Error::ParseMimeMessageError)?;
+        self.interpret_msg_as_html(&msg).await
+    }
 }
 
 fn get_ctype(part: &MessagePart) -> String {
@@ -627,12 +738,60 @@ fn is_plain(part: &MessagePart) -> bool {
     get_ctype(part) == "text/plain"
 }
 
+/// Find the part of the given message matching the given Content-ID.
+fn find_part_by_cid<'a, 'x>(msg: &'a Message<'x>, cid: &str) -> Option<&'a MessagePart<'x>> {
+    msg.parts.iter().find(|part| {
+        part.content_id()
+            .map(|id| id.trim_start_matches('<').trim_end_matches('>'))
+            .is_some_and(|id| id == cid)
+    })
+}
+
+/// Return the file name component of the given untrusted name.
+///
+/// Names coming from messages cannot be trusted: absolute paths and
+/// paths containing `..` are rejected, so that files are never
+/// written outside of the attachments directory.
+fn get_safe_file_name(name: &str) -> Option<&str> {
+    let path = Path::new(name);
+
+    let unsafe_path = path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+
+    if unsafe_path {
+        return None;
+    }
+
+    path.file_name()?.to_str()
+}
+
+/// Return the `DelSp` parameter of the given part if it is
+/// `format=flowed`, otherwise return `None`.
+fn get_flowed_delsp(part: &MessagePart) -> Option<bool> {
+    let ctype = part.content_type()?;
+
+    let flowed = ctype
+        .attribute("format")
+        .is_some_and(|format| format.eq_ignore_ascii_case("flowed"));
+
+    if !flowed {
+        return None;
+    }
+
+    let delsp = ctype
+        .attribute("delsp")
+        .is_some_and(|delsp| delsp.eq_ignore_ascii_case("yes"));
+
+    Some(delsp)
+}
+
 #[cfg(test)]
 mod tests {
     use concat_with::concat_line;
-    use mail_builder::{mime::MimePart, MessageBuilder};
+    use mail_builder::{headers::content_type::ContentType, mime::MimePart, MessageBuilder};
 
-    use super::{FilterParts, MimeBodyInterpreter};
+    use super::{get_safe_file_name, CidResolution, FilterParts, MimeBodyInterpreter};
 
     #[tokio::test]
     async fn nested_multiparts() {
@@ -1012,4 +1171,137 @@ async fn hide_parts_multipart_mixed() {
 
         assert_eq!(tpl, expected_tpl);
     }
+
+    #[tokio::test]
+    async fn plain_format_flowed() {
+        let ctype = ContentType::new("text/plain")
+            .attribute("format", "flowed")
+            .attribute("delsp", "yes");
+        let builder = MessageBuilder::new().body(MimePart::new(
+            ctype,
+            concat_line!(
+                "> Hello, this is a quo ",
+                "> ted paragraph.",
+                "",
+                "And this is a para ",
+                "graph with a space-stuffed",
+                " From line.",
+                "-- ",
+                "signature",
+            ),
+        ));
+
+        let tpl = MimeBodyInterpreter::new()
+            .interpret_msg_builder(builder)
+            .await
+            .unwrap();
+
+        let expected_tpl = concat_line!(
+            "> Hello, this is a quoted paragraph.",
+            "",
+            "And this is a paragraph with a space-stuffed",
+            "From line.",
+            "-- ",
+            "signature",
+            "",
+        );
+
+        assert_eq!(tpl, expected_tpl);
+    }
+
+    fn related_builder() -> MessageBuilder<'static> {
+        MessageBuilder::new().body(MimePart::new(
+            "multipart/related",
+            vec![
+                MimePart::new("text/html", "<p>Hello</p><img src=\"cid:logo@localhost\">"),
+                MimePart::new("image/png", b"PNG".to_vec())
+                    .inline()
+                    .cid("logo@localhost"),
+            ],
+        ))
+    }
+
+    #[tokio::test]
+    async fn html_cid_resolution_data_uri() {
+        let tpl = MimeBodyInterpreter::new()
+            .with_filter_parts(FilterParts::Only("text/html".into()))
+            .with_cid_resolution(CidResolution::DataUri)
+            .interpret_msg_builder(related_builder())
+            .await
+            .unwrap();
+
+        assert_eq!(tpl, "<p>Hello</p><img src=\"data:image/png;base64,UE5H\">");
+    }
+
+    #[tokio::test]
+    async fn html_cid_resolution_path() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let tpl = MimeBodyInterpreter::new()
+            .with_filter_parts(FilterParts::Only("text/html".into()))
+            .with_cid_resolution(CidResolution::Path)
+            .with_save_attachments_dir(dir.path())
+            .interpret_msg_builder(related_builder())
+            .await
+            .unwrap();
+
+        let path = dir.path().join("logo@localhost");
+
+        assert_eq!(
+            tpl,
+            format!("<p>Hello</p><img src=\"{}\">", path.to_string_lossy())
+        );
+        assert_eq!(std::fs::read(path).unwrap(), b"PNG");
+    }
+
+    #[tokio::test]
+    async fn html_cid_resolution_path_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let attachments_dir = dir.path().join("attachments");
+        std::fs::create_dir(&attachments_dir).unwrap();
+
+        let builder = MessageBuilder::new().body(MimePart::new(
+            "multipart/related",
+            vec![
+                MimePart::new("text/html", "<img src=\"cid:../evil\">"),
+                MimePart::new("image/png", b"PNG".to_vec())
+                    .inline()
+                    .cid("../evil"),
+            ],
+        ));
+
+        let tpl = MimeBodyInterpreter::new()
+            .with_filter_parts(FilterParts::Only("text/html".into()))
+            .with_cid_resolution(CidResolution::Path)
+            .with_save_attachments_dir(&attachments_dir)
+            .interpret_msg_builder(builder)
+            .await
+            .unwrap();
+
+        assert_eq!(tpl, "<img src=\"cid:../evil\">");
+        assert!(!dir.path().join("evil").exists());
+    }
+
+    #[test]
+    fn safe_file_name() {
+        assert_eq!(get_safe_file_name("logo.png"), Some("logo.png"));
+        assert_eq!(get_safe_file_name("./logo.png"), Some("logo.png"));
+        assert_eq!(get_safe_file_name("dir/logo.png"), Some("logo.png"));
+        assert_eq!(get_safe_file_name("../logo.png"), None);
+        assert_eq!(get_safe_file_name("dir/../../logo.png"), None);
+        assert_eq!(get_safe_file_name("/etc/passwd"), None);
+        assert_eq!(get_safe_file_name(""), None);
+    }
+
+    #[tokio::test]
+    async fn self_contained_html() {
+        let bytes = related_builder().write_to_vec().unwrap();
+
+        let html = MimeBodyInterpreter::new()
+            .interpret_bytes_as_html(&bytes)
+            .await
+            .unwrap();
+
+        assert_eq!(html, "<p>Hello</p><img src=\"data:image/png;base64,UE5H\">");
+    }
 }
@@ -8,15 +8,21 @@
 
 #[cfg(feature = "compiler")]
 pub mod compiler;
+pub mod flowed;
+#[cfg(feature = "interpreter")]
+pub mod html;
 #[cfg(feature = "interpreter")]
 pub mod interpreter;
 
 #[cfg(feature = "compiler")]
 #[doc(inline)]
-pub use self::compiler::MmlBodyCompiler;
+pub use self::compiler::{Diagnostic, MmlBodyCompiler, Severity};
 #[cfg(feature = "interpreter")]
 #[doc(inline)]
-pub use self::interpreter::{FilterParts, MimeBodyInterpreter};
+pub use self::{
+    html::{CidResolution, HtmlRenderer},
+    interpreter::{FilterParts, MimeBodyInterpreter},
+};
 
 pub(crate) const PART_BEGIN: &str = "<#part";
 pub(crate) const PART_BEGIN_ESCAPED: &str = "<#!part";
@@ -59,6 +65,8 @@
 #[cfg(feature = "pgp")]
 pub(crate) const SIGN: &str = "sign";
 pub(crate) const SIZE: &str = "size";
+#[cfg(feature = "markdown")]
+pub(crate) const TEXT_MARKDOWN: &str = "text/markdown";
 pub(crate) const TYPE: &str = "type";
 
 pub(crate) const BACKSLASH: char = '\\';
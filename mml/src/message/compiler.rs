@@ -25,6 +25,17 @@ pub fn new() -> Self {
         Self::default()
     }
 
+    /// Compile `text/plain` parts as `format=flowed`.
+    pub fn set_format_flowed(&mut self, flowed: bool) {
+        self.mml_body_compiler.set_format_flowed(flowed);
+    }
+
+    /// Compile `text/plain` parts as `format=flowed`.
+    pub fn with_format_flowed(mut self, flowed: bool) -> Self {
+        self.mml_body_compiler.set_format_flowed(flowed);
+        self
+    }
+
     /// Customize PGP.
     #[cfg(feature = "pgp")]
     pub fn set_pgp(&mut self, pgp: impl Into<Pgp>) {
@@ -86,17 +97,32 @@ impl MmlCompiler<'_> {
     /// The fact to return a intermediate structure allows users to
     /// customize the final form of the desired MIME message.
     pub async fn compile(&self) -> Result<MmlCompileResult<'_>> {
-        let mml_body = self
+        let mml_body_part = self
             .mml_msg
             .text_bodies()
             .next()
-            .ok_or(Error::ParseMmlEmptyBodyError)?
+            .ok_or(Error::ParseMmlEmptyBodyError)?;
+        let mml_body = mml_body_part
             .text_contents()
             .ok_or(Error::ParseMmlEmptyBodyContentError)?;
 
         let mml_body_compiler = &self.mml_body_compiler;
 
-        let mut mime_msg_builder = mml_body_compiler.compile(mml_body).await?;
+        let mut mime_msg_builder = match mml_body_compiler.compile(mml_body).await {
+            Ok(builder) => builder,
+            Err(Error::ParseMmlError(diagnostics, mml_body)) => {
+                // diagnostics are located in the body, they need to
+                // be located in the whole message instead
+                let mml_msg = String::from_utf8_lossy(self.mml_msg.raw_message());
+                let offset = mml_body_part.raw_body_offset();
+                let diagnostics = diagnostics
+                    .into_iter()
+                    .map(|diagnostic| diagnostic.shift(&mml_msg, offset))
+                    .collect();
+                return Err(Error::ParseMmlError(diagnostics, mml_body));
+            }
+            Err(err) => return Err(err),
+        };
 
         mime_msg_builder = mime_msg_builder.header("MIME-Version", Text::new("1.0"));
 
@@ -9,7 +9,7 @@
 #[cfg(feature = "pgp")]
 use crate::pgp::Pgp;
 use crate::{
-    message::{FilterParts, MimeBodyInterpreter},
+    message::{CidResolution, FilterParts, HtmlRenderer, MimeBodyInterpreter},
     Error, Result,
 };
 
@@ -188,6 +188,25 @@ pub fn with_save_some_attachments_dir(self, dir: Option<impl Into<PathBuf>>) ->
         }
     }
 
+    /// Customize the renderer of HTML parts.
+    ///
+    /// The renderer is used to display `text/html` parts as plain
+    /// text, for example when a `multipart/alternative` has no
+    /// `text/plain` part.
+    pub fn with_html_renderer(mut self, renderer: impl Into<HtmlRenderer>) -> Self {
+        self.mime_body_interpreter = self.mime_body_interpreter.with_html_renderer(renderer);
+        self
+    }
+
+    /// Customize the resolution of `cid:` references.
+    ///
+    /// This only applies when `text/html` parts are shown as raw
+    /// HTML, for example using `FilterParts::Only("text/html")`.
+    pub fn with_cid_resolution(mut self, resolution: CidResolution) -> Self {
+        self.mime_body_interpreter = self.mime_body_interpreter.with_cid_resolution(resolution);
+        self
+    }
+
     /// Customize PGP.
     #[cfg(feature = "pgp")]
     pub fn set_pgp(&mut self, pgp: impl Into<Pgp>) {
@@ -293,6 +312,23 @@ pub async fn from_msg_builder(self, builder: MessageBuilder<'_>) -> Result<Strin
         let bytes = builder.write_to_vec().map_err(Error::BuildEmailError)?;
         self.from_bytes(&bytes).await
     }
+
+    /// Interpret the given MIME [Message] as a self-contained HTML
+    /// [String], where `cid:` references are replaced by data URIs.
+    ///
+    /// Headers are not part of the HTML.
+    pub async fn from_msg_as_html(self, msg: &Message<'_>) -> Result<String> {
+        self.mime_body_interpreter.interpret_msg_as_html(msg).await
+    }
+
+    /// Interpret the given MIME message bytes as a self-contained
+    /// HTML [String].
+    pub async fn from_bytes_as_html(self, bytes: impl AsRef<[u8]>) -> Result<String> {
+        let msg = MessageParser::new()
+            .parse(bytes.as_ref())
+            .ok_or(Error::ParseRawEmailError)?;
+        self.from_msg_as_html(&msg).await
+    }
 }
 
 #[cfg(test)]
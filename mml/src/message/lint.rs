@@ -0,0 +1,234 @@
+//! # MML message linting module
+//!
+//! Module dedicated to MML message linting. The linter reports
+//! problems of a MML message without compiling it, which makes it
+//! cheap enough to be run while the message is being edited.
+
+use std::fs;
+
+use mail_parser::MessageParser;
+use shellexpand_utils::shellexpand_path;
+
+#[cfg(feature = "pgp")]
+use crate::message::body::ENCRYPT;
+use crate::message::body::{
+    compiler::diagnostics::{lint_body, scan_tags},
+    Diagnostic, FILENAME,
+};
+
+#[cfg(feature = "pgp")]
+use super::header;
+
+/// The default size threshold of attachments, in bytes.
+pub const DEFAULT_ATTACHMENT_SIZE_THRESHOLD: u64 = 10 * 1024 * 1024;
+
+/// MML message linter.
+///
+/// The linter follows the builder pattern, where the build function
+/// is named `lint`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MmlLinter {
+    /// Attachments bigger than this size, in bytes, are reported.
+    ///
+    /// Most email providers reject messages bigger than a few dozen
+    /// megabytes, knowing that attachments grow by a third once
+    /// encoded in base64.
+    attachment_size_threshold: u64,
+}
+
+impl Default for MmlLinter {
+    fn default() -> Self {
+        Self {
+            attachment_size_threshold: DEFAULT_ATTACHMENT_SIZE_THRESHOLD,
+        }
+    }
+}
+
+impl MmlLinter {
+    /// Create a new linter with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_attachment_size_threshold(&mut self, threshold: u64) {
+        self.attachment_size_threshold = threshold;
+    }
+
+    pub fn with_attachment_size_threshold(mut self, threshold: u64) -> Self {
+        self.set_attachment_size_threshold(threshold);
+        self
+    }
+
+    /// Lint the given MML message.
+    ///
+    /// Diagnostics are located in the given message and sorted by
+    /// position. The message can be compiled if none of them is an
+    /// error.
+    pub fn lint(&self, mml_msg: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let Some(msg) = MessageParser::new().parse(mml_msg.as_bytes()) else {
+            diagnostics.push(Diagnostic::error("cannot parse MML message", mml_msg, 0..0));
+            return diagnostics;
+        };
+
+        let has_subject = msg
+            .subject()
+            .is_some_and(|subject| !subject.trim().is_empty());
+
+        if !has_subject {
+            diagnostics.push(
+                Diagnostic::warning("missing Subject header", mml_msg, 0..0)
+                    .with_suggestion("add a `Subject: …` header"),
+            );
+        }
+
+        let Some(body_part) = msg.text_bodies().next() else {
+            return diagnostics;
+        };
+
+        let Some(body) = body_part.text_contents() else {
+            return diagnostics;
+        };
+
+        let tags = scan_tags(body);
+        let mut body_diagnostics = lint_body(body);
+
+        for tag in &tags {
+            if let Some(prop) = tag.get(FILENAME) {
+                let path = shellexpand_path(prop.val.unwrap_or_default());
+
+                match fs::metadata(&path) {
+                    Err(_) => body_diagnostics.push(
+                        Diagnostic::error(
+                            format!("cannot find attachment at {path:?}"),
+                            body,
+                            prop.val_span.clone(),
+                        )
+                        .with_suggestion("check the path of the file"),
+                    ),
+                    Ok(metadata) if metadata.len() > self.attachment_size_threshold => {
+                        body_diagnostics.push(
+                            Diagnostic::warning(
+                                format!(
+                                    "attachment of {} bytes exceeds the threshold of {} bytes",
+                                    metadata.len(),
+                                    self.attachment_size_threshold
+                                ),
+                                body,
+                                prop.val_span.clone(),
+                            )
+                            .with_suggestion("share a link to the file instead"),
+                        )
+                    }
+                    Ok(_) => (),
+                }
+            }
+
+            #[cfg(feature = "pgp")]
+            if let Some(prop) = tag.get(ENCRYPT) {
+                if header::extract_emails(msg.to()).is_empty() {
+                    body_diagnostics.push(
+                        Diagnostic::warning(
+                            "encrypted part without recipients",
+                            body,
+                            prop.key_span.clone(),
+                        )
+                        .with_suggestion("add recipients to the `To` header"),
+                    );
+                }
+            }
+        }
+
+        let offset = body_part.raw_body_offset();
+        diagnostics.extend(
+            body_diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.shift(mml_msg, offset)),
+        );
+
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use concat_with::concat_line;
+    use std::io::Write;
+    use tempfile::Builder;
+
+    use crate::message::{MmlLinter, Severity};
+
+    #[test]
+    fn valid() {
+        let mml = concat_line!(
+            "From: from@localhost",
+            "To: to@localhost",
+            "Subject: subject",
+            "",
+            "<#part type=text/html>",
+            "<h1>Hello, world!</h1>",
+            "<#/part>",
+        );
+
+        assert_eq!(MmlLinter::new().lint(mml), vec![]);
+    }
+
+    #[test]
+    fn missing_subject() {
+        let mml = concat_line!("From: from@localhost", "", "Hello, world!");
+        let diagnostics = MmlLinter::new().lint(mml);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].message, "missing Subject header");
+    }
+
+    #[test]
+    fn unknown_prop() {
+        let mml = concat_line!(
+            "Subject: subject",
+            "",
+            "<#part typ=text/html>",
+            "<h1>Hello, world!</h1>",
+        );
+
+        let diagnostics = MmlLinter::new().lint(mml);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 8));
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("did you mean `type`?")
+        );
+    }
+
+    #[test]
+    fn attachments() {
+        let mut attachment = Builder::new().suffix(".txt").tempfile().unwrap();
+        write!(attachment, "Hello, world!").unwrap();
+        let path = attachment.path().to_string_lossy();
+
+        let mml = format!(
+            "Subject: subject\n\n<#part filename={path}><#/part>\n<#part filename=/missing.txt><#/part>\n"
+        );
+
+        let diagnostics = MmlLinter::new()
+            .with_attachment_size_threshold(4)
+            .lint(&mml);
+
+        assert_eq!(diagnostics.len(), 2);
+
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].message,
+            "attachment of 13 bytes exceeds the threshold of 4 bytes"
+        );
+        assert_eq!(&mml[diagnostics[0].span.clone()], path);
+
+        assert_eq!(diagnostics[1].severity, Severity::Error);
+        assert_eq!(diagnostics[1].line, 4);
+    }
+}
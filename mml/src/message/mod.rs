@@ -7,6 +7,11 @@
 //! A MML message/body can be compiled into a MIME message/body using
 //! the [MmlCompilerBuilder]/[MmlBodyCompiler] builders.
 //!
+//! ## Linting
+//!
+//! A MML message can be checked without being compiled using the
+//! [MmlLinter] builder.
+//!
 //! ## Interpretation
 //!
 //! A MIME message/body can be interpreted as a MML message/body using
@@ -18,16 +23,19 @@
 pub(crate) mod header;
 #[cfg(feature = "interpreter")]
 pub mod interpreter;
-
 #[cfg(feature = "compiler")]
+pub mod lint;
+
+#[cfg(feature = "interpreter")]
 #[doc(inline)]
 pub use self::{
-    body::MmlBodyCompiler,
-    compiler::{MmlCompileResult, MmlCompiler, MmlCompilerBuilder},
+    body::{CidResolution, FilterParts, HtmlRenderer, MimeBodyInterpreter},
+    interpreter::{FilterHeaders, MimeInterpreter, MimeInterpreterBuilder},
 };
-#[cfg(feature = "interpreter")]
+#[cfg(feature = "compiler")]
 #[doc(inline)]
 pub use self::{
-    body::{FilterParts, MimeBodyInterpreter},
-    interpreter::{FilterHeaders, MimeInterpreter, MimeInterpreterBuilder},
+    body::{Diagnostic, MmlBodyCompiler, Severity},
+    compiler::{MmlCompileResult, MmlCompiler, MmlCompilerBuilder},
+    lint::MmlLinter,
 };
@@ -4,6 +4,7 @@
 //! struct, and various implementations of transformation.
 
 use std::{
+    io::ErrorKind,
     ops::{Deref, DerefMut},
     process::Stdio,
 };
@@ -34,6 +35,10 @@ pub struct Command {
     /// Defaults to `true`.
     #[cfg_attr(feature = "derive", serde(skip))]
     piped: bool,
+
+    /// The environment variables given to the command.
+    #[cfg_attr(feature = "derive", serde(skip))]
+    envs: Vec<(String, String)>,
 }
 
 impl Command {
@@ -45,6 +50,7 @@ pub fn new(cmd: impl ToString) -> Self {
         Self {
             inner: cmd.to_string(),
             piped: true,
+            envs: Vec::new(),
         }
     }
 
@@ -65,6 +71,34 @@ pub fn with_output_piped(mut self, piped: bool) -> Self {
         self
     }
 
+    /// Defines an environment variable given to the command.
+    ///
+    /// See [`Command::with_env`] for the builder pattern alternative.
+    pub fn set_env(&mut self, key: impl ToString, val: impl ToString) {
+        self.envs.push((key.to_string(), val.to_string()));
+    }
+
+    /// Defines an environment variable given to the command, using
+    /// the builder pattern.
+    ///
+    /// See [`Command::set_env`] for the setter alternative.
+    pub fn with_env(mut self, key: impl ToString, val: impl ToString) -> Self {
+        self.set_env(key, val);
+        self
+    }
+
+    /// Defines environment variables given to the command, using the
+    /// builder pattern.
+    pub fn with_envs<K: ToString, V: ToString>(
+        mut self,
+        envs: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        for (key, val) in envs {
+            self.set_env(key, val);
+        }
+        self
+    }
+
     /// Wrapper around [`alloc::str::replace`].
     ///
     /// This function is particularly useful when you need to replace
@@ -102,6 +136,7 @@ pub async fn run_with(&self, input: impl AsRef<[u8]>) -> Result<Output> {
 
         let mut cmd = new_async_command()
             .arg(&self.inner)
+            .envs(self.envs.iter().map(|(key, val)| (key, val)))
             .stdin(stdin)
             .stdout(if self.piped {
                 debug!("stdout piped");
@@ -120,11 +155,16 @@ pub async fn run_with(&self, input: impl AsRef<[u8]>) -> Result<Output> {
             .spawn()?;
 
         if !input.is_empty() {
-            cmd.stdin
-                .as_mut()
-                .ok_or(Error::GetStdinError)?
-                .write_all(input)
-                .await?;
+            let stdin = cmd.stdin.as_mut().ok_or(Error::GetStdinError)?;
+
+            // commands are free not to read their standard input, in
+            // which case they may exit before the input is written
+            match stdin.write_all(input).await {
+                Err(err) if err.kind() == ErrorKind::BrokenPipe => {
+                    debug!("command exited before reading its whole input");
+                }
+                res => res?,
+            }
         }
 
         #[cfg(feature = "async-std")]
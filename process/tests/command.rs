@@ -19,3 +19,17 @@ async fn test_command() {
         err => panic!("unexpected error: {err:?}"),
     }
 }
+
+#[test_log::test(test)]
+async fn test_command_with_env() {
+    let cmd = Command::new("echo $GREETING").with_env("GREETING", "hello, world!");
+    let out = cmd.run().await.unwrap().to_string_lossy();
+    assert_eq!(out, "hello, world!\n");
+}
+
+#[test_log::test(test)]
+async fn test_command_without_reading_input() {
+    let input = vec![b'a'; 1024 * 1024];
+    let out = Command::new("true").run_with(input).await.unwrap();
+    assert!(out.is_empty());
+}